@@ -2,23 +2,27 @@ use super::{
   constants::TEXTURE_ROLES,
   helpers::{
     authlib_injector::{
-      self,
+      self, diagnosis,
       info::{fetch_auth_server_info, fetch_auth_url, get_auth_server_info_by_url},
       jar::check_authlib_jar,
     },
-    microsoft, offline,
+    maintenance, microsoft, offline,
+    skin::validate_skin_image,
   },
   models::{
-    AccountError, AccountInfo, AuthServer, OAuthCodeResponse, Player, PlayerInfo, PlayerType,
+    AccountError, AccountInfo, AuthServer, AuthServerDiagnosis, CapeInfo, OAuthCodeResponse,
+    Player, PlayerInfo, PlayerType, Texture,
   },
 };
 use crate::{
   account::helpers::misc, error::SJMCLResult, launcher_config::models::LauncherConfig,
-  storage::Storage,
+  storage::Storage, utils::image::decode_image,
 };
+use std::fs;
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
 use url::Url;
+use uuid::Uuid;
 
 #[tauri::command]
 pub fn retrieve_player_list(app: AppHandle) -> SJMCLResult<Vec<Player>> {
@@ -294,12 +298,25 @@ pub async fn relogin_player_3rdparty_password(
   )
   .await?;
 
-  let new_player = player_list
-    .into_iter()
+  let refreshed_player = match player_list
+    .iter()
     .find(|player| player.uuid == old_player.uuid)
-    .ok_or(AccountError::NotFound)?;
-
-  let refreshed_player = authlib_injector::password::refresh(&app, &new_player).await?;
+  {
+    Some(new_player) => authlib_injector::password::refresh(&app, new_player).await?,
+    None => {
+      // only the selected profile is returned if the account has switched to another one
+      let account_player = player_list
+        .iter()
+        .find(|player| {
+          player
+            .available_profiles
+            .iter()
+            .any(|profile| profile.uuid == old_player.uuid)
+        })
+        .ok_or(AccountError::NotFound)?;
+      authlib_injector::password::switch_profile(&app, account_player, old_player.uuid).await?
+    }
+  };
 
   {
     let mut account_state = account_binding.lock()?;
@@ -351,6 +368,38 @@ pub async fn add_player_from_selection(app: AppHandle, player: Player) -> SJMCLR
   misc::check_full_login_availability(&app).await
 }
 
+#[tauri::command]
+pub async fn switch_player_profile(
+  app: AppHandle,
+  player_id: String,
+  profile_uuid: Uuid,
+) -> SJMCLResult<()> {
+  let player = get_player_info_by_id(&app, &player_id)?;
+  // only password logins are bound to a profile by refreshing
+  if player.player_type != PlayerType::ThirdParty || player.refresh_token.is_some() {
+    return Err(AccountError::Invalid.into());
+  }
+  if player.uuid == profile_uuid {
+    return Ok(());
+  }
+
+  {
+    let account_binding = app.state::<Mutex<AccountInfo>>();
+    let account_state = account_binding.lock()?;
+    if account_state.players.iter().any(|other| {
+      other.uuid == profile_uuid
+        && other.player_type == PlayerType::ThirdParty
+        && other.auth_server_url == player.auth_server_url
+    }) {
+      return Err(AccountError::Duplicate.into());
+    }
+  }
+
+  let switched_player =
+    authlib_injector::password::switch_profile(&app, &player, profile_uuid).await?;
+  maintenance::save_refreshed_player(&app, &player_id, switched_player)
+}
+
 #[tauri::command]
 pub fn update_player_skin_offline_preset(
   app: AppHandle,
@@ -378,6 +427,99 @@ pub fn update_player_skin_offline_preset(
   Ok(())
 }
 
+fn get_player_info_by_id(app: &AppHandle, player_id: &str) -> SJMCLResult<PlayerInfo> {
+  let account_binding = app.state::<Mutex<AccountInfo>>();
+  let account_state = account_binding.lock()?;
+  let player = account_state
+    .players
+    .iter()
+    .find(|player| player.id == player_id)
+    .ok_or(AccountError::NotFound)?;
+  Ok(player.clone())
+}
+
+fn save_player_textures(
+  app: &AppHandle,
+  player_id: &str,
+  textures: Vec<Texture>,
+) -> SJMCLResult<()> {
+  let account_binding = app.state::<Mutex<AccountInfo>>();
+  let mut account_state = account_binding.lock()?;
+  let player = account_state
+    .get_player_by_id_mut(player_id.to_string())
+    .ok_or(AccountError::NotFound)?;
+  player.textures = textures;
+  account_state.save()?;
+  Ok(())
+}
+
+/// Uploads a png skin, or saves it locally for offline players.
+/// `model` is `default` (classic) or `slim`.
+#[tauri::command]
+pub async fn upload_player_skin(
+  app: AppHandle,
+  player_id: String,
+  file_path: String,
+  model: String,
+) -> SJMCLResult<()> {
+  let player = get_player_info_by_id(&app, &player_id)?;
+  let png_bytes = fs::read(&file_path).map_err(|_| AccountError::TextureError)?;
+  let img = decode_image(png_bytes.clone()).map_err(|_| AccountError::TextureError)?;
+  validate_skin_image(&img)?;
+
+  let textures = match player.player_type {
+    PlayerType::Offline => offline::load_custom_skin(img, &model)?,
+    PlayerType::Microsoft => {
+      microsoft::profile::upload_skin(&app, &player, png_bytes, &model).await?
+    }
+    PlayerType::ThirdParty => {
+      authlib_injector::texture::upload_texture(&app, &player, "SKIN", png_bytes, &model).await?
+    }
+  };
+  save_player_textures(&app, &player_id, textures)
+}
+
+/// Resets the skin to the default one of the account, or to Steve for offline players.
+#[tauri::command]
+pub async fn reset_player_skin(app: AppHandle, player_id: String) -> SJMCLResult<()> {
+  let player = get_player_info_by_id(&app, &player_id)?;
+  let textures = match player.player_type {
+    PlayerType::Offline => offline::load_preset_skin(&app, "steve".to_string())?,
+    PlayerType::Microsoft => microsoft::profile::reset_skin(&app, &player).await?,
+    PlayerType::ThirdParty => {
+      authlib_injector::texture::reset_texture(&app, &player, "SKIN").await?
+    }
+  };
+  save_player_textures(&app, &player_id, textures)
+}
+
+#[tauri::command]
+pub async fn retrieve_player_capes(
+  app: AppHandle,
+  player_id: String,
+) -> SJMCLResult<Vec<CapeInfo>> {
+  let player = get_player_info_by_id(&app, &player_id)?;
+  if player.player_type != PlayerType::Microsoft {
+    return Err(AccountError::Invalid.into());
+  }
+  microsoft::profile::fetch_capes(&app, &player).await
+}
+
+/// Shows the cape of `cape_id`, or hides the cape if `None`.
+#[tauri::command]
+pub async fn update_player_cape(
+  app: AppHandle,
+  player_id: String,
+  cape_id: Option<String>,
+) -> SJMCLResult<()> {
+  let player = get_player_info_by_id(&app, &player_id)?;
+  if player.player_type != PlayerType::Microsoft {
+    return Err(AccountError::Invalid.into());
+  }
+  let textures = microsoft::profile::set_active_cape(&app, &player, cape_id).await?;
+  save_player_textures(&app, &player_id, textures)
+}
+
 #[tauri::command]
 pub async fn delete_player(app: AppHandle, player_id: String) -> SJMCLResult<()> {
   {
@@ -416,44 +558,7 @@ pub async fn delete_player(app: AppHandle, player_id: String) -> SJMCLResult<()>
 
 #[tauri::command]
 pub async fn refresh_player(app: AppHandle, player_id: String) -> SJMCLResult<()> {
-  let account_binding = app.state::<Mutex<AccountInfo>>();
-
-  let cloned_account_state = account_binding.lock()?.clone();
-
-  let player = cloned_account_state
-    .players
-    .iter()
-    .find(|player| player.id == player_id)
-    .ok_or(AccountError::NotFound)?;
-
-  let refreshed_player = match player.player_type {
-    PlayerType::ThirdParty => {
-      let auth_server = AuthServer::from(get_auth_server_info_by_url(
-        &app,
-        player.auth_server_url.clone().unwrap_or_default(),
-      )?);
-
-      authlib_injector::common::refresh(&app, player, &auth_server).await?
-    }
-
-    PlayerType::Microsoft => microsoft::oauth::refresh(&app, player).await?,
-
-    PlayerType::Offline => {
-      return Err(AccountError::Invalid.into());
-    }
-  };
-
-  let mut account_state = account_binding.lock()?;
-
-  if let Some(player) = account_state
-    .players
-    .iter_mut()
-    .find(|player| player.id == player_id)
-  {
-    *player = refreshed_player;
-    account_state.save()?;
-  }
-
+  maintenance::refresh_and_save_player(&app, &player_id).await?;
   Ok(())
 }
 
@@ -488,6 +593,21 @@ pub async fn fetch_auth_server(app: AppHandle, url: String) -> SJMCLResult<AuthS
   ))
 }
 
+#[tauri::command]
+pub async fn diagnose_auth_server(
+  app: AppHandle,
+  url: String,
+  username: Option<String>,
+  password: Option<String>,
+) -> SJMCLResult<AuthServerDiagnosis> {
+  let parsed_url = Url::parse(&url)
+    .or(Url::parse(&format!("https://{}", url)))
+    .map_err(|_| AccountError::Invalid)?;
+  let test_account = username.zip(password);
+
+  Ok(diagnosis::diagnose_auth_server(&app, parsed_url, test_account).await)
+}
+
 #[tauri::command]
 pub async fn add_auth_server(app: AppHandle, auth_url: String) -> SJMCLResult<()> {
   if get_auth_server_info_by_url(&app, auth_url.clone()).is_ok() {
@@ -1,3 +1,9 @@
 pub const ACCOUNTS_FILE_NAME: &str = "sjmcl.account.json";
+pub const ACCOUNTS_SCHEMA_VERSION: u32 = 1;
 
 pub const TEXTURE_ROLES: [&str; 2] = ["steve", "alex"];
+
+pub const LOCAL_YGGDRASIL_KEY_FILE_NAME: &str = "sjmcl.local-yggdrasil.pem";
+pub const LOCAL_YGGDRASIL_SERVER_NAME: &str = "SJMCL Offline";
+
+pub const PLAYER_AUTH_STATE_EVENT: &str = "account:player-auth-state-update";
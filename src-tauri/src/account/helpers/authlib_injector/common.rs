@@ -6,7 +6,7 @@ use crate::{
       misc::fetch_image,
       offline::load_preset_skin,
     },
-    models::{AccountError, AuthServer, PlayerInfo, PlayerType, Texture},
+    models::{AccountError, AuthServer, PlayerAuthState, PlayerInfo, PlayerType, Texture},
   },
   error::SJMCLResult,
 };
@@ -78,6 +78,8 @@ pub async fn parse_profile(
       textures,
       password,
       auth_server_url,
+      auth_state: PlayerAuthState::Valid,
+      available_profiles: Vec::new(),
     }
     .with_generated_id(),
   )
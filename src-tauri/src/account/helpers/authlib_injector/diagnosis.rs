@@ -0,0 +1,500 @@
+//! Compatibility checks of a Yggdrasil server against what the launcher and authlib-injector need.
+//!
+//! ref: https://github.com/yushijinhun/authlib-injector/wiki/Yggdrasil-%E6%9C%8D%E5%8A%A1%E7%AB%AF%E6%8A%80%E6%9C%AF%E8%A7%84%E8%8C%83
+
+use super::{
+  info::get_client_id,
+  models::{MinecraftProfile, TextureInfo},
+};
+use crate::account::models::{
+  AuthServerCheck, AuthServerCheckResult, AuthServerCheckStatus, AuthServerDiagnosis,
+};
+use base64::{engine::general_purpose, Engine};
+use rsa::{pkcs8::DecodePublicKey, RsaPublicKey};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_http::reqwest;
+use url::Url;
+use uuid::Uuid;
+
+type CheckOutcome = Result<String, String>;
+
+struct Diagnosis {
+  checks: Vec<AuthServerCheckResult>,
+}
+
+impl Diagnosis {
+  fn record(&mut self, check: AuthServerCheck, outcome: CheckOutcome) {
+    let (status, message) = match outcome {
+      Ok(message) => (AuthServerCheckStatus::Passed, message),
+      Err(message) => (AuthServerCheckStatus::Failed, message),
+    };
+    self.checks.push(AuthServerCheckResult {
+      check,
+      status,
+      message,
+    });
+  }
+
+  fn skip(&mut self, check: AuthServerCheck, message: &str) {
+    self.checks.push(AuthServerCheckResult {
+      check,
+      status: AuthServerCheckStatus::Skipped,
+      message: message.to_string(),
+    });
+  }
+}
+
+/// The session of the test account, shared by the checks after `authenticate`.
+struct TestSession {
+  access_token: String,
+  client_token: String,
+  selected_profile: Option<Value>,
+  is_profile_bound: bool,
+}
+
+async fn post_json(
+  client: &reqwest::Client,
+  url: String,
+  body: Value,
+) -> Result<reqwest::Response, String> {
+  client
+    .post(url)
+    .header("Content-Type", "application/json")
+    .body(body.to_string())
+    .send()
+    .await
+    .map_err(|e| format!("request failed: {}", e))
+}
+
+async fn describe_error(response: reqwest::Response) -> String {
+  let status = response.status();
+  let body = response.json::<Value>().await.unwrap_or_default();
+  match body["errorMessage"].as_str() {
+    Some(message) => format!("HTTP {}: {}", status, message),
+    None => format!("HTTP {}", status),
+  }
+}
+
+async fn check_ali_redirection(client: &reqwest::Client, root: &Url) -> (String, CheckOutcome) {
+  let response = match client.get(root.clone()).send().await {
+    Ok(response) => response,
+    Err(e) => return (root.to_string(), Err(format!("request failed: {}", e))),
+  };
+  match response.headers().get("X-Authlib-Injector-API-Location") {
+    Some(location) => {
+      let location = location.to_str().unwrap_or_default();
+      match root.join(location) {
+        Ok(auth_url) => (
+          auth_url.to_string(),
+          Ok(format!("redirected to {}", auth_url)),
+        ),
+        Err(_) => (
+          root.to_string(),
+          Err(format!("invalid API location header: {}", location)),
+        ),
+      }
+    }
+    None => (
+      root.to_string(),
+      Ok("no redirection header, the url is used as the API root".to_string()),
+    ),
+  }
+}
+
+async fn check_api_root(client: &reqwest::Client, auth_url: &str) -> Result<Value, String> {
+  let response = client
+    .get(auth_url)
+    .send()
+    .await
+    .map_err(|e| format!("request failed: {}", e))?;
+  if !response.status().is_success() {
+    return Err(describe_error(response).await);
+  }
+  let metadata = response
+    .json::<Value>()
+    .await
+    .map_err(|_| "the metadata is not valid JSON".to_string())?;
+  if !metadata["meta"].is_object() {
+    return Err("the metadata has no `meta` object".to_string());
+  }
+  Ok(metadata)
+}
+
+fn check_signature_public_key(metadata: &Value) -> CheckOutcome {
+  let pem = metadata["signaturePublickey"]
+    .as_str()
+    .ok_or("the metadata has no `signaturePublickey`")?;
+  if !pem.trim_start().starts_with("-----BEGIN PUBLIC KEY-----") {
+    return Err("the key is not a PEM encoded public key".to_string());
+  }
+  RsaPublicKey::from_public_key_pem(pem.trim())
+    .map(|_| "a valid RSA public key".to_string())
+    .map_err(|e| format!("the key can not be parsed: {}", e))
+}
+
+fn is_domain_allowed(skin_domains: &[&str], host: &str) -> bool {
+  skin_domains
+    .iter()
+    .any(|rule| match rule.strip_prefix('.') {
+      Some(suffix) => host.ends_with(&format!(".{}", suffix)),
+      None => host == *rule,
+    })
+}
+
+fn check_skin_domains(metadata: &Value, texture_urls: &[String]) -> CheckOutcome {
+  let skin_domains: Vec<&str> = metadata["skinDomains"]
+    .as_array()
+    .ok_or("the metadata has no `skinDomains`")?
+    .iter()
+    .filter_map(Value::as_str)
+    .collect();
+  if skin_domains.is_empty() {
+    return Err("no skin domain is whitelisted, skins will not load in-game".to_string());
+  }
+  for texture_url in texture_urls {
+    let host = Url::parse(texture_url)
+      .ok()
+      .and_then(|url| url.host_str().map(String::from))
+      .unwrap_or_default();
+    if !is_domain_allowed(&skin_domains, &host) {
+      return Err(format!("the texture host {} is not whitelisted", host));
+    }
+  }
+  if texture_urls.is_empty() {
+    return Ok(format!("{} (no texture to match)", skin_domains.join(", ")));
+  }
+  Ok(skin_domains.join(", "))
+}
+
+async fn check_authenticate(
+  client: &reqwest::Client,
+  auth_url: &str,
+  username: &str,
+  password: &str,
+) -> Result<TestSession, String> {
+  let client_token = Uuid::new_v4().as_simple().to_string();
+  let response = post_json(
+    client,
+    format!("{}/authserver/authenticate", auth_url),
+    json!({
+      "username": username,
+      "password": password,
+      "clientToken": client_token,
+      "requestUser": false,
+      "agent": { "name": "Minecraft", "version": 1 },
+    }),
+  )
+  .await?;
+  if !response.status().is_success() {
+    return Err(describe_error(response).await);
+  }
+  let content = response
+    .json::<Value>()
+    .await
+    .map_err(|_| "the response is not valid JSON".to_string())?;
+  let access_token = content["accessToken"]
+    .as_str()
+    .ok_or("the response has no `accessToken`")?
+    .to_string();
+  if content["clientToken"].as_str() != Some(client_token.as_str()) {
+    return Err("the `clientToken` in the response does not match the request".to_string());
+  }
+  let available_profiles = content["availableProfiles"]
+    .as_array()
+    .ok_or("the response has no `availableProfiles`")?;
+
+  // the only profile is bound to the token by refreshing, as the launcher does on login
+  let is_profile_bound = content["selectedProfile"].is_object();
+  let selected_profile = if is_profile_bound {
+    Some(content["selectedProfile"].clone())
+  } else if available_profiles.len() == 1 {
+    Some(available_profiles[0].clone())
+  } else {
+    None
+  };
+  Ok(TestSession {
+    access_token,
+    client_token,
+    selected_profile,
+    is_profile_bound,
+  })
+}
+
+async fn check_validate(
+  client: &reqwest::Client,
+  auth_url: &str,
+  session: &TestSession,
+) -> CheckOutcome {
+  let response = post_json(
+    client,
+    format!("{}/authserver/validate", auth_url),
+    json!({ "accessToken": session.access_token, "clientToken": session.client_token }),
+  )
+  .await?;
+  if response.status().as_u16() == 204 {
+    Ok("the new token is valid".to_string())
+  } else {
+    Err(describe_error(response).await)
+  }
+}
+
+async fn check_refresh(
+  client: &reqwest::Client,
+  auth_url: &str,
+  session: &mut TestSession,
+) -> CheckOutcome {
+  let mut body = json!({
+    "accessToken": session.access_token,
+    "clientToken": session.client_token,
+  });
+  if let Some(profile) = session
+    .selected_profile
+    .as_ref()
+    .filter(|_| !session.is_profile_bound)
+  {
+    body["selectedProfile"] = json!({ "id": profile["id"], "name": profile["name"] });
+  }
+  let response = post_json(client, format!("{}/authserver/refresh", auth_url), body).await?;
+  if !response.status().is_success() {
+    return Err(describe_error(response).await);
+  }
+  let content = response
+    .json::<Value>()
+    .await
+    .map_err(|_| "the response is not valid JSON".to_string())?;
+  session.access_token = content["accessToken"]
+    .as_str()
+    .ok_or("the response has no `accessToken`")?
+    .to_string();
+  if session.selected_profile.is_some() && !content["selectedProfile"].is_object() {
+    return Err("the profile is not bound to the refreshed token".to_string());
+  }
+  session.is_profile_bound = session.selected_profile.is_some();
+  Ok("the token is refreshed".to_string())
+}
+
+/// Joins a random server id with the test session and checks that `hasJoined` finds it,
+/// or without a session, only checks that the route exists.
+async fn check_has_joined(
+  client: &reqwest::Client,
+  auth_url: &str,
+  session: Option<&TestSession>,
+) -> Result<Option<MinecraftProfile>, String> {
+  let server_id = Uuid::new_v4().as_simple().to_string();
+  let profile = session.and_then(|session| {
+    session
+      .selected_profile
+      .as_ref()
+      .map(|profile| (session, profile))
+  });
+
+  let mut username = "SJMCL".to_string();
+  if let Some((session, profile)) = profile {
+    username = profile["name"].as_str().unwrap_or_default().to_string();
+    let response = post_json(
+      client,
+      format!("{}/sessionserver/session/minecraft/join", auth_url),
+      json!({
+        "accessToken": session.access_token,
+        "selectedProfile": profile["id"],
+        "serverId": server_id,
+      }),
+    )
+    .await?;
+    if response.status().as_u16() != 204 {
+      return Err(format!("join failed, {}", describe_error(response).await));
+    }
+  }
+
+  let response = client
+    .get(format!(
+      "{}/sessionserver/session/minecraft/hasJoined",
+      auth_url
+    ))
+    .query(&[
+      ("username", username.as_str()),
+      ("serverId", server_id.as_str()),
+    ])
+    .send()
+    .await
+    .map_err(|e| format!("request failed: {}", e))?;
+  match (profile.is_some(), response.status().as_u16()) {
+    (true, 200) => response
+      .json::<MinecraftProfile>()
+      .await
+      .map(Some)
+      .map_err(|_| "the joined profile can not be parsed".to_string()),
+    (true, _) => Err(format!(
+      "the joined profile is not found, {}",
+      describe_error(response).await
+    )),
+    (false, 204) => Ok(None),
+    (false, _) => Err(describe_error(response).await),
+  }
+}
+
+fn get_texture_urls(profile: &MinecraftProfile) -> Vec<String> {
+  profile
+    .properties
+    .iter()
+    .filter(|property| property.name == "textures")
+    .filter_map(|property| general_purpose::STANDARD.decode(&property.value).ok())
+    .filter_map(|bytes| serde_json::from_slice::<TextureInfo>(&bytes).ok())
+    .flat_map(|info| info.textures.into_values().map(|texture| texture.url))
+    .collect()
+}
+
+async fn check_openid_feature(
+  client: &reqwest::Client,
+  auth_url: &str,
+  openid_configuration_url: &str,
+) -> CheckOutcome {
+  let configuration = client
+    .get(openid_configuration_url)
+    .send()
+    .await
+    .map_err(|e| format!("request failed: {}", e))?
+    .json::<Value>()
+    .await
+    .map_err(|_| "the OpenID configuration is not valid JSON".to_string())?;
+  for key in [
+    "device_authorization_endpoint",
+    "token_endpoint",
+    "jwks_uri",
+  ] {
+    if !configuration[key].is_string() {
+      return Err(format!("the OpenID configuration has no `{}`", key));
+    }
+  }
+
+  let jwks = client
+    .get(configuration["jwks_uri"].as_str().unwrap_or_default())
+    .send()
+    .await
+    .map_err(|e| format!("request failed: {}", e))?
+    .json::<Value>()
+    .await
+    .map_err(|_| "the JWKS is not valid JSON".to_string())?;
+  if jwks["keys"].as_array().map_or(true, Vec::is_empty) {
+    return Err("the JWKS has no keys".to_string());
+  }
+
+  let domain = Url::parse(auth_url)
+    .ok()
+    .and_then(|url| url.domain().map(String::from))
+    .unwrap_or_default();
+  if get_client_id(domain).is_empty() && !configuration["shared_client_id"].is_string() {
+    return Err("no client id is registered or shared for the launcher".to_string());
+  }
+  Ok("device authorization is supported".to_string())
+}
+
+async fn invalidate(client: &reqwest::Client, auth_url: &str, session: &TestSession) {
+  let _ = post_json(
+    client,
+    format!("{}/authserver/invalidate", auth_url),
+    json!({ "accessToken": session.access_token, "clientToken": session.client_token }),
+  )
+  .await;
+}
+
+/// Runs all checks against the server. The account checks are skipped without a test account.
+/// Later checks are skipped if the ones they depend on fail.
+pub async fn diagnose_auth_server(
+  app: &AppHandle,
+  root: Url,
+  test_account: Option<(String, String)>,
+) -> AuthServerDiagnosis {
+  let client = app.state::<reqwest::Client>();
+  let mut diagnosis = Diagnosis { checks: Vec::new() };
+
+  let (auth_url, outcome) = check_ali_redirection(&client, &root).await;
+  let auth_url = auth_url.trim_end_matches('/').to_string();
+  diagnosis.record(AuthServerCheck::AliRedirection, outcome);
+
+  let metadata = match check_api_root(&client, &auth_url).await {
+    Ok(metadata) => {
+      let server_name = metadata["meta"]["serverName"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+      diagnosis.record(AuthServerCheck::ApiRoot, Ok(server_name));
+      metadata
+    }
+    Err(message) => {
+      diagnosis.record(AuthServerCheck::ApiRoot, Err(message));
+      return AuthServerDiagnosis {
+        auth_url,
+        checks: diagnosis.checks,
+      };
+    }
+  };
+  diagnosis.record(
+    AuthServerCheck::SignaturePublicKey,
+    check_signature_public_key(&metadata),
+  );
+
+  let mut session = None;
+  match test_account {
+    Some((username, password)) => {
+      match check_authenticate(&client, &auth_url, &username, &password).await {
+        Ok(new_session) => {
+          diagnosis.record(AuthServerCheck::Authenticate, Ok(String::new()));
+          session = Some(new_session);
+        }
+        Err(message) => diagnosis.record(AuthServerCheck::Authenticate, Err(message)),
+      }
+    }
+    None => diagnosis.skip(AuthServerCheck::Authenticate, "no test account"),
+  }
+  match session.as_mut() {
+    Some(session) => {
+      let outcome = check_validate(&client, &auth_url, session).await;
+      diagnosis.record(AuthServerCheck::Validate, outcome);
+      let outcome = check_refresh(&client, &auth_url, session).await;
+      diagnosis.record(AuthServerCheck::Refresh, outcome);
+    }
+    None => {
+      diagnosis.skip(AuthServerCheck::Validate, "not authenticated");
+      diagnosis.skip(AuthServerCheck::Refresh, "not authenticated");
+    }
+  }
+
+  let mut texture_urls = Vec::new();
+  match check_has_joined(&client, &auth_url, session.as_ref()).await {
+    Ok(Some(profile)) => {
+      texture_urls = get_texture_urls(&profile);
+      diagnosis.record(AuthServerCheck::HasJoined, Ok(profile.name));
+    }
+    Ok(None) => diagnosis.record(
+      AuthServerCheck::HasJoined,
+      Ok("the route exists, joining is not tested without a profile".to_string()),
+    ),
+    Err(message) => diagnosis.record(AuthServerCheck::HasJoined, Err(message)),
+  }
+  // the whitelist is always checked, the texture hosts only if the profile has textures
+  diagnosis.record(
+    AuthServerCheck::SkinDomains,
+    check_skin_domains(&metadata, &texture_urls),
+  );
+
+  match metadata["meta"]["feature.openid_configuration_url"].as_str() {
+    Some(openid_configuration_url) => {
+      let outcome = check_openid_feature(&client, &auth_url, openid_configuration_url).await;
+      diagnosis.record(AuthServerCheck::OpenidFeature, outcome);
+    }
+    None => diagnosis.skip(
+      AuthServerCheck::OpenidFeature,
+      "`feature.openid_configuration_url` is not declared",
+    ),
+  }
+
+  if let Some(session) = &session {
+    invalidate(&client, &auth_url, session).await;
+  }
+  AuthServerDiagnosis {
+    auth_url,
+    checks: diagnosis.checks,
+  }
+}
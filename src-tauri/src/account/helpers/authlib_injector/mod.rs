@@ -1,7 +1,9 @@
 pub mod common;
 pub mod constants;
+pub mod diagnosis;
 pub mod info;
 pub mod jar;
 pub mod models;
 pub mod oauth;
 pub mod password;
+pub mod texture;
@@ -1,6 +1,6 @@
 use super::constants::SCOPE;
 use crate::account::helpers::authlib_injector::{common::parse_profile, models::MinecraftProfile};
-use crate::account::helpers::misc::{OAuthCode, OAuthTokens};
+use crate::account::helpers::misc::{get_oauth_refresh_error, OAuthCode, OAuthTokens};
 use crate::account::models::{AccountError, AccountInfo, OAuthCodeResponse, PlayerInfo};
 use crate::error::SJMCLResult;
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
@@ -216,7 +216,7 @@ pub async fn refresh(
     .await?;
 
   if !token_response.status().is_success() {
-    return Err(AccountError::Expired)?;
+    return Err(get_oauth_refresh_error(token_response).await)?;
   }
 
   let tokens: OAuthTokens = token_response
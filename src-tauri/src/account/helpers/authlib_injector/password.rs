@@ -1,13 +1,14 @@
 use crate::{
   account::{
     helpers::authlib_injector::{common::parse_profile, models::MinecraftProfile},
-    models::{AccountError, PlayerInfo},
+    models::{AccountError, GameProfile, PlayerInfo},
   },
   error::SJMCLResult,
 };
 use serde_json::{json, Value};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_http::reqwest;
+use uuid::Uuid;
 
 async fn get_profile(
   app: &AppHandle,
@@ -42,12 +43,12 @@ async fn get_profile(
   .await
 }
 
-pub async fn login(
+async fn authenticate(
   app: &AppHandle,
-  auth_server_url: String,
-  username: String,
-  password: String,
-) -> SJMCLResult<Vec<PlayerInfo>> {
+  auth_server_url: &str,
+  username: &str,
+  password: &str,
+) -> SJMCLResult<Value> {
   let client = app.state::<reqwest::Client>();
 
   let response = client
@@ -72,23 +73,57 @@ pub async fn login(
     return Err(AccountError::Invalid.into());
   }
 
-  let content = response
-    .json::<Value>()
-    .await
-    .map_err(|_| AccountError::ParseError)?;
+  Ok(
+    response
+      .json::<Value>()
+      .await
+      .map_err(|_| AccountError::ParseError)?,
+  )
+}
+
+fn parse_game_profile(profile: &Value) -> SJMCLResult<GameProfile> {
+  Ok(GameProfile {
+    uuid: Uuid::parse_str(profile["id"].as_str().ok_or(AccountError::ParseError)?)
+      .map_err(|_| AccountError::ParseError)?,
+    name: profile["name"]
+      .as_str()
+      .ok_or(AccountError::ParseError)?
+      .to_string(),
+  })
+}
+
+pub async fn login(
+  app: &AppHandle,
+  auth_server_url: String,
+  username: String,
+  password: String,
+) -> SJMCLResult<Vec<PlayerInfo>> {
+  let content = authenticate(app, &auth_server_url, &username, &password).await?;
 
   let access_token = content["accessToken"]
     .as_str()
     .unwrap_or_default()
     .to_string();
 
+  // all profiles of the account are kept on its player, so that it can switch between them
+  let available_profiles = content["availableProfiles"]
+    .as_array()
+    .map(|profiles| {
+      profiles
+        .iter()
+        .filter_map(|profile| parse_game_profile(profile).ok())
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+
+  let mut players = vec![];
   if let Some(selected_profile) = content["selectedProfile"].as_object() {
     let id = selected_profile["id"]
       .as_str()
       .ok_or(AccountError::ParseError)?
       .to_string();
 
-    Ok(vec![
+    players.push(
       get_profile(
         app,
         auth_server_url.clone(),
@@ -98,29 +133,18 @@ pub async fn login(
         password.clone(),
       )
       .await?,
-    ])
+    );
   } else {
-    let available_profiles = content["availableProfiles"]
-      .as_array()
-      .ok_or(AccountError::ParseError)?;
-
     if available_profiles.is_empty() {
       return Err(AccountError::NotFound.into());
     }
 
-    let mut players = vec![];
-
-    for profile in available_profiles {
-      let id = profile["id"]
-        .as_str()
-        .ok_or(AccountError::ParseError)?
-        .to_string();
-
+    for profile in &available_profiles {
       let player = get_profile(
         app,
         auth_server_url.clone(),
         access_token.clone(),
-        id,
+        profile.uuid.as_simple().to_string(),
         username.clone(),
         password.clone(),
       )
@@ -128,12 +152,20 @@ pub async fn login(
 
       players.push(player);
     }
+  }
 
-    Ok(players)
+  for player in players.iter_mut() {
+    player.available_profiles = available_profiles.clone();
   }
+  Ok(players)
 }
 
-pub async fn refresh(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<PlayerInfo> {
+async fn refresh_with_profile(
+  app: &AppHandle,
+  player: &PlayerInfo,
+  access_token: Option<String>,
+  profile: &GameProfile,
+) -> SJMCLResult<PlayerInfo> {
   let client = app.state::<reqwest::Client>();
 
   let response = client
@@ -144,10 +176,10 @@ pub async fn refresh(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<Player
     .header("Content-Type", "application/json")
     .body(
       json!({
-        "accessToken": player.access_token,
+        "accessToken": access_token,
         "selectedProfile": {
-          "id": player.uuid.as_simple(),
-          "name": player.name
+          "id": profile.uuid.as_simple(),
+          "name": profile.name
         }
       })
       .to_string(),
@@ -156,6 +188,16 @@ pub async fn refresh(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<Player
     .await
     .map_err(|_| AccountError::NetworkError)?;
 
+  // the token is rejected, or it is not allowed to select a profile
+  if response.status() == reqwest::StatusCode::FORBIDDEN {
+    return Err(AccountError::Invalid)?;
+  }
+  // rate limits and server failures are retried later
+  if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+    || response.status().is_server_error()
+  {
+    return Err(AccountError::NetworkError)?;
+  }
   if !response.status().is_success() {
     return Err(AccountError::Expired)?;
   }
@@ -177,7 +219,7 @@ pub async fn refresh(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<Player
     .ok_or(AccountError::ParseError)?
     .to_string();
 
-  get_profile(
+  let mut refreshed_player = get_profile(
     app,
     player.auth_server_url.clone().unwrap_or_default(),
     access_token,
@@ -185,5 +227,46 @@ pub async fn refresh(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<Player
     player.auth_account.clone().unwrap_or_default(),
     player.password.clone().unwrap_or_default(),
   )
-  .await
+  .await?;
+  refreshed_player.available_profiles = player.available_profiles.clone();
+  Ok(refreshed_player)
+}
+
+pub async fn refresh(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<PlayerInfo> {
+  let profile = GameProfile {
+    uuid: player.uuid,
+    name: player.name.clone(),
+  };
+  refresh_with_profile(app, player, player.access_token.clone(), &profile).await
+}
+
+/// Switches the player to another profile of the same account.
+/// A profile can only be selected on a token that has none yet, servers answer a bound token with
+/// 400 (IllegalArgumentException). So a fresh token is requested with the saved password and the
+/// profile is selected on it. Without a saved password, the current token is tried as is.
+pub async fn switch_profile(
+  app: &AppHandle,
+  player: &PlayerInfo,
+  profile_uuid: Uuid,
+) -> SJMCLResult<PlayerInfo> {
+  let profile = player
+    .available_profiles
+    .iter()
+    .find(|profile| profile.uuid == profile_uuid)
+    .ok_or(AccountError::NotFound)?;
+
+  let access_token = match &player.password {
+    Some(password) => {
+      let content = authenticate(
+        app,
+        &player.auth_server_url.clone().unwrap_or_default(),
+        &player.auth_account.clone().unwrap_or_default(),
+        password,
+      )
+      .await?;
+      content["accessToken"].as_str().map(String::from)
+    }
+    None => player.access_token.clone(),
+  };
+  refresh_with_profile(app, player, access_token, profile).await
 }
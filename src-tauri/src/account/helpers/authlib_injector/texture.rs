@@ -0,0 +1,103 @@
+use crate::{
+  account::{
+    helpers::authlib_injector::{common::parse_profile, models::MinecraftProfile},
+    models::{AccountError, PlayerInfo, Texture},
+  },
+  error::SJMCLResult,
+};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_http::reqwest;
+
+// https://github.com/yushijinhun/authlib-injector/wiki/Yggdrasil-%E6%9C%8D%E5%8A%A1%E7%AB%AF%E6%8A%80%E6%9C%AF%E8%A7%84%E8%8C%83#%E6%9D%90%E8%B4%A8%E4%B8%8A%E4%BC%A0
+fn get_texture_url(player: &PlayerInfo, texture_type: &str) -> String {
+  format!(
+    "{}/api/user/profile/{}/{}",
+    player.auth_server_url.clone().unwrap_or_default(),
+    player.uuid.as_simple(),
+    texture_type.to_lowercase()
+  )
+}
+
+async fn send_texture_request(
+  request: reqwest::RequestBuilder,
+  player: &PlayerInfo,
+) -> SJMCLResult<()> {
+  let response = request
+    .header(
+      "Authorization",
+      format!("Bearer {}", player.access_token.clone().unwrap_or_default()),
+    )
+    .send()
+    .await
+    .map_err(|_| AccountError::NetworkError)?;
+
+  match response.status() {
+    status if status.is_success() => Ok(()),
+    reqwest::StatusCode::UNAUTHORIZED => Err(AccountError::Expired.into()),
+    // the server does not allow uploading this type of texture, or the file is invalid
+    reqwest::StatusCode::BAD_REQUEST | reqwest::StatusCode::FORBIDDEN => {
+      Err(AccountError::TextureError.into())
+    }
+    _ => Err(AccountError::NetworkError.into()),
+  }
+}
+
+// the upload and reset endpoints respond with no content, fetch the textures again
+async fn fetch_textures(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<Vec<Texture>> {
+  let client = app.state::<reqwest::Client>();
+  let auth_server_url = player.auth_server_url.clone().unwrap_or_default();
+  let profile = client
+    .get(format!(
+      "{}/sessionserver/session/minecraft/profile/{}",
+      auth_server_url,
+      player.uuid.as_simple()
+    ))
+    .send()
+    .await
+    .map_err(|_| AccountError::NetworkError)?
+    .json::<MinecraftProfile>()
+    .await
+    .map_err(|_| AccountError::ParseError)?;
+
+  let player_info = parse_profile(app, &profile, None, None, None, None, None).await?;
+  Ok(player_info.textures)
+}
+
+/// Uploads a png texture, `texture_type` is `SKIN` or `CAPE`,
+/// and `model` (skins only) is `default` or `slim`.
+pub async fn upload_texture(
+  app: &AppHandle,
+  player: &PlayerInfo,
+  texture_type: &str,
+  png_bytes: Vec<u8>,
+  model: &str,
+) -> SJMCLResult<Vec<Texture>> {
+  let client = app.state::<reqwest::Client>();
+  let file = reqwest::multipart::Part::bytes(png_bytes)
+    .file_name(format!("{}.png", texture_type.to_lowercase()))
+    .mime_str("image/png")
+    .map_err(|_| AccountError::TextureError)?;
+  let mut form = reqwest::multipart::Form::new().part("file", file);
+  if texture_type == "SKIN" {
+    // empty for the classic model
+    let model = if model == "slim" { "slim" } else { "" };
+    form = form.text("model", model);
+  }
+
+  let request = client
+    .put(get_texture_url(player, texture_type))
+    .multipart(form);
+  send_texture_request(request, player).await?;
+  fetch_textures(app, player).await
+}
+
+pub async fn reset_texture(
+  app: &AppHandle,
+  player: &PlayerInfo,
+  texture_type: &str,
+) -> SJMCLResult<Vec<Texture>> {
+  let client = app.state::<reqwest::Client>();
+  let request = client.delete(get_texture_url(player, texture_type));
+  send_texture_request(request, player).await?;
+  fetch_textures(app, player).await
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+// requests from authlib-injector and the game are small, reject anything larger
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+pub struct HttpRequest {
+  pub method: String,
+  pub path: String,
+  pub query: HashMap<String, String>,
+  pub body: Vec<u8>,
+}
+
+pub struct HttpResponse {
+  pub status: u16,
+  pub content_type: &'static str,
+  pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+  pub fn json(value: &serde_json::Value) -> Self {
+    HttpResponse {
+      status: 200,
+      content_type: "application/json; charset=utf-8",
+      body: value.to_string().into_bytes(),
+    }
+  }
+
+  pub fn png(bytes: Vec<u8>) -> Self {
+    HttpResponse {
+      status: 200,
+      content_type: "image/png",
+      body: bytes,
+    }
+  }
+
+  pub fn no_content() -> Self {
+    Self::status(204)
+  }
+
+  pub fn status(status: u16) -> Self {
+    HttpResponse {
+      status,
+      content_type: "text/plain",
+      body: Vec::new(),
+    }
+  }
+
+  /// The error format of the Yggdrasil api.
+  pub fn error(status: u16, error: &str, message: &str) -> Self {
+    HttpResponse {
+      status,
+      ..Self::json(&serde_json::json!({ "error": error, "errorMessage": message }))
+    }
+  }
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+  query
+    .split('&')
+    .filter_map(|pair| {
+      let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+      let key = urlencoding::decode(key).ok()?.to_string();
+      let value = urlencoding::decode(&value.replace('+', " "))
+        .ok()?
+        .to_string();
+      Some((key, value))
+    })
+    .collect()
+}
+
+/// Reads one HTTP/1.1 request. Returns `None` if the stream is closed or malformed.
+pub async fn read_request(stream: &mut TcpStream) -> Option<HttpRequest> {
+  let mut reader = BufReader::new(stream);
+  let mut request_line = String::new();
+  reader.read_line(&mut request_line).await.ok()?;
+  let mut parts = request_line.split_whitespace();
+  let method = parts.next()?.to_string();
+  let target = parts.next()?;
+  let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+  let mut content_length = 0;
+  loop {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await.ok()? == 0 {
+      return None;
+    }
+    let line = line.trim_end();
+    if line.is_empty() {
+      break;
+    }
+    if let Some((name, value)) = line.split_once(':') {
+      if name.trim().eq_ignore_ascii_case("content-length") {
+        content_length = value.trim().parse().ok()?;
+      }
+    }
+  }
+  if content_length > MAX_BODY_SIZE {
+    return None;
+  }
+
+  let mut body = vec![0; content_length];
+  reader.read_exact(&mut body).await.ok()?;
+  Some(HttpRequest {
+    method,
+    path: path.to_string(),
+    query: parse_query(query),
+    body,
+  })
+}
+
+pub async fn write_response(stream: &mut TcpStream, response: HttpResponse) -> std::io::Result<()> {
+  let reason = match response.status {
+    200 => "OK",
+    204 => "No Content",
+    400 => "Bad Request",
+    403 => "Forbidden",
+    404 => "Not Found",
+    405 => "Method Not Allowed",
+    _ => "Internal Server Error",
+  };
+  let head = format!(
+    "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+    response.status,
+    reason,
+    response.content_type,
+    response.body.len()
+  );
+  stream.write_all(head.as_bytes()).await?;
+  stream.write_all(&response.body).await?;
+  stream.flush().await
+}
@@ -0,0 +1,164 @@
+//! A loopback Yggdrasil server for offline players, so that their skins can be seen in-game in
+//! single-player. It is started on the first launch with an offline player, and injected by
+//! authlib-injector in the same way as the third-party auth servers.
+//! As it only listens on 127.0.0.1, it can not be reached from other machines, e.g. by the players
+//! of a LAN game, who still see the default skins. Serving them would also need the other
+//! launchers to trust the key of this server, so LAN games are deliberately left out.
+//!
+//! ref: https://github.com/yushijinhun/authlib-injector/wiki/Yggdrasil-%E6%9C%8D%E5%8A%A1%E7%AB%AF%E6%8A%80%E6%9C%AF%E8%A7%84%E8%8C%83
+
+mod http;
+mod routes;
+
+use crate::{
+  account::constants::{LOCAL_YGGDRASIL_KEY_FILE_NAME, LOCAL_YGGDRASIL_SERVER_NAME},
+  error::SJMCLResult,
+  APP_DATA_DIR,
+};
+use rsa::{
+  pkcs1v15::SigningKey,
+  pkcs8::{DecodePrivateKey, EncodePrivateKey, EncodePublicKey, LineEnding},
+  RsaPrivateKey, RsaPublicKey,
+};
+use serde_json::{json, Value};
+use sha1::Sha1;
+use std::{
+  collections::HashMap,
+  fs,
+  sync::{Arc, Mutex},
+  time::Duration,
+};
+use tauri::AppHandle;
+use tokio::{net::TcpListener, sync::OnceCell};
+use uuid::Uuid;
+
+/// A skin encoded as png, kept until the player changes the skin.
+struct EncodedSkin {
+  image: Vec<u8>, // the raw rgba pixels it is encoded from
+  png: Vec<u8>,
+  hash: String, // sha256 of the png, used in the texture url
+}
+
+pub struct LocalYggdrasilServer {
+  pub api_root: String,
+  app: AppHandle,
+  signing_key: SigningKey<Sha1>,
+  public_key_pem: String,
+  access_tokens: Mutex<HashMap<String, Uuid>>, // access token -> profile id, for this session only
+  joined_servers: Mutex<HashMap<String, Uuid>>, // server id -> profile id, set by `join`
+  encoded_skins: Mutex<HashMap<Uuid, Arc<EncodedSkin>>>, // profile id -> skin
+}
+
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+static LOCAL_YGGDRASIL_KEY: OnceCell<RsaPrivateKey> = OnceCell::const_new();
+static LOCAL_YGGDRASIL_SERVER: OnceCell<LocalYggdrasilServer> = OnceCell::const_new();
+
+// generating a key takes a while, so it is kept across launcher sessions
+fn load_or_generate_key() -> SJMCLResult<RsaPrivateKey> {
+  let key_path = APP_DATA_DIR
+    .get()
+    .unwrap()
+    .join(LOCAL_YGGDRASIL_KEY_FILE_NAME);
+  if let Ok(pem) = fs::read_to_string(&key_path) {
+    if let Ok(key) = RsaPrivateKey::from_pkcs8_pem(&pem) {
+      return Ok(key);
+    }
+  }
+  let key = RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 4096)?;
+  match key.to_pkcs8_pem(LineEnding::LF) {
+    Ok(pem) => {
+      if let Err(e) = fs::write(&key_path, pem.as_bytes()) {
+        log::error!("failed to save the local yggdrasil key: {}", e);
+      }
+    }
+    Err(e) => log::error!("failed to encode the local yggdrasil key: {}", e),
+  }
+  Ok(key)
+}
+
+/// Loads (or generates on the first run) the signing key once, and reuses it afterwards.
+/// Called on startup in the background, so that launching does not wait for the generation.
+pub async fn get_or_load_key() -> SJMCLResult<&'static RsaPrivateKey> {
+  LOCAL_YGGDRASIL_KEY
+    .get_or_try_init(|| async { tokio::task::spawn_blocking(load_or_generate_key).await? })
+    .await
+}
+
+async fn start_server(app: &AppHandle) -> SJMCLResult<LocalYggdrasilServer> {
+  let private_key = get_or_load_key().await?;
+  let public_key_pem = RsaPublicKey::from(private_key).to_public_key_pem(LineEnding::LF)?;
+
+  // only reachable from this machine
+  let listener = TcpListener::bind("127.0.0.1:0").await?;
+  let port = listener.local_addr()?.port();
+
+  let app_handle = app.clone();
+  tauri::async_runtime::spawn(async move {
+    loop {
+      let (mut stream, _) = match listener.accept().await {
+        Ok(connection) => connection,
+        Err(e) => {
+          // e.g. out of file descriptors, wait for them to be released instead of spinning
+          log::error!("local yggdrasil server failed to accept: {}", e);
+          tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+          continue;
+        }
+      };
+      tauri::async_runtime::spawn(async move {
+        let Some(server) = LOCAL_YGGDRASIL_SERVER.get() else {
+          return;
+        };
+        if let Some(request) = http::read_request(&mut stream).await {
+          let response = routes::handle_request(server, request);
+          if let Err(e) = http::write_response(&mut stream, response).await {
+            log::error!("local yggdrasil server failed to respond: {}", e);
+          }
+        }
+      });
+    }
+  });
+
+  Ok(LocalYggdrasilServer {
+    api_root: format!("http://127.0.0.1:{}", port),
+    app: app_handle,
+    signing_key: SigningKey::<Sha1>::new(private_key.clone()),
+    public_key_pem,
+    access_tokens: Mutex::new(HashMap::new()),
+    joined_servers: Mutex::new(HashMap::new()),
+    encoded_skins: Mutex::new(HashMap::new()),
+  })
+}
+
+/// Starts the server if it is not running yet, and returns it.
+pub async fn get_or_start_server(app: &AppHandle) -> SJMCLResult<&'static LocalYggdrasilServer> {
+  LOCAL_YGGDRASIL_SERVER
+    .get_or_try_init(|| start_server(app))
+    .await
+}
+
+impl LocalYggdrasilServer {
+  /// Issues a random access token of the player, valid until the launcher exits.
+  pub fn issue_access_token(&self, profile_id: Uuid) -> String {
+    let token = Uuid::new_v4().as_simple().to_string();
+    if let Ok(mut access_tokens) = self.access_tokens.lock() {
+      access_tokens.insert(token.clone(), profile_id);
+    }
+    token
+  }
+
+  /// The api metadata, passed to authlib-injector as the prefetched metadata.
+  pub fn metadata(&self) -> Value {
+    json!({
+      "meta": {
+        "serverName": LOCAL_YGGDRASIL_SERVER_NAME,
+        "implementationName": "SJMCL",
+        "implementationVersion": env!("CARGO_PKG_VERSION"),
+        "feature.non_email_login": true,
+        "feature.no_mojang_namespace": true,
+      },
+      "skinDomains": ["127.0.0.1"],
+      "signaturePublickey": self.public_key_pem,
+    })
+  }
+}
@@ -0,0 +1,286 @@
+use super::http::{HttpRequest, HttpResponse};
+use super::{EncodedSkin, LocalYggdrasilServer};
+use crate::account::models::{AccountInfo, PlayerInfo, PlayerType};
+use base64::{engine::general_purpose, Engine};
+use image::{codecs::png::PngEncoder, ImageEncoder};
+use rsa::signature::{SignatureEncoding, Signer};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+use uuid::Uuid;
+
+fn get_offline_players(server: &LocalYggdrasilServer) -> Vec<PlayerInfo> {
+  let account_binding = server.app.state::<Mutex<AccountInfo>>();
+  let Ok(account_state) = account_binding.lock() else {
+    return Vec::new();
+  };
+  account_state
+    .players
+    .iter()
+    .filter(|player| player.player_type == PlayerType::Offline)
+    .cloned()
+    .collect()
+}
+
+/// Returns the png of the player's skin, only encoded again after the skin is changed.
+fn get_encoded_skin(
+  server: &LocalYggdrasilServer,
+  player: &PlayerInfo,
+) -> Option<Arc<EncodedSkin>> {
+  let skin = player.textures.iter().find(|t| t.texture_type == "SKIN")?;
+  let image = &skin.image.image;
+  let mut encoded_skins = server.encoded_skins.lock().ok()?;
+  if let Some(encoded) = encoded_skins.get(&player.uuid) {
+    if encoded.image == image.as_raw().as_slice() {
+      return Some(encoded.clone());
+    }
+  }
+
+  let mut png = Vec::new();
+  PngEncoder::new(&mut png)
+    .write_image(
+      image.as_raw(),
+      image.width(),
+      image.height(),
+      image::ColorType::Rgba8.into(),
+    )
+    .ok()?;
+  let encoded = Arc::new(EncodedSkin {
+    image: image.as_raw().clone(),
+    hash: hex::encode(Sha256::digest(&png)),
+    png,
+  });
+  encoded_skins.insert(player.uuid, encoded.clone());
+  Some(encoded)
+}
+
+fn sign(server: &LocalYggdrasilServer, data: &str) -> String {
+  general_purpose::STANDARD.encode(server.signing_key.sign(data.as_bytes()).to_bytes())
+}
+
+fn profile_json(server: &LocalYggdrasilServer, player: &PlayerInfo, signed: bool) -> Value {
+  let mut textures = json!({});
+  if let Some(encoded) = get_encoded_skin(server, player) {
+    let model = player
+      .textures
+      .iter()
+      .find(|t| t.texture_type == "SKIN")
+      .map(|t| t.model.as_str());
+    let mut skin = json!({ "url": format!("{}/textures/{}", server.api_root, encoded.hash) });
+    if model == Some("slim") {
+      skin["metadata"] = json!({ "model": "slim" });
+    }
+    textures["SKIN"] = skin;
+  }
+  let textures_value = general_purpose::STANDARD.encode(
+    json!({
+      "timestamp": chrono::Utc::now().timestamp_millis(),
+      "profileId": player.uuid.as_simple().to_string(),
+      "profileName": player.name,
+      "textures": textures,
+    })
+    .to_string(),
+  );
+
+  let mut property = json!({ "name": "textures", "value": textures_value });
+  if signed {
+    property["signature"] = json!(sign(server, &textures_value));
+  }
+  json!({
+    "id": player.uuid.as_simple().to_string(),
+    "name": player.name,
+    "properties": [property],
+  })
+}
+
+fn short_profile_json(player: &PlayerInfo) -> Value {
+  json!({ "id": player.uuid.as_simple().to_string(), "name": player.name })
+}
+
+fn find_player_by_token<'a>(
+  server: &LocalYggdrasilServer,
+  players: &'a [PlayerInfo],
+  token: &str,
+) -> Option<&'a PlayerInfo> {
+  let profile_id = *server.access_tokens.lock().ok()?.get(token)?;
+  players.iter().find(|player| player.uuid == profile_id)
+}
+
+fn parse_body(request: &HttpRequest) -> Value {
+  serde_json::from_slice(&request.body).unwrap_or(Value::Null)
+}
+
+fn invalid_token() -> HttpResponse {
+  HttpResponse::error(403, "ForbiddenOperationException", "Invalid token.")
+}
+
+fn authenticate(
+  server: &LocalYggdrasilServer,
+  request: &HttpRequest,
+  players: &[PlayerInfo],
+) -> HttpResponse {
+  let body = parse_body(request);
+  let username = body["username"].as_str().unwrap_or_default();
+  let Some(player) = players.iter().find(|player| player.name == username) else {
+    return HttpResponse::error(
+      403,
+      "ForbiddenOperationException",
+      "Invalid credentials. Invalid username or password.",
+    );
+  };
+  let client_token = body["clientToken"]
+    .as_str()
+    .map(String::from)
+    .unwrap_or_else(|| Uuid::new_v4().as_simple().to_string());
+  HttpResponse::json(&json!({
+    "accessToken": server.issue_access_token(player.uuid),
+    "clientToken": client_token,
+    "availableProfiles": [short_profile_json(player)],
+    "selectedProfile": short_profile_json(player),
+  }))
+}
+
+fn refresh(
+  server: &LocalYggdrasilServer,
+  request: &HttpRequest,
+  players: &[PlayerInfo],
+) -> HttpResponse {
+  let body = parse_body(request);
+  let token = body["accessToken"].as_str().unwrap_or_default();
+  let Some(player) = find_player_by_token(server, players, token) else {
+    return invalid_token();
+  };
+  // the old token is revoked by a refresh
+  if let Ok(mut access_tokens) = server.access_tokens.lock() {
+    access_tokens.remove(token);
+  }
+  HttpResponse::json(&json!({
+    "accessToken": server.issue_access_token(player.uuid),
+    "clientToken": body["clientToken"],
+    "selectedProfile": short_profile_json(player),
+  }))
+}
+
+fn validate(
+  server: &LocalYggdrasilServer,
+  request: &HttpRequest,
+  players: &[PlayerInfo],
+) -> HttpResponse {
+  let body = parse_body(request);
+  let token = body["accessToken"].as_str().unwrap_or_default();
+  match find_player_by_token(server, players, token) {
+    Some(_) => HttpResponse::no_content(),
+    None => invalid_token(),
+  }
+}
+
+fn join(
+  server: &LocalYggdrasilServer,
+  request: &HttpRequest,
+  players: &[PlayerInfo],
+) -> HttpResponse {
+  let body = parse_body(request);
+  let token = body["accessToken"].as_str().unwrap_or_default();
+  let profile_id = body["selectedProfile"].as_str().unwrap_or_default();
+  let server_id = body["serverId"].as_str().unwrap_or_default();
+  let Some(player) = find_player_by_token(server, players, token) else {
+    return invalid_token();
+  };
+  if player.uuid.as_simple().to_string() != profile_id || server_id.is_empty() {
+    return invalid_token();
+  }
+  if let Ok(mut joined_servers) = server.joined_servers.lock() {
+    joined_servers.insert(server_id.to_string(), player.uuid);
+  }
+  HttpResponse::no_content()
+}
+
+fn has_joined(
+  server: &LocalYggdrasilServer,
+  request: &HttpRequest,
+  players: &[PlayerInfo],
+) -> HttpResponse {
+  let username = request.query.get("username").cloned().unwrap_or_default();
+  let server_id = request.query.get("serverId").cloned().unwrap_or_default();
+  let joined_uuid = server
+    .joined_servers
+    .lock()
+    .ok()
+    .and_then(|joined_servers| joined_servers.get(&server_id).copied());
+  match players
+    .iter()
+    .find(|player| player.name == username && Some(player.uuid) == joined_uuid)
+  {
+    Some(player) => HttpResponse::json(&profile_json(server, player, true)),
+    None => HttpResponse::no_content(),
+  }
+}
+
+fn query_profile(
+  server: &LocalYggdrasilServer,
+  request: &HttpRequest,
+  players: &[PlayerInfo],
+  uuid: &str,
+) -> HttpResponse {
+  let signed = request.query.get("unsigned").map(String::as_str) == Some("false");
+  match Uuid::parse_str(uuid)
+    .ok()
+    .and_then(|uuid| players.iter().find(|player| player.uuid == uuid))
+  {
+    Some(player) => HttpResponse::json(&profile_json(server, player, signed)),
+    None => HttpResponse::no_content(),
+  }
+}
+
+fn query_profiles_by_names(request: &HttpRequest, players: &[PlayerInfo]) -> HttpResponse {
+  let names: Vec<String> = serde_json::from_slice(&request.body).unwrap_or_default();
+  let profiles: Vec<Value> = players
+    .iter()
+    .filter(|player| {
+      names
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(&player.name))
+    })
+    .map(short_profile_json)
+    .collect();
+  HttpResponse::json(&Value::Array(profiles))
+}
+
+fn texture(server: &LocalYggdrasilServer, players: &[PlayerInfo], hash: &str) -> HttpResponse {
+  players
+    .iter()
+    .filter_map(|player| get_encoded_skin(server, player))
+    .find(|encoded| encoded.hash == hash)
+    .map_or(HttpResponse::status(404), |encoded| {
+      HttpResponse::png(encoded.png.clone())
+    })
+}
+
+pub fn handle_request(server: &LocalYggdrasilServer, request: HttpRequest) -> HttpResponse {
+  let players = get_offline_players(server);
+  let path = request.path.trim_end_matches('/');
+
+  match (request.method.as_str(), path) {
+    ("GET", "") => HttpResponse::json(&server.metadata()),
+    ("POST", "/authserver/authenticate") => authenticate(server, &request, &players),
+    ("POST", "/authserver/refresh") => refresh(server, &request, &players),
+    ("POST", "/authserver/validate") => validate(server, &request, &players),
+    ("POST", "/authserver/invalidate") | ("POST", "/authserver/signout") => {
+      HttpResponse::no_content()
+    }
+    ("POST", "/sessionserver/session/minecraft/join") => join(server, &request, &players),
+    ("GET", "/sessionserver/session/minecraft/hasJoined") => has_joined(server, &request, &players),
+    ("POST", "/api/profiles/minecraft") => query_profiles_by_names(&request, &players),
+    ("GET", path) => {
+      if let Some(uuid) = path.strip_prefix("/sessionserver/session/minecraft/profile/") {
+        query_profile(server, &request, &players, uuid)
+      } else if let Some(hash) = path.strip_prefix("/textures/") {
+        texture(server, &players, hash)
+      } else {
+        HttpResponse::status(404)
+      }
+    }
+    _ => HttpResponse::status(404),
+  }
+}
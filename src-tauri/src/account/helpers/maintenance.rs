@@ -0,0 +1,182 @@
+use crate::{
+  account::{
+    constants::PLAYER_AUTH_STATE_EVENT,
+    helpers::{
+      authlib_injector::{self, info::get_auth_server_info_by_url},
+      microsoft,
+    },
+    models::{AccountError, AccountInfo, AuthServer, PlayerAuthState, PlayerInfo, PlayerType},
+  },
+  error::{SJMCLError, SJMCLResult},
+  launcher_config::models::LauncherConfig,
+  storage::Storage,
+};
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+lazy_static! {
+  // player id -> lock held while refreshing, as a refresh token can only be used once
+  static ref PLAYER_REFRESH_LOCKS: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>> =
+    Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerAuthStatePayload {
+  pub player_id: String,
+  pub auth_state: PlayerAuthState,
+}
+
+/// Reads the `exp` claim (unix timestamp in seconds) of a JWT access token, without verifying it.
+/// Returns `None` if the token is not a JWT, e.g. the random tokens of some Yggdrasil servers.
+pub fn get_token_expiration(token: &str) -> Option<i64> {
+  let mut validation = Validation::default();
+  validation.insecure_disable_signature_validation();
+  validation.validate_exp = false;
+  validation.validate_aud = false;
+  validation.required_spec_claims.clear();
+  let token_data = decode::<Value>(token, &DecodingKey::from_secret(&[]), &validation).ok()?;
+  token_data.claims["exp"].as_i64()
+}
+
+/// Whether the error means the credentials can not be refreshed anymore,
+/// rather than a temporary failure such as the network being down.
+pub fn is_revoked_error(error: &SJMCLError) -> bool {
+  error.0 == AccountError::Expired.to_string() || error.0 == AccountError::Invalid.to_string()
+}
+
+/// Refreshes the tokens of a Microsoft or third-party player.
+/// Players logged in with a saved password log in again if the refresh is rejected.
+pub async fn refresh_player_info(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<PlayerInfo> {
+  match player.player_type {
+    PlayerType::Microsoft => microsoft::oauth::refresh(app, player).await,
+    PlayerType::ThirdParty => {
+      let auth_server_url = player.auth_server_url.clone().unwrap_or_default();
+      let auth_server =
+        AuthServer::from(get_auth_server_info_by_url(app, auth_server_url.clone())?);
+      match authlib_injector::common::refresh(app, player, &auth_server).await {
+        Err(e) if is_revoked_error(&e) && player.password.is_some() => {
+          let new_player = authlib_injector::password::login(
+            app,
+            auth_server_url,
+            player.auth_account.clone().unwrap_or_default(),
+            player.password.clone().unwrap_or_default(),
+          )
+          .await?
+          .into_iter()
+          .find(|new_player| new_player.uuid == player.uuid)
+          .ok_or(AccountError::NotFound)?;
+          authlib_injector::password::refresh(app, &new_player).await
+        }
+        result => result,
+      }
+    }
+    PlayerType::Offline => Err(AccountError::Invalid.into()),
+  }
+}
+
+/// Refreshes a player and saves the result, or marks the player revoked if the refresh is rejected.
+/// Refreshes of the same player run one at a time, and the player is read again under the lock,
+/// so a refresh token rotated by a concurrent refresh is never reused.
+pub async fn refresh_and_save_player(app: &AppHandle, player_id: &str) -> SJMCLResult<PlayerInfo> {
+  let refresh_lock = PLAYER_REFRESH_LOCKS
+    .lock()?
+    .entry(player_id.to_string())
+    .or_default()
+    .clone();
+  let _refresh_guard = refresh_lock.lock().await;
+
+  let player = {
+    let account_binding = app.state::<Mutex<AccountInfo>>();
+    let account_state = account_binding.lock()?;
+    account_state
+      .players
+      .iter()
+      .find(|player| player.id == player_id)
+      .cloned()
+      .ok_or(AccountError::NotFound)?
+  };
+  match refresh_player_info(app, &player).await {
+    Ok(refreshed_player) => {
+      save_refreshed_player(app, player_id, refreshed_player.clone())?;
+      Ok(refreshed_player)
+    }
+    Err(e) => {
+      if is_revoked_error(&e) {
+        set_player_auth_state(app, player_id, PlayerAuthState::Revoked)?;
+      }
+      Err(e)
+    }
+  }
+}
+
+fn emit_auth_state(app: &AppHandle, player_id: &str, auth_state: PlayerAuthState) {
+  let payload = PlayerAuthStatePayload {
+    player_id: player_id.to_string(),
+    auth_state,
+  };
+  if let Err(e) = app.emit(PLAYER_AUTH_STATE_EVENT, payload) {
+    log::error!("failed to emit the player auth state: {}", e);
+  }
+}
+
+/// Replaces the player with the refreshed one, keeping it selected if its ID changes
+/// (e.g. the player was renamed).
+pub fn save_refreshed_player(
+  app: &AppHandle,
+  player_id: &str,
+  mut refreshed_player: PlayerInfo,
+) -> SJMCLResult<()> {
+  refreshed_player.auth_state = PlayerAuthState::Valid;
+  let new_player_id = refreshed_player.id.clone();
+  {
+    let account_binding = app.state::<Mutex<AccountInfo>>();
+    let mut account_state = account_binding.lock()?;
+    let player = account_state
+      .get_player_by_id_mut(player_id.to_string())
+      .ok_or(AccountError::NotFound)?;
+    *player = refreshed_player;
+    account_state.save()?;
+  }
+
+  if new_player_id != player_id {
+    let config_binding = app.state::<Mutex<LauncherConfig>>();
+    let mut config_state = config_binding.lock()?;
+    if config_state.states.shared.selected_player_id == player_id {
+      config_state.partial_update(
+        app,
+        "states.shared.selected_player_id",
+        &serde_json::to_string(&new_player_id)?,
+      )?;
+      config_state.save()?;
+    }
+  }
+  emit_auth_state(app, &new_player_id, PlayerAuthState::Valid);
+  Ok(())
+}
+
+pub fn set_player_auth_state(
+  app: &AppHandle,
+  player_id: &str,
+  auth_state: PlayerAuthState,
+) -> SJMCLResult<()> {
+  {
+    let account_binding = app.state::<Mutex<AccountInfo>>();
+    let mut account_state = account_binding.lock()?;
+    let player = account_state
+      .get_player_by_id_mut(player_id.to_string())
+      .ok_or(AccountError::NotFound)?;
+    if player.auth_state == auth_state {
+      return Ok(());
+    }
+    player.auth_state = auth_state;
+    account_state.save()?;
+  }
+  emit_auth_state(app, player_id, auth_state);
+  Ok(())
+}
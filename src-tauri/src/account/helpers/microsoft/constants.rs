@@ -1,3 +1,4 @@
 pub static CLIENT_ID: &str = "b2468fd2-7996-4f42-8857-5b65d834ef5c";
 pub static SCOPE: &str = "XboxLive.signin offline_access";
 pub static TOKEN_ENDPOINT: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/token";
+pub static PROFILE_ENDPOINT: &str = "https://api.minecraftservices.com/minecraft/profile";
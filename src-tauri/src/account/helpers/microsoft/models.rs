@@ -8,9 +8,11 @@ pub struct MinecraftProfile {
 
 #[derive(serde::Deserialize, Debug)]
 pub struct TextureEntry {
+  pub id: String,
   pub state: String,
   pub url: String,
   pub variant: Option<String>,
+  pub alias: Option<String>, // capes only
 }
 
 structstruck::strike! {
@@ -1,9 +1,9 @@
 use super::constants::{CLIENT_ID, SCOPE, TOKEN_ENDPOINT};
 use crate::account::helpers::microsoft::models::{MinecraftProfile, XstsResponse};
-use crate::account::helpers::misc::{fetch_image, OAuthCode, OAuthTokens};
+use crate::account::helpers::misc::{fetch_image, get_oauth_refresh_error, OAuthCode, OAuthTokens};
 use crate::account::helpers::offline::load_preset_skin;
 use crate::account::models::{
-  AccountError, AccountInfo, OAuthCodeResponse, PlayerInfo, PlayerType, Texture,
+  AccountError, AccountInfo, OAuthCodeResponse, PlayerAuthState, PlayerInfo, PlayerType, Texture,
 };
 use crate::error::SJMCLResult;
 use serde_json::{json, Value};
@@ -149,12 +149,10 @@ async fn fetch_minecraft_profile(
   )
 }
 
-async fn parse_profile(app: &AppHandle, tokens: &OAuthTokens) -> SJMCLResult<PlayerInfo> {
-  let xbl_token = fetch_xbl_token(app, tokens.access_token.clone()).await?;
-  let (xsts_userhash, xsts_token) = fetch_xsts_token(app, xbl_token).await?;
-  let minecraft_token = fetch_minecraft_token(app, xsts_userhash, xsts_token).await?;
-  let profile = fetch_minecraft_profile(app, minecraft_token.clone()).await?;
-
+pub async fn parse_profile_textures(
+  app: &AppHandle,
+  profile: &MinecraftProfile,
+) -> SJMCLResult<Vec<Texture>> {
   let mut textures = vec![];
   if let Some(skins) = &profile.skins {
     for skin in skins {
@@ -162,7 +160,11 @@ async fn parse_profile(app: &AppHandle, tokens: &OAuthTokens) -> SJMCLResult<Pla
         textures.push(Texture {
           texture_type: "SKIN".to_string(),
           image: fetch_image(app, skin.url.clone()).await?,
-          model: skin.variant.clone().unwrap_or("default".to_string()),
+          // the services api uses `CLASSIC` and `SLIM`
+          model: match skin.variant.as_deref().map(str::to_lowercase).as_deref() {
+            Some("slim") => "slim".to_string(),
+            _ => "default".to_string(),
+          },
           preset: None,
         });
       }
@@ -185,6 +187,16 @@ async fn parse_profile(app: &AppHandle, tokens: &OAuthTokens) -> SJMCLResult<Pla
     // this player didn't have a texture, use preset Steve skin instead
     textures = load_preset_skin(app, "steve".to_string())?;
   }
+  Ok(textures)
+}
+
+async fn parse_profile(app: &AppHandle, tokens: &OAuthTokens) -> SJMCLResult<PlayerInfo> {
+  let xbl_token = fetch_xbl_token(app, tokens.access_token.clone()).await?;
+  let (xsts_userhash, xsts_token) = fetch_xsts_token(app, xbl_token).await?;
+  let minecraft_token = fetch_minecraft_token(app, xsts_userhash, xsts_token).await?;
+  let profile = fetch_minecraft_profile(app, minecraft_token.clone()).await?;
+
+  let textures = parse_profile_textures(app, &profile).await?;
 
   Ok(
     PlayerInfo {
@@ -198,6 +210,8 @@ async fn parse_profile(app: &AppHandle, tokens: &OAuthTokens) -> SJMCLResult<Pla
       textures,
       auth_server_url: None,
       password: None,
+      auth_state: PlayerAuthState::Valid,
+      available_profiles: Vec::new(),
     }
     .with_generated_id(),
   )
@@ -289,7 +303,7 @@ pub async fn refresh(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<Player
     .map_err(|_| AccountError::NetworkError)?;
 
   if !token_response.status().is_success() {
-    return Err(AccountError::Expired)?;
+    return Err(get_oauth_refresh_error(token_response).await)?;
   }
 
   let tokens: OAuthTokens = token_response
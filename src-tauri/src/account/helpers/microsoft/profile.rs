@@ -0,0 +1,101 @@
+use super::constants::PROFILE_ENDPOINT;
+use crate::account::helpers::microsoft::models::MinecraftProfile;
+use crate::account::helpers::microsoft::oauth::parse_profile_textures;
+use crate::account::models::{AccountError, CapeInfo, PlayerInfo, Texture};
+use crate::error::SJMCLResult;
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_http::reqwest;
+
+// the skin and cape endpoints all respond with the updated profile
+async fn send_profile_request(
+  request: reqwest::RequestBuilder,
+  player: &PlayerInfo,
+) -> SJMCLResult<MinecraftProfile> {
+  let response = request
+    .header(
+      "Authorization",
+      format!("Bearer {}", player.access_token.clone().unwrap_or_default()),
+    )
+    .send()
+    .await
+    .map_err(|_| AccountError::NetworkError)?;
+
+  match response.status() {
+    status if status.is_success() => {}
+    reqwest::StatusCode::UNAUTHORIZED => return Err(AccountError::Expired.into()),
+    reqwest::StatusCode::BAD_REQUEST => return Err(AccountError::TextureError.into()),
+    _ => return Err(AccountError::NetworkError.into()),
+  }
+  Ok(
+    response
+      .json::<MinecraftProfile>()
+      .await
+      .map_err(|_| AccountError::ParseError)?,
+  )
+}
+
+/// Uploads a png skin, `model` is `default` (classic) or `slim`.
+pub async fn upload_skin(
+  app: &AppHandle,
+  player: &PlayerInfo,
+  png_bytes: Vec<u8>,
+  model: &str,
+) -> SJMCLResult<Vec<Texture>> {
+  let client = app.state::<reqwest::Client>();
+  let variant = if model == "slim" { "slim" } else { "classic" };
+  let file = reqwest::multipart::Part::bytes(png_bytes)
+    .file_name("skin.png")
+    .mime_str("image/png")
+    .map_err(|_| AccountError::TextureError)?;
+  let form = reqwest::multipart::Form::new()
+    .text("variant", variant)
+    .part("file", file);
+
+  let request = client
+    .post(format!("{}/skins", PROFILE_ENDPOINT))
+    .multipart(form);
+  let profile = send_profile_request(request, player).await?;
+  parse_profile_textures(app, &profile).await
+}
+
+pub async fn reset_skin(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<Vec<Texture>> {
+  let client = app.state::<reqwest::Client>();
+  let request = client.delete(format!("{}/skins/active", PROFILE_ENDPOINT));
+  let profile = send_profile_request(request, player).await?;
+  parse_profile_textures(app, &profile).await
+}
+
+pub async fn fetch_capes(app: &AppHandle, player: &PlayerInfo) -> SJMCLResult<Vec<CapeInfo>> {
+  let client = app.state::<reqwest::Client>();
+  let profile = send_profile_request(client.get(PROFILE_ENDPOINT), player).await?;
+  Ok(
+    profile
+      .capes
+      .unwrap_or_default()
+      .into_iter()
+      .map(|cape| CapeInfo {
+        active: cape.state == "ACTIVE",
+        name: cape.alias.unwrap_or_default(),
+        id: cape.id,
+        url: cape.url,
+      })
+      .collect(),
+  )
+}
+
+/// Shows the cape of `cape_id`, or hides the cape if `None`.
+pub async fn set_active_cape(
+  app: &AppHandle,
+  player: &PlayerInfo,
+  cape_id: Option<String>,
+) -> SJMCLResult<Vec<Texture>> {
+  let client = app.state::<reqwest::Client>();
+  let url = format!("{}/capes/active", PROFILE_ENDPOINT);
+  let request = match cape_id {
+    Some(cape_id) => client.put(url).json(&json!({ "capeId": cape_id })),
+    None => client.delete(url),
+  };
+  let profile = send_profile_request(request, player).await?;
+  parse_profile_textures(app, &profile).await
+}
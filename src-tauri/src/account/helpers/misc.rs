@@ -28,6 +28,24 @@ pub struct OAuthTokens {
   pub id_token: Option<String>,
 }
 
+/// The error of a rejected OAuth token refresh. Only a refresh token rejected by the server
+/// (400 / 401 `invalid_grant`) is `Expired`, rate limits and server failures are retried later.
+pub async fn get_oauth_refresh_error(response: reqwest::Response) -> AccountError {
+  let status = response.status();
+  if status != reqwest::StatusCode::BAD_REQUEST && status != reqwest::StatusCode::UNAUTHORIZED {
+    return AccountError::NetworkError;
+  }
+  let content = response
+    .json::<serde_json::Value>()
+    .await
+    .unwrap_or_default();
+  if content["error"] == "invalid_grant" {
+    AccountError::Expired
+  } else {
+    AccountError::NetworkError
+  }
+}
+
 pub async fn fetch_image(app: &AppHandle, url: String) -> SJMCLResult<ImageWrapper> {
   let client = app.state::<reqwest::Client>();
 
@@ -1,4 +1,6 @@
 pub mod authlib_injector;
+pub mod local_yggdrasil;
+pub mod maintenance;
 pub mod microsoft;
 pub mod misc;
 pub mod offline;
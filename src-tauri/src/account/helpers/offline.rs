@@ -1,11 +1,13 @@
 use crate::{
   account::{
     constants::TEXTURE_ROLES,
-    models::{AccountError, PlayerInfo, PlayerType, Texture},
+    helpers::skin::validate_skin_image,
+    models::{AccountError, PlayerAuthState, PlayerInfo, PlayerType, Texture},
   },
   error::SJMCLResult,
   utils::{fs::get_app_resource_filepath, image::load_image_from_dir},
 };
+use image::RgbaImage;
 use rand::seq::IndexedRandom;
 use tauri::AppHandle;
 use uuid::Uuid;
@@ -24,6 +26,16 @@ pub fn load_preset_skin(app: &AppHandle, preset_role: String) -> SJMCLResult<Vec
   }])
 }
 
+pub fn load_custom_skin(img: RgbaImage, model: &str) -> SJMCLResult<Vec<Texture>> {
+  validate_skin_image(&img)?;
+  Ok(vec![Texture {
+    texture_type: "SKIN".to_string(),
+    image: img.into(),
+    model: if model == "slim" { "slim" } else { "default" }.to_string(),
+    preset: None,
+  }])
+}
+
 pub async fn login(app: &AppHandle, username: String, raw_uuid: String) -> SJMCLResult<PlayerInfo> {
   let name_with_prefix = format!("OfflinePlayer:{}", username);
   let uuid = if let Ok(id) = Uuid::parse_str(&raw_uuid) {
@@ -49,6 +61,8 @@ pub async fn login(app: &AppHandle, username: String, raw_uuid: String) -> SJMCL
       access_token: None,
       refresh_token: None,
       textures: load_preset_skin(app, texture_role.to_string())?,
+      auth_state: PlayerAuthState::Unknown,
+      available_profiles: Vec::new(),
     }
     .with_generated_id(),
   )
@@ -1,5 +1,14 @@
+use crate::{account::models::AccountError, error::SJMCLResult};
 use image::RgbaImage;
 
+/// Checks the layout of a skin: 64x64, or the legacy 64x32 before 1.8.
+pub fn validate_skin_image(img: &RgbaImage) -> SJMCLResult<()> {
+  match img.dimensions() {
+    (64, 64) | (64, 32) => Ok(()),
+    _ => Err(AccountError::TextureError.into()),
+  }
+}
+
 pub fn draw_avatar(size: u32, img: &RgbaImage) -> RgbaImage {
   let (skin_width, _) = img.dimensions();
 
@@ -1,8 +1,12 @@
 use super::{
-  constants::ACCOUNTS_FILE_NAME,
+  constants::{ACCOUNTS_FILE_NAME, ACCOUNTS_SCHEMA_VERSION},
   helpers::{authlib_injector::constants::PRESET_AUTH_SERVERS, skin::draw_avatar},
 };
-use crate::{storage::Storage, utils::image::ImageWrapper, APP_DATA_DIR};
+use crate::{
+  storage::{introduce_schema_version, MigrationStep, SchemaMigration, Storage},
+  utils::image::ImageWrapper,
+  APP_DATA_DIR,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::path::PathBuf;
@@ -19,6 +23,16 @@ pub enum PlayerType {
   Microsoft,
 }
 
+// kept up to date by the background account maintenance
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PlayerAuthState {
+  #[default]
+  Unknown,
+  Valid,
+  Revoked, // the refresh token was revoked or expired, need to log in again
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Texture {
@@ -28,6 +42,24 @@ pub struct Texture {
   pub preset: Option<String>,
 }
 
+// capes owned by a Microsoft account
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CapeInfo {
+  pub id: String,
+  pub name: String,
+  pub url: String,
+  pub active: bool,
+}
+
+// a game profile (character) of a third-party account
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GameProfile {
+  pub uuid: Uuid,
+  pub name: String,
+}
+
 // only for the client
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
@@ -43,6 +75,10 @@ pub struct Player {
   pub access_token: Option<String>,
   pub refresh_token: Option<String>,
   pub textures: Vec<Texture>,
+  #[serde(default)]
+  pub auth_state: PlayerAuthState,
+  #[serde(default)]
+  pub available_profiles: Vec<GameProfile>, // only from authlib-injector password login
 }
 
 impl From<PlayerInfo> for Player {
@@ -74,6 +110,8 @@ impl From<PlayerInfo> for Player {
       refresh_token: player_info.refresh_token,
       auth_server,
       textures: player_info.textures,
+      auth_state: player_info.auth_state,
+      available_profiles: player_info.available_profiles,
     }
   }
 }
@@ -92,6 +130,10 @@ pub struct PlayerInfo {
   pub access_token: Option<String>,
   pub refresh_token: Option<String>,
   pub textures: Vec<Texture>,
+  #[serde(default)]
+  pub auth_state: PlayerAuthState,
+  #[serde(default)]
+  pub available_profiles: Vec<GameProfile>, // only from authlib-injector password login
 }
 
 impl PlayerInfo {
@@ -123,6 +165,8 @@ impl From<Player> for PlayerInfo {
         .auth_server
         .as_ref()
         .map(|server| server.auth_url.clone()),
+      auth_state: player.auth_state,
+      available_profiles: player.available_profiles,
     }
   }
 }
@@ -199,9 +243,45 @@ impl From<AuthServerInfo> for AuthServer {
   }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuthServerCheck {
+  AliRedirection,
+  ApiRoot,
+  SignaturePublicKey,
+  SkinDomains,
+  Authenticate,
+  Validate,
+  Refresh,
+  HasJoined,
+  OpenidFeature,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuthServerCheckStatus {
+  Passed,
+  Failed,
+  Skipped, // the check needs a test account or the server does not declare the feature
+}
+
+structstruck::strike! {
+  #[strikethrough[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]]
+  #[strikethrough[serde(rename_all = "camelCase", deny_unknown_fields)]]
+  pub struct AuthServerDiagnosis {
+    pub auth_url: String,
+    pub checks: Vec<pub struct AuthServerCheckResult {
+      pub check: AuthServerCheck,
+      pub status: AuthServerCheckStatus,
+      pub message: String,
+    }>,
+  }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct AccountInfo {
+  pub schema_version: u32,
   pub players: Vec<PlayerInfo>,
   pub auth_servers: Vec<AuthServerInfo>,
   pub is_oauth_processing: bool,
@@ -210,6 +290,7 @@ pub struct AccountInfo {
 impl Default for AccountInfo {
   fn default() -> Self {
     AccountInfo {
+      schema_version: ACCOUNTS_SCHEMA_VERSION,
       players: vec![],
       auth_servers: PRESET_AUTH_SERVERS
         .iter()
@@ -237,6 +318,11 @@ impl Storage for AccountInfo {
   }
 }
 
+impl SchemaMigration for AccountInfo {
+  const SCHEMA_VERSION: u32 = ACCOUNTS_SCHEMA_VERSION;
+  const MIGRATIONS: &'static [MigrationStep] = &[introduce_schema_version];
+}
+
 #[derive(Debug, Display)]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 pub enum AccountError {
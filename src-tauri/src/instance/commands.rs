@@ -3,29 +3,61 @@ use super::{
     copy_whole_dir, generate_unique_filename, get_files_with_regex, get_subdirectories,
   },
   helpers::{
+    game_options::{get_legacy_options_data_version, match_option_key, GameOptions},
     game_version::{compare_game_versions, get_major_game_version},
     misc::{
       get_instance_game_config, get_instance_subdir_path_by_id, refresh_and_update_instances,
       unify_instance_name,
     },
     mods::common::{get_mod_info_from_dir, get_mod_info_from_jar},
-    resourcepack::{load_resourcepack_from_dir, load_resourcepack_from_zip},
-    server::{load_servers_info_from_path, query_server_status},
-    world::{level_data_to_world_info, load_level_data_from_path},
+    resourcepack::{
+      get_active_resource_packs, load_pack_meta_from_dir, load_pack_meta_from_zip,
+      load_resourcepack_from_dir, load_resourcepack_from_zip, set_active_resource_packs,
+    },
+    schematic::{get_unparsed_schematic_info, load_schematic_info, load_schematic_materials},
+    screenshot::{
+      generate_missing_screenshot_thumbnails, get_screenshot_info, get_screenshot_paths,
+      get_screenshot_thumbnail_dir, move_screenshot, write_screenshots_export,
+    },
+    server::{
+      apply_server_entry, create_server_nbt, get_server_address, load_servers_info_from_path,
+      load_servers_nbt_from_path, normalize_server_address, query_server_status,
+      save_servers_nbt_to_path,
+    },
+    shaderpack::{
+      check_shaderpack_compatibility, detect_shader_loaders, load_shaderpack_from_dir,
+      load_shaderpack_from_zip, load_shaderpack_options, save_shaderpack_options,
+    },
+    world::{
+      apply_level_data_patch, is_world_in_use, level_data_to_world_info, load_level_data_from_path,
+      load_level_nbt_from_path, save_level_nbt_to_path,
+    },
+    world_backup::{
+      get_backup_file_name, get_snapshot_world_name, list_world_backups, restore_world_snapshot,
+    },
   },
   models::{
     misc::{
-      GameServerInfo, Instance, InstanceError, InstanceSubdirType, InstanceSummary, LocalModInfo,
-      ModLoaderType, ResourcePackInfo, SchematicInfo, ScreenshotInfo, ShaderPackInfo,
+      DataPackInfo, DuplicateInstanceOptions, GameOptionsInfo, GameOptionsPatch,
+      GameOptionsTemplate, GameServerEntry, GameServerInfo, Instance, InstanceError,
+      InstanceSubdirType, InstanceSummary, LocalModInfo, ModLoaderType, ResourcePackInfo,
+      SchematicInfo, SchematicMaterial, ScreenshotInfo, ShaderPackInfo,
+    },
+    world::{
+      backup::WorldBackupInfo,
+      base::WorldInfo,
+      level::{LevelData, LevelDataPatch},
     },
-    world::{base::WorldInfo, level::LevelData},
   },
 };
 use crate::{
   error::SJMCLResult,
   instance::{
     helpers::{
+      client_jar::load_resource_pack_format_from_jar,
       client_json::{replace_native_libraries, McClientInfo, PatchesInfo},
+      datapack::{load_world_datapacks, set_world_datapack_enabled},
+      duplicate::get_duplicate_excluded_entries,
       loader::{
         common::{execute_processors, install_mod_loader},
         forge::InstallProfile,
@@ -44,20 +76,29 @@ use crate::{
   },
   partial::{PartialError, PartialUpdate},
   resource::{
-    helpers::misc::get_source_priority_list,
-    models::{GameClientResourceInfo, ModLoaderResourceInfo},
+    helpers::{misc::get_source_priority_list, update_check::get_pinned_resource_key},
+    models::{
+      GameClientResourceInfo, ModLoaderResourceInfo, OtherResourceFileInfo, ResourceUpdateRecords,
+    },
   },
   storage::{load_json_async, save_json_async, Storage},
-  tasks::{commands::schedule_progressive_task_group, download::DownloadParam, PTaskParam},
-  utils::{fs::create_url_shortcut, image::ImageWrapper},
+  tasks::{
+    commands::schedule_progressive_task_group, download::DownloadParam,
+    instance_duplicate::InstanceDuplicateParam, world_backup::WorldBackupParam, PTaskParam,
+  },
+  utils::{
+    fs::create_url_shortcut,
+    image::{load_image_from_dir_async, ImageWrapper},
+  },
 };
 use lazy_static::lazy_static;
 use regex::{Regex, RegexBuilder};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::{sync::Mutex, time::SystemTime};
 use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_http::reqwest;
 use tokio;
 use url::Url;
@@ -101,6 +142,8 @@ pub async fn retrieve_instance_list(app: AppHandle) -> SJMCLResult<Vec<InstanceS
         .is_ge(),
       use_spec_game_config: instance.use_spec_game_config,
       is_version_isolated,
+      update_channel: instance.update_channel.clone(),
+      pinned_resources: instance.pinned_resources.clone(),
     });
   }
   Ok(summary_list)
@@ -136,6 +179,11 @@ pub async fn update_instance_config(
       instance.icon_src = serde_json::from_str::<String>(&value).unwrap_or(value);
     } else if key_path == "starred" {
       instance.starred = value.parse::<bool>()?;
+    } else if key_path == "update_channel" {
+      instance.update_channel = serde_json::from_value(serde_json::Value::String(
+        value.trim_matches('"').to_string(),
+      ))
+      .map_err(|_| PartialError::InvalidType)?;
     } else if key_path == "use_spec_game_config" {
       let value = value.parse::<bool>()?;
       instance.use_spec_game_config = value;
@@ -253,6 +301,48 @@ pub async fn rename_instance(
   Ok(new_path)
 }
 
+#[tauri::command]
+pub async fn duplicate_instance(
+  app: AppHandle,
+  instance_id: String,
+  new_name: String,
+  options: DuplicateInstanceOptions,
+) -> SJMCLResult<PathBuf> {
+  let instance = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let state = binding.lock()?;
+    state
+      .get(&instance_id)
+      .cloned()
+      .ok_or(InstanceError::InstanceNotFoundByID)?
+  };
+  if !sanitize_filename::is_sanitized(&new_name) {
+    return Err(InstanceError::InvalidNameError.into());
+  }
+  let versions_dir = instance
+    .version_path
+    .parent()
+    .ok_or(InstanceError::InvalidSourcePath)?;
+  let dest = versions_dir.join(&new_name);
+  if dest.exists() {
+    return Err(InstanceError::ConflictNameError.into());
+  }
+
+  schedule_progressive_task_group(
+    app.clone(),
+    format!("instance-duplicate?{}", new_name),
+    vec![PTaskParam::InstanceDuplicate(InstanceDuplicateParam {
+      src: instance.version_path.clone(),
+      dest: dest.clone(),
+      excluded: get_duplicate_excluded_entries(&options),
+      filename: None,
+    })],
+    true,
+  )
+  .await?;
+  Ok(dest)
+}
+
 #[tauri::command]
 pub fn copy_resource_to_instances(
   app: AppHandle,
@@ -415,6 +505,8 @@ pub async fn retrieve_game_server_list(
       players_max: 0,
       players_online: 0,
       online: false,
+      accept_textures: server.accept_textures.map(|v| v != 0),
+      hidden: server.hidden.map_or(false, |v| v != 0),
     });
   }
 
@@ -453,6 +545,125 @@ pub async fn retrieve_game_server_list(
   Ok(game_servers)
 }
 
+fn get_instance_servers_path(app: &AppHandle, instance_id: &str) -> SJMCLResult<PathBuf> {
+  let root_dir = get_instance_subdir_path_by_id(app, instance_id, &InstanceSubdirType::Root)
+    .ok_or(InstanceError::InstanceNotFoundByID)?;
+  Ok(root_dir.join("servers.dat"))
+}
+
+#[tauri::command]
+pub fn add_game_server(
+  app: AppHandle,
+  instance_id: String,
+  server: GameServerEntry,
+  index: Option<usize>, // appended if not given
+) -> SJMCLResult<()> {
+  let nbt_path = get_instance_servers_path(&app, &instance_id)?;
+  let mut servers = load_servers_nbt_from_path(&nbt_path)?;
+  let address = normalize_server_address(&server.ip);
+  if servers
+    .iter()
+    .any(|s| normalize_server_address(get_server_address(s)) == address)
+  {
+    return Err(InstanceError::ServerAlreadyExistError.into());
+  }
+  let index = index.unwrap_or(servers.len()).min(servers.len());
+  servers.insert(index, create_server_nbt(&server));
+  save_servers_nbt_to_path(&nbt_path, &servers)
+}
+
+#[tauri::command]
+pub fn update_game_server(
+  app: AppHandle,
+  instance_id: String,
+  index: usize,
+  server: GameServerEntry,
+) -> SJMCLResult<()> {
+  let nbt_path = get_instance_servers_path(&app, &instance_id)?;
+  let mut servers = load_servers_nbt_from_path(&nbt_path)?;
+  let address = normalize_server_address(&server.ip);
+  if servers
+    .iter()
+    .enumerate()
+    .any(|(i, s)| i != index && normalize_server_address(get_server_address(s)) == address)
+  {
+    return Err(InstanceError::ServerAlreadyExistError.into());
+  }
+  let compound = servers
+    .get_mut(index)
+    .ok_or(InstanceError::ServerNotExistError)?;
+  apply_server_entry(compound, &server);
+  save_servers_nbt_to_path(&nbt_path, &servers)
+}
+
+#[tauri::command]
+pub fn delete_game_server(app: AppHandle, instance_id: String, index: usize) -> SJMCLResult<()> {
+  let nbt_path = get_instance_servers_path(&app, &instance_id)?;
+  let mut servers = load_servers_nbt_from_path(&nbt_path)?;
+  if index >= servers.len() {
+    return Err(InstanceError::ServerNotExistError.into());
+  }
+  servers.remove(index);
+  save_servers_nbt_to_path(&nbt_path, &servers)
+}
+
+#[tauri::command]
+pub fn reorder_game_servers(
+  app: AppHandle,
+  instance_id: String,
+  order: Vec<usize>, // the current indices, in the new order
+) -> SJMCLResult<()> {
+  let nbt_path = get_instance_servers_path(&app, &instance_id)?;
+  let servers = load_servers_nbt_from_path(&nbt_path)?;
+  let mut sorted_order = order.clone();
+  sorted_order.sort_unstable();
+  if !sorted_order.into_iter().eq(0..servers.len()) {
+    return Err(InstanceError::ServerNotExistError.into());
+  }
+  let reordered: Vec<_> = order.iter().map(|&i| servers[i].clone()).collect();
+  save_servers_nbt_to_path(&nbt_path, &reordered)
+}
+
+/// Appends the servers to each of the instances, skipping the addresses already in the list.
+/// Returns the number of servers added to each instance.
+#[tauri::command]
+pub fn apply_game_server_list(
+  app: AppHandle,
+  instance_ids: Vec<String>,
+  servers: Vec<GameServerEntry>,
+) -> SJMCLResult<HashMap<String, usize>> {
+  let mut added_counts = HashMap::new();
+  // instances without version isolation share the same servers.dat
+  let mut applied_paths = HashMap::<PathBuf, usize>::new();
+  for instance_id in instance_ids {
+    let nbt_path = get_instance_servers_path(&app, &instance_id)?;
+    if let Some(&count) = applied_paths.get(&nbt_path) {
+      added_counts.insert(instance_id, count);
+      continue;
+    }
+
+    let mut existing_servers = load_servers_nbt_from_path(&nbt_path)?;
+    let mut addresses: HashSet<String> = existing_servers
+      .iter()
+      .map(|s| normalize_server_address(get_server_address(s)))
+      .collect();
+    let mut count = 0;
+    for server in &servers {
+      if !addresses.insert(normalize_server_address(&server.ip)) {
+        continue;
+      }
+      existing_servers.push(create_server_nbt(server));
+      count += 1;
+    }
+    if count > 0 {
+      save_servers_nbt_to_path(&nbt_path, &existing_servers)?;
+    }
+    applied_paths.insert(nbt_path, count);
+    added_counts.insert(instance_id, count);
+  }
+  Ok(added_counts)
+}
+
 #[tauri::command]
 pub async fn retrieve_local_mod_list(
   app: AppHandle,
@@ -505,6 +716,17 @@ pub async fn retrieve_local_mod_list(
   Ok(mod_infos)
 }
 
+fn get_instance_resource_pack_format(app: &AppHandle, instance_id: &String) -> Option<i64> {
+  let jar_path = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let state = binding.lock().ok()?;
+    let instance = state.get(instance_id)?;
+    instance.version_path.join(format!("{}.jar", instance.name))
+  };
+  let mut jar = ZipArchive::new(fs::File::open(jar_path).ok()?).ok()?;
+  load_resource_pack_format_from_jar(&mut jar)
+}
+
 #[tauri::command]
 pub async fn retrieve_resource_pack_list(
   app: AppHandle,
@@ -516,45 +738,228 @@ pub async fn retrieve_resource_pack_list(
       Some(path) => path,
       None => return Ok(Vec::new()),
     };
-  let mut info_list: Vec<ResourcePackInfo> = Vec::new();
+  let options = get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Root)
+    .and_then(|root_dir| GameOptions::load(&root_dir.join("options.txt")).ok())
+    .unwrap_or_default();
+  let active_packs = get_active_resource_packs(&options);
+  let expected_format = get_instance_resource_pack_format(&app, &instance_id);
 
+  let mut packs = Vec::new();
   let valid_extensions = RegexBuilder::new(r"\.zip$")
     .case_insensitive(true)
     .build()
     .unwrap();
-
   for path in get_files_with_regex(&resource_packs_dir, &valid_extensions).unwrap_or(vec![]) {
-    if let Ok((description, icon_src)) = load_resourcepack_from_zip(&path) {
-      let name = match path.file_stem() {
-        Some(stem) => stem.to_string_lossy().to_string(),
-        None => String::new(),
-      };
-      info_list.push(ResourcePackInfo {
-        name,
-        description,
-        icon_src: icon_src.map(ImageWrapper::from),
-        file_path: path.clone(),
-      });
+    if let Ok((meta, icon_src)) = load_pack_meta_from_zip(&path) {
+      packs.push((path, meta, icon_src));
     }
   }
-
   for path in get_subdirectories(&resource_packs_dir).unwrap_or(vec![]) {
-    if let Ok((description, icon_src)) = load_resourcepack_from_dir(&path).await {
-      let name = match path.file_stem() {
-        Some(stem) => stem.to_string_lossy().to_string(),
-        None => String::new(),
-      };
-      info_list.push(ResourcePackInfo {
-        name,
-        description,
-        icon_src: icon_src.map(ImageWrapper::from),
-        file_path: path.clone(),
-      });
+    if let Ok((meta, icon_src)) = load_pack_meta_from_dir(&path).await {
+      packs.push((path, meta, icon_src));
     }
   }
+
+  let mut info_list: Vec<ResourcePackInfo> = Vec::new();
+  for (path, meta, icon_src) in packs {
+    let file_name = path
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+    let name = match path.file_stem() {
+      Some(stem) => stem.to_string_lossy().to_string(),
+      None => String::new(),
+    };
+    let potential_incompatibility = match expected_format {
+      Some(format) => meta.pack_format.is_some() && !meta.supports_format(format),
+      None => false,
+    };
+    info_list.push(ResourcePackInfo {
+      name,
+      description: meta.description,
+      icon_src: icon_src.map(ImageWrapper::from),
+      file_path: path,
+      pack_format: meta.pack_format,
+      supported_formats: meta.supported_formats,
+      overlays: meta.overlays,
+      enabled: active_packs.contains(&file_name),
+      potential_incompatibility,
+    });
+  }
   Ok(info_list)
 }
 
+#[tauri::command]
+pub fn retrieve_active_resource_packs(
+  app: AppHandle,
+  instance_id: String,
+) -> SJMCLResult<Vec<String>> {
+  let root_dir = get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Root)
+    .ok_or(InstanceError::InstanceNotFoundByID)?;
+  let options = GameOptions::load(&root_dir.join("options.txt"))?;
+  Ok(get_active_resource_packs(&options))
+}
+
+#[tauri::command]
+pub async fn update_active_resource_packs(
+  app: AppHandle,
+  instance_id: String,
+  file_names: Vec<String>, // from the highest priority to the lowest
+) -> SJMCLResult<()> {
+  let root_dir = get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Root)
+    .ok_or(InstanceError::InstanceNotFoundByID)?;
+  let incompatible_packs: Vec<String> =
+    retrieve_resource_pack_list(app.clone(), instance_id.clone())
+      .await?
+      .into_iter()
+      .filter(|info| info.potential_incompatibility)
+      .filter_map(|info| {
+        info
+          .file_path
+          .file_name()
+          .map(|name| name.to_string_lossy().to_string())
+      })
+      .collect();
+  let expected_format = get_instance_resource_pack_format(&app, &instance_id);
+
+  let options_path = root_dir.join("options.txt");
+  let mut options = GameOptions::load(&options_path)?;
+  set_active_resource_packs(
+    &mut options,
+    &file_names,
+    &incompatible_packs,
+    expected_format,
+  );
+  options.save(&options_path)
+}
+
+#[tauri::command]
+pub fn retrieve_game_options(app: AppHandle, instance_id: String) -> SJMCLResult<GameOptionsInfo> {
+  let root_dir = get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Root)
+    .ok_or(InstanceError::InstanceNotFoundByID)?;
+  Ok(GameOptions::load(&root_dir.join("options.txt"))?.to_info())
+}
+
+#[tauri::command]
+pub fn update_game_options(
+  app: AppHandle,
+  instance_id: String,
+  patch: GameOptionsPatch,
+) -> SJMCLResult<GameOptionsInfo> {
+  let root_dir = get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Root)
+    .ok_or(InstanceError::InstanceNotFoundByID)?;
+  let options_path = root_dir.join("options.txt");
+  let mut options = GameOptions::load(&options_path)?;
+  options.apply_patch(&patch);
+  options.save(&options_path)?;
+  Ok(options.to_info())
+}
+
+#[tauri::command]
+pub fn retrieve_game_options_template() -> SJMCLResult<GameOptionsTemplate> {
+  Ok(GameOptionsTemplate::load().unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn save_game_options_template(
+  app: AppHandle,
+  instance_id: String,
+  keys: Vec<String>, // keys or prefixes ending with `*`, all the keys if empty
+) -> SJMCLResult<GameOptionsTemplate> {
+  let root_dir = get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Root)
+    .ok_or(InstanceError::InstanceNotFoundByID)?;
+  let options = GameOptions::load(&root_dir.join("options.txt"))?;
+  if options.entries().is_empty() {
+    return Err(InstanceError::FileNotFoundError.into());
+  }
+  let instance_name = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let state = binding.lock()?;
+    state
+      .get(&instance_id)
+      .map(|instance| instance.name.clone())
+  };
+
+  let mut template = GameOptionsTemplate::load().unwrap_or_default();
+  template.entries = options
+    .entries()
+    .iter()
+    .filter(|(key, _)| keys.is_empty() || keys.iter().any(|k| match_option_key(k, key)))
+    .cloned()
+    .collect();
+  template.data_version = options.data_version();
+  template.source_instance_name = instance_name;
+  template.save()?;
+  Ok(template)
+}
+
+#[tauri::command]
+pub fn update_game_options_template_settings(
+  apply_to_new_instances: bool,
+  sync_keys: Vec<String>,
+) -> SJMCLResult<()> {
+  let mut template = GameOptionsTemplate::load().unwrap_or_default();
+  template.apply_to_new_instances = apply_to_new_instances;
+  template.sync_keys = sync_keys;
+  template.save()?;
+  Ok(())
+}
+
+/// Syncs the `sync_keys` of the template to the given instances (all if `None`),
+/// returns the number of the `options.txt` files updated.
+#[tauri::command]
+pub async fn sync_game_options_template(
+  app: AppHandle,
+  instance_ids: Option<Vec<String>>,
+) -> SJMCLResult<usize> {
+  let template = GameOptionsTemplate::load().unwrap_or_default();
+  if template.entries.is_empty() || template.sync_keys.is_empty() {
+    return Ok(0);
+  }
+  let instances: Vec<Instance> = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let state = binding.lock()?;
+    state
+      .values()
+      .filter(|instance| {
+        instance_ids
+          .as_ref()
+          .map_or(true, |ids| ids.contains(&instance.id))
+      })
+      .cloned()
+      .collect()
+  };
+
+  // instances without version isolation share the same `options.txt`
+  let mut synced_paths = HashSet::new();
+  for instance in instances {
+    let Some(root_dir) = get_instance_subdir_paths(&app, &instance, &[&InstanceSubdirType::Root])
+      .and_then(|paths| paths.into_iter().next())
+    else {
+      continue;
+    };
+    let options_path = root_dir.join("options.txt");
+    if !synced_paths.insert(options_path.clone()) {
+      continue;
+    }
+    let mut options = match GameOptions::load(&options_path) {
+      Ok(options) => options,
+      Err(e) => {
+        log::error!("failed to sync options of {}: {:?}", instance.id, e);
+        synced_paths.remove(&options_path);
+        continue;
+      }
+    };
+    let target_version = get_legacy_options_data_version(&app, &instance.version).await;
+    options.apply_template(&template, &template.sync_keys, target_version);
+    if let Err(e) = options.save(&options_path) {
+      log::error!("failed to sync options of {}: {:?}", instance.id, e);
+      synced_paths.remove(&options_path);
+    }
+  }
+  Ok(synced_paths.len())
+}
+
 #[tauri::command]
 pub async fn retrieve_server_resource_pack_list(
   app: AppHandle,
@@ -586,6 +991,7 @@ pub async fn retrieve_server_resource_pack_list(
         description,
         icon_src: icon_src.map(ImageWrapper::from),
         file_path: path.clone(),
+        ..Default::default()
       });
     }
   }
@@ -602,6 +1008,7 @@ pub async fn retrieve_server_resource_pack_list(
         description,
         icon_src: icon_src.map(ImageWrapper::from),
         file_path: path.clone(),
+        ..Default::default()
       });
     }
   }
@@ -622,27 +1029,31 @@ pub fn retrieve_schematic_list(
   if !schematics_dir.exists() {
     return Ok(Vec::new());
   }
-  let valid_extensions = RegexBuilder::new(r"\.(litematic|schematic)$")
+  let valid_extensions = RegexBuilder::new(r"\.(litematic|schem|schematic|nbt)$")
     .case_insensitive(true)
     .build()
     .unwrap();
   let mut schematic_list = Vec::new();
   for schematic_path in get_files_with_regex(schematics_dir.as_path(), &valid_extensions)? {
-    schematic_list.push(SchematicInfo {
-      name: schematic_path
-        .file_stem()
-        .unwrap()
-        .to_string_lossy()
-        .to_string(),
-      file_path: schematic_path,
-    });
+    match load_schematic_info(&schematic_path) {
+      Ok(info) => schematic_list.push(info),
+      Err(_) => {
+        log::error!("Failed to parse schematic {}", schematic_path.display());
+        schematic_list.push(get_unparsed_schematic_info(&schematic_path));
+      }
+    }
   }
 
   Ok(schematic_list)
 }
 
 #[tauri::command]
-pub fn retrieve_shader_pack_list(
+pub fn retrieve_schematic_materials(file_path: PathBuf) -> SJMCLResult<Vec<SchematicMaterial>> {
+  load_schematic_materials(&file_path)
+}
+
+#[tauri::command]
+pub async fn retrieve_shader_pack_list(
   app: AppHandle,
   instance_id: String,
 ) -> SJMCLResult<Vec<ShaderPackInfo>> {
@@ -662,16 +1073,87 @@ pub fn retrieve_shader_pack_list(
     .build()
     .unwrap();
   let mut shaderpack_list = Vec::new();
-  for path in get_files_with_regex(shaderpacks_dir, &valid_extensions)? {
-    shaderpack_list.push(ShaderPackInfo {
-      file_name: path.file_stem().unwrap().to_string_lossy().to_string(),
-      file_path: path,
-    });
+  for path in get_files_with_regex(&shaderpacks_dir, &valid_extensions)? {
+    match load_shaderpack_from_zip(&path) {
+      Ok(info) => shaderpack_list.push(info),
+      Err(e) => log::error!("Failed to load shader pack {}: {}", path.display(), e.0),
+    }
+  }
+  for path in get_subdirectories(&shaderpacks_dir)? {
+    if let Ok(info) = load_shaderpack_from_dir(&path) {
+      shaderpack_list.push(info);
+    }
+  }
+
+  // check whether the packs can be loaded by the installed shader loaders
+  let client_path = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let state = binding.lock()?;
+    let instance = state
+      .get(&instance_id)
+      .ok_or(InstanceError::InstanceNotFoundByID)?;
+    instance
+      .version_path
+      .join(format!("{}.json", instance.name))
+  };
+  let client_info = load_json_async::<McClientInfo>(&client_path).await.ok();
+  // best-effort, assume no shader loader if the mods can not be listed
+  let mods = retrieve_local_mod_list(app.clone(), instance_id.clone())
+    .await
+    .unwrap_or_default();
+  let loaders = detect_shader_loaders(&mods, client_info.as_ref());
+  for info in shaderpack_list.iter_mut() {
+    check_shaderpack_compatibility(info, &loaders);
   }
 
   Ok(shaderpack_list)
 }
 
+// only the packs directly under the shaderpacks folder of the instance can be configured
+fn get_shader_pack_path(
+  app: &AppHandle,
+  instance_id: &str,
+  file_name: &str,
+) -> SJMCLResult<PathBuf> {
+  if !sanitize_filename::is_sanitized(file_name) {
+    return Err(InstanceError::InvalidNameError.into());
+  }
+  let shaderpacks_dir =
+    get_instance_subdir_path_by_id(app, instance_id, &InstanceSubdirType::ShaderPacks)
+      .ok_or(InstanceError::InstanceNotFoundByID)?
+      .canonicalize()
+      .map_err(|_| InstanceError::FileNotFoundError)?;
+  let path = shaderpacks_dir
+    .join(file_name)
+    .canonicalize()
+    .map_err(|_| InstanceError::FileNotFoundError)?;
+  if path.parent() != Some(shaderpacks_dir.as_path()) {
+    return Err(InstanceError::InvalidSourcePath.into());
+  }
+  Ok(path)
+}
+
+#[tauri::command]
+pub fn retrieve_shader_pack_options(
+  app: AppHandle,
+  instance_id: String,
+  file_name: String,
+) -> SJMCLResult<BTreeMap<String, String>> {
+  let file_path = get_shader_pack_path(&app, &instance_id, &file_name)?;
+  load_shaderpack_options(&file_path)
+}
+
+#[tauri::command]
+pub fn save_shader_pack_options(
+  app: AppHandle,
+  instance_id: String,
+  file_name: String,
+  options: BTreeMap<String, String>,
+) -> SJMCLResult<()> {
+  let file_path = get_shader_pack_path(&app, &instance_id, &file_name)?;
+  save_shaderpack_options(&file_path, &options)
+}
+
 #[tauri::command]
 pub fn retrieve_screenshot_list(
   app: AppHandle,
@@ -683,33 +1165,151 @@ pub fn retrieve_screenshot_list(
       None => return Ok(Vec::new()),
     };
 
-  if !screenshots_dir.exists() {
-    return Ok(Vec::new());
-  }
-
-  // The default screenshot format in Minecraft is PNG. For broader compatibility, JPG and JPEG formats are also included here.
-  let valid_extensions = RegexBuilder::new(r"\.(jpg|jpeg|png)$")
-    .case_insensitive(true)
-    .build()
-    .unwrap();
+  let thumbnail_dir = get_screenshot_thumbnail_dir(&app)?;
   let mut screenshot_list = Vec::new();
-  for path in get_files_with_regex(screenshots_dir, &valid_extensions)? {
-    let metadata = path.metadata().unwrap();
-    let modified_time = metadata.modified().unwrap();
-    let timestamp = modified_time
-      .duration_since(SystemTime::UNIX_EPOCH)
-      .unwrap()
-      .as_secs();
-    screenshot_list.push(ScreenshotInfo {
-      file_name: path.file_stem().unwrap().to_string_lossy().to_string(),
-      file_path: path,
-      time: timestamp,
-    });
+  for path in get_screenshot_paths(&screenshots_dir)? {
+    match get_screenshot_info(&path, &thumbnail_dir) {
+      Ok(info) => screenshot_list.push(info),
+      Err(e) => log::error!("Failed to read screenshot {}: {}", path.display(), e.0),
+    }
   }
 
+  generate_missing_screenshot_thumbnails(&app, &screenshot_list);
   Ok(screenshot_list)
 }
 
+// only accept the files right in the screenshots folder of the instance
+fn check_screenshot_paths(
+  app: &AppHandle,
+  instance_id: &str,
+  file_paths: &[PathBuf],
+) -> SJMCLResult<()> {
+  let screenshots_dir =
+    get_instance_subdir_path_by_id(app, instance_id, &InstanceSubdirType::Screenshots)
+      .ok_or(InstanceError::InstanceNotFoundByID)?
+      .canonicalize()
+      .map_err(|_| InstanceError::FileNotFoundError)?;
+  for path in file_paths {
+    if !path.is_file() {
+      return Err(InstanceError::FileNotFoundError.into());
+    }
+    let path = path
+      .canonicalize()
+      .map_err(|_| InstanceError::FileNotFoundError)?;
+    if path.parent() != Some(screenshots_dir.as_path()) {
+      return Err(InstanceError::InvalidSourcePath.into());
+    }
+  }
+  Ok(())
+}
+
+#[tauri::command]
+pub fn delete_screenshots(
+  app: AppHandle,
+  instance_id: String,
+  file_paths: Vec<PathBuf>,
+) -> SJMCLResult<()> {
+  check_screenshot_paths(&app, &instance_id, &file_paths)?;
+  let thumbnail_dir = get_screenshot_thumbnail_dir(&app)?;
+  for path in file_paths {
+    if let Ok(info) = get_screenshot_info(&path, &thumbnail_dir) {
+      let _ = fs::remove_file(info.thumbnail_path);
+    }
+    fs::remove_file(&path)?;
+  }
+  Ok(())
+}
+
+#[tauri::command]
+pub fn move_screenshots(
+  app: AppHandle,
+  instance_id: String,
+  file_paths: Vec<PathBuf>,
+  target_instance_id: String,
+) -> SJMCLResult<Vec<PathBuf>> {
+  check_screenshot_paths(&app, &instance_id, &file_paths)?;
+  let target_dir =
+    get_instance_subdir_path_by_id(&app, &target_instance_id, &InstanceSubdirType::Screenshots)
+      .ok_or(InstanceError::InstanceNotFoundByID)?;
+
+  let mut moved_paths = Vec::new();
+  for path in file_paths {
+    moved_paths.push(move_screenshot(&path, &target_dir)?);
+  }
+  Ok(moved_paths)
+}
+
+#[tauri::command]
+pub async fn copy_screenshot_to_clipboard(app: AppHandle, file_path: PathBuf) -> SJMCLResult<()> {
+  let image = load_image_from_dir_async(&file_path)
+    .await
+    .ok_or(InstanceError::ScreenshotParseError)?;
+  let (width, height) = image.dimensions();
+  app
+    .clipboard()
+    .write_image(&tauri::image::Image::new_owned(
+      image.into_raw(),
+      width,
+      height,
+    ))?;
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn export_screenshots(
+  app: AppHandle,
+  instance_ids: Option<Vec<String>>, // all instances if None
+  start_time: Option<u64>,
+  end_time: Option<u64>,
+  save_path: PathBuf,
+  as_zip: bool,
+) -> SJMCLResult<usize> {
+  let instances: Vec<Instance> = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let state = binding.lock()?;
+    state
+      .values()
+      .filter(|instance| {
+        instance_ids
+          .as_ref()
+          .map_or(true, |ids| ids.contains(&instance.id))
+      })
+      .cloned()
+      .collect()
+  };
+
+  let mut screenshots = Vec::new();
+  let mut visited_dirs = Vec::new();
+  for instance in instances {
+    let Some(screenshots_dir) =
+      get_instance_subdir_paths(&app, &instance, &[&InstanceSubdirType::Screenshots])
+        .and_then(|mut paths| paths.pop())
+    else {
+      continue;
+    };
+    // instances without version isolation share the same screenshots folder
+    if visited_dirs.contains(&screenshots_dir) {
+      continue;
+    }
+    for path in get_screenshot_paths(&screenshots_dir)? {
+      let time = path
+        .metadata()?
+        .modified()?
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+      if start_time.map_or(true, |t| time >= t) && end_time.map_or(true, |t| time < t) {
+        screenshots.push((instance.name.clone(), path));
+      }
+    }
+    visited_dirs.push(screenshots_dir);
+  }
+
+  let count = screenshots.len();
+  tokio::task::spawn_blocking(move || write_screenshots_export(&screenshots, &save_path, as_zip))
+    .await??;
+  Ok(count)
+}
+
 lazy_static! {
   static ref RENAME_LOCK: Mutex<()> = Mutex::new(());
   static ref RENAME_REGEX: Regex = RegexBuilder::new(r"^(.*?)(\.disabled)*$")
@@ -755,6 +1355,42 @@ pub fn toggle_mod_by_extension(file_path: PathBuf, enable: bool) -> SJMCLResult<
   Ok(())
 }
 
+#[tauri::command]
+pub async fn toggle_resource_pinned(
+  app: AppHandle,
+  instance_id: String,
+  file_path: PathBuf,
+  pinned: bool,
+) -> SJMCLResult<()> {
+  let key = get_pinned_resource_key(&file_path).ok_or(InstanceError::InvalidSourcePath)?;
+  let instance = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let mut state = binding.lock()?;
+    let instance = state
+      .get_mut(&instance_id)
+      .ok_or(InstanceError::InstanceNotFoundByID)?;
+    instance
+      .pinned_resources
+      .retain(|pinned_key| pinned_key != &key);
+    if pinned {
+      instance.pinned_resources.push(key);
+    }
+    instance.clone()
+  };
+  instance.save_json_cfg().await?;
+
+  // a pinned resource is no longer reported as outdated
+  if pinned {
+    let binding = app.state::<Mutex<ResourceUpdateRecords>>();
+    let mut records = binding.lock()?;
+    if let Some(updates) = records.instances.get_mut(&instance_id) {
+      updates.retain(|update| update.file_path != file_path);
+      records.save()?;
+    }
+  }
+  Ok(())
+}
+
 #[tauri::command]
 pub async fn retrieve_world_details(
   app: AppHandle,
@@ -777,6 +1413,273 @@ pub async fn retrieve_world_details(
   }
 }
 
+#[tauri::command]
+pub async fn update_world_level_data(
+  app: AppHandle,
+  instance_id: String,
+  world_name: String,
+  patch: LevelDataPatch,
+) -> SJMCLResult<LevelData> {
+  let worlds_dir =
+    match get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Saves) {
+      Some(path) => path,
+      None => return Err(InstanceError::WorldNotExistError.into()),
+    };
+  if !sanitize_filename::is_sanitized(&world_name) {
+    return Err(InstanceError::InvalidNameError.into());
+  }
+  let world_dir = worlds_dir.join(&world_name);
+  let level_path = world_dir.join("level.dat");
+  if !level_path.exists() {
+    return Err(InstanceError::LevelNotExistError.into());
+  }
+  // the game will overwrite level.dat on exit, so never edit an opened world
+  if is_world_in_use(&app, &instance_id, &world_dir) {
+    return Err(InstanceError::WorldInUseError.into());
+  }
+
+  let (mut root, root_name) = load_level_nbt_from_path(&level_path)?;
+  apply_level_data_patch(&mut root, &patch)?;
+  save_level_nbt_to_path(&level_path, &root_name, &root)?;
+
+  load_level_data_from_path(&level_path)
+    .await
+    .map_err(|_| InstanceError::LevelParseError.into())
+}
+
+#[tauri::command]
+pub async fn create_world_backup(
+  app: AppHandle,
+  instance_id: String,
+  world_name: String,
+) -> SJMCLResult<()> {
+  let dirs = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let state = binding.lock()?;
+    let instance = state
+      .get(&instance_id)
+      .ok_or(InstanceError::InstanceNotFoundByID)?;
+    get_instance_subdir_paths(
+      &app,
+      instance,
+      &[&InstanceSubdirType::Saves, &InstanceSubdirType::Backups],
+    )
+    .ok_or(InstanceError::InstanceNotFoundByID)?
+  };
+  let [saves_dir, backups_dir] = dirs.as_slice() else {
+    return Err(InstanceError::InstanceNotFoundByID.into());
+  };
+
+  if !sanitize_filename::is_sanitized(&world_name) {
+    return Err(InstanceError::InvalidNameError.into());
+  }
+  let world_dir = saves_dir.join(&world_name);
+  if !world_dir.join("level.dat").exists() {
+    return Err(InstanceError::WorldNotExistError.into());
+  }
+  if is_world_in_use(&app, &instance_id, &world_dir) {
+    return Err(InstanceError::WorldInUseError.into());
+  }
+
+  schedule_progressive_task_group(
+    app.clone(),
+    format!("world-backup?{}", world_name),
+    vec![PTaskParam::WorldBackup(WorldBackupParam {
+      instance_id,
+      src: world_dir,
+      dest: backups_dir.join(get_backup_file_name(&world_name)),
+      filename: None,
+    })],
+    true,
+  )
+  .await?;
+  Ok(())
+}
+
+#[tauri::command]
+pub fn retrieve_world_backup_list(
+  app: AppHandle,
+  instance_id: String,
+  world_name: Option<String>,
+) -> SJMCLResult<Vec<WorldBackupInfo>> {
+  let backups_dir =
+    match get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Backups) {
+      Some(path) => path,
+      None => return Ok(Vec::new()),
+    };
+  list_world_backups(&backups_dir, world_name.as_deref())
+}
+
+#[tauri::command]
+pub async fn restore_world_backup(
+  app: AppHandle,
+  instance_id: String,
+  backup_path: PathBuf,
+  new_world_name: Option<String>,
+) -> SJMCLResult<PathBuf> {
+  let worlds_dir =
+    match get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Saves) {
+      Some(path) => path,
+      None => return Err(InstanceError::InstanceNotFoundByID.into()),
+    };
+  let backups_dir =
+    match get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Backups) {
+      Some(path) => path,
+      None => return Err(InstanceError::InstanceNotFoundByID.into()),
+    };
+  if !backup_path.is_file() || backup_path.parent() != Some(backups_dir.as_path()) {
+    return Err(InstanceError::BackupNotExistError.into());
+  }
+
+  let target_name = match new_world_name {
+    // restore as a new world, never overwrite an existing one
+    Some(name) => {
+      if !sanitize_filename::is_sanitized(&name) {
+        return Err(InstanceError::InvalidNameError.into());
+      }
+      if worlds_dir.join(&name).exists() {
+        return Err(InstanceError::ConflictNameError.into());
+      }
+      name
+    }
+    // restore to the original world
+    None => {
+      let name = get_snapshot_world_name(&backup_path)?;
+      if !sanitize_filename::is_sanitized(&name) {
+        return Err(InstanceError::InvalidNameError.into());
+      }
+      if is_world_in_use(&app, &instance_id, &worlds_dir.join(&name)) {
+        return Err(InstanceError::WorldInUseError.into());
+      }
+      name
+    }
+  };
+
+  tokio::task::spawn_blocking(move || {
+    restore_world_snapshot(&backup_path, &worlds_dir, &target_name)
+  })
+  .await?
+}
+
+#[tauri::command]
+pub fn delete_world_backup(
+  app: AppHandle,
+  instance_id: String,
+  backup_path: PathBuf,
+) -> SJMCLResult<()> {
+  let backups_dir =
+    match get_instance_subdir_path_by_id(&app, &instance_id, &InstanceSubdirType::Backups) {
+      Some(path) => path,
+      None => return Err(InstanceError::InstanceNotFoundByID.into()),
+    };
+  if !backup_path.is_file() || backup_path.parent() != Some(backups_dir.as_path()) {
+    return Err(InstanceError::BackupNotExistError.into());
+  }
+  fs::remove_file(&backup_path)?;
+  Ok(())
+}
+
+fn get_world_dir(app: &AppHandle, instance_id: &str, world_name: &str) -> SJMCLResult<PathBuf> {
+  if !sanitize_filename::is_sanitized(world_name) {
+    return Err(InstanceError::InvalidNameError.into());
+  }
+  let worlds_dir = get_instance_subdir_path_by_id(app, instance_id, &InstanceSubdirType::Saves)
+    .ok_or(InstanceError::InstanceNotFoundByID)?;
+  let world_dir = worlds_dir.join(world_name);
+  if !world_dir.join("level.dat").exists() {
+    return Err(InstanceError::WorldNotExistError.into());
+  }
+  Ok(world_dir)
+}
+
+#[tauri::command]
+pub async fn retrieve_world_datapack_list(
+  app: AppHandle,
+  instance_id: String,
+  world_name: String,
+) -> SJMCLResult<Vec<DataPackInfo>> {
+  let world_dir = get_world_dir(&app, &instance_id, &world_name)?;
+  load_world_datapacks(&world_dir).await
+}
+
+#[tauri::command]
+pub fn add_world_datapack(
+  app: AppHandle,
+  instance_id: String,
+  world_name: String,
+  src_path: PathBuf,
+) -> SJMCLResult<PathBuf> {
+  let world_dir = get_world_dir(&app, &instance_id, &world_name)?;
+  let file_name = src_path
+    .file_name()
+    .ok_or(InstanceError::InvalidSourcePath)?;
+  if !src_path.join("pack.mcmeta").is_file() && load_pack_meta_from_zip(&src_path).is_err() {
+    return Err(InstanceError::InvalidSourcePath.into());
+  }
+
+  let datapacks_dir = world_dir.join("datapacks");
+  fs::create_dir_all(&datapacks_dir).map_err(|_| InstanceError::FolderCreationFailed)?;
+  let dest_path = generate_unique_filename(&datapacks_dir, file_name);
+  if src_path.is_dir() {
+    copy_whole_dir(&src_path, &dest_path).map_err(|_| InstanceError::FileCopyFailed)?;
+  } else {
+    fs::copy(&src_path, &dest_path).map_err(|_| InstanceError::FileCopyFailed)?;
+  }
+  Ok(dest_path)
+}
+
+#[tauri::command]
+pub async fn download_world_datapack(
+  app: AppHandle,
+  instance_id: String,
+  world_name: String,
+  file_info: OtherResourceFileInfo,
+) -> SJMCLResult<()> {
+  let world_dir = get_world_dir(&app, &instance_id, &world_name)?;
+  // the file name comes from the remote api, never let it escape the datapacks folder
+  if !sanitize_filename::is_sanitized(&file_info.file_name) {
+    return Err(InstanceError::InvalidNameError.into());
+  }
+  let datapacks_dir = world_dir.join("datapacks");
+  fs::create_dir_all(&datapacks_dir).map_err(|_| InstanceError::FolderCreationFailed)?;
+
+  schedule_progressive_task_group(
+    app.clone(),
+    format!("datapack?{}", file_info.name),
+    vec![PTaskParam::Download(DownloadParam {
+      src: Url::parse(&file_info.download_url).map_err(|_| InstanceError::NetworkError)?,
+      dest: datapacks_dir.join(&file_info.file_name),
+      filename: Some(file_info.file_name.clone()),
+      sha1: (!file_info.sha1.is_empty()).then_some(file_info.sha1.clone()),
+    })],
+    true,
+  )
+  .await?;
+  Ok(())
+}
+
+#[tauri::command]
+pub fn toggle_world_datapack(
+  app: AppHandle,
+  instance_id: String,
+  world_name: String,
+  file_name: String,
+  enabled: bool,
+) -> SJMCLResult<()> {
+  let world_dir = get_world_dir(&app, &instance_id, &world_name)?;
+  if !sanitize_filename::is_sanitized(&file_name) {
+    return Err(InstanceError::InvalidNameError.into());
+  }
+  if !world_dir.join("datapacks").join(&file_name).exists() {
+    return Err(InstanceError::FileNotFoundError.into());
+  }
+  // the game will overwrite level.dat on exit, so never edit an opened world
+  if is_world_in_use(&app, &instance_id, &world_dir) {
+    return Err(InstanceError::WorldInUseError.into());
+  }
+  set_world_datapack_enabled(&world_dir, &file_name, enabled)
+}
+
 #[tauri::command]
 pub fn create_launch_desktop_shortcut(app: AppHandle, instance_id: String) -> SJMCLResult<()> {
   let binding = app.state::<Mutex<HashMap<String, Instance>>>();
@@ -849,6 +1752,7 @@ pub async fn create_instance(
     play_time: 0,
     use_spec_game_config: false,
     spec_game_config: None,
+    ..Default::default()
   };
 
   // Download version info
@@ -949,6 +1853,24 @@ pub async fn create_instance(
     }
   }
 
+  // apply the options template after the modpack overrides, which may contain `options.txt`
+  let template = GameOptionsTemplate::load().unwrap_or_default();
+  if template.apply_to_new_instances && !template.entries.is_empty() {
+    if let Some(root_dir) = get_instance_subdir_paths(&app, &instance, &[&InstanceSubdirType::Root])
+      .and_then(|paths| paths.into_iter().next())
+    {
+      let options_path = root_dir.join("options.txt");
+      if !options_path.exists() {
+        let mut options = GameOptions::default();
+        let target_version = get_legacy_options_data_version(&app, &instance.version).await;
+        options.apply_template(&template, &[], target_version);
+        if let Err(e) = options.save(&options_path) {
+          log::error!("failed to apply the options template: {:?}", e);
+        }
+      }
+    }
+  }
+
   schedule_progressive_task_group(
     app.clone(),
     format!("game-client?{}", name),
@@ -1 +1,6 @@
 pub const INSTANCE_CFG_FILE_NAME: &str = "sjmclcfg.json";
+pub const INSTANCE_CFG_SCHEMA_VERSION: u32 = 1;
+pub const GAME_OPTIONS_TEMPLATE_FILE_NAME: &str = "sjmcl.options-template.json";
+
+pub const SCREENSHOT_THUMBNAIL_DIR_NAME: &str = "ScreenshotThumbnails";
+pub const SCREENSHOT_THUMBNAIL_READY_EVENT: &str = "instance:screenshot-thumbnail-ready";
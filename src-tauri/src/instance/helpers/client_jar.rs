@@ -61,6 +61,19 @@ fn from_server<R: Read + Seek>(jar: &mut ZipArchive<R>) -> Option<String> {
     .map(|s| s.to_string())
 }
 
+/// Reads the resource pack format from `version.json`, which is available since 1.14.
+/// `pack_version` is a number before 1.17, `{resource, data}` before 1.21.9,
+/// and `{resource_major, resource_minor, ...}` since then.
+pub fn load_resource_pack_format_from_jar<R: Read + Seek>(jar: &mut ZipArchive<R>) -> Option<i64> {
+  let file = jar.by_name("version.json").ok()?;
+  let json_value: Value = serde_json::from_reader(file).ok()?;
+  let pack_version = json_value.get("pack_version")?;
+  pack_version
+    .as_i64()
+    .or_else(|| pack_version.get("resource").and_then(Value::as_i64))
+    .or_else(|| pack_version.get("resource_major").and_then(Value::as_i64))
+}
+
 pub fn load_game_version_from_jar<R: Read + Seek>(jar: &mut ZipArchive<R>) -> Option<String> {
   if let Some(version) = from_json(jar) {
     return Some(version);
@@ -0,0 +1,166 @@
+use crate::{
+  error::SJMCLResult,
+  instance::{
+    helpers::{
+      resourcepack::{load_pack_meta_from_dir, load_pack_meta_from_zip, PackMeta},
+      world::{load_level_nbt_from_path, save_level_nbt_to_path},
+    },
+    models::misc::{DataPackInfo, InstanceError},
+  },
+  utils::{
+    fs::{get_files_with_regex, get_subdirectories},
+    image::ImageWrapper,
+  },
+};
+use image::RgbaImage;
+use quartz_nbt::{NbtCompound, NbtList, NbtTag};
+use regex::RegexBuilder;
+use std::path::{Path, PathBuf};
+
+// (first DataVersion, data pack format) of each release since 1.13, in ascending order.
+// https://minecraft.wiki/w/Pack_format#List_of_data_pack_formats
+const DATA_PACK_FORMATS: &[(i64, i64)] = &[
+  (1519, 4),  // 1.13
+  (2225, 5),  // 1.15
+  (2578, 6),  // 1.16.2
+  (2724, 7),  // 1.17
+  (2860, 8),  // 1.18
+  (2975, 9),  // 1.18.2
+  (3105, 10), // 1.19
+  (3337, 12), // 1.19.4
+  (3463, 15), // 1.20
+  (3578, 18), // 1.20.2
+  (3698, 26), // 1.20.3
+  (3837, 41), // 1.20.5
+  (3953, 48), // 1.21
+  (4080, 57), // 1.21.2
+  (4189, 61), // 1.21.4
+  (4325, 71), // 1.21.5
+  (4435, 80), // 1.21.6
+  (4438, 81), // 1.21.7
+  (4554, 88), // 1.21.9
+];
+
+/// Returns the data pack format expected by a world, or None for worlds before 1.13.
+pub fn get_data_pack_format(data_version: i64) -> Option<i64> {
+  DATA_PACK_FORMATS
+    .iter()
+    .rev()
+    .find(|(min_data_version, _)| data_version >= *min_data_version)
+    .map(|(_, format)| *format)
+}
+
+// the game refers to packs in the world's datapacks folder as `file/<file name>`.
+fn get_pack_id(file_name: &str) -> String {
+  format!("file/{}", file_name)
+}
+
+fn get_pack_id_list(data_packs: Option<&NbtCompound>, key: &str) -> Vec<String> {
+  data_packs
+    .and_then(|packs| packs.get::<_, &NbtList>(key).ok())
+    .map(|list| {
+      list
+        .iter()
+        .filter_map(|tag| match tag {
+          NbtTag::String(id) => Some(id.clone()),
+          _ => None,
+        })
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Lists the data packs in `<world>/datapacks`, together with their states in level.dat.
+pub async fn load_world_datapacks(world_dir: &Path) -> SJMCLResult<Vec<DataPackInfo>> {
+  let datapacks_dir = world_dir.join("datapacks");
+  let (root, _) = load_level_nbt_from_path(&world_dir.join("level.dat"))?;
+  let data = root
+    .get::<_, &NbtCompound>("Data")
+    .map_err(|_| InstanceError::LevelParseError)?;
+  let data_packs = data.get::<_, &NbtCompound>("DataPacks").ok();
+  let disabled = get_pack_id_list(data_packs, "Disabled");
+  let expected_format = data
+    .get::<_, i32>("DataVersion")
+    .ok()
+    .and_then(|version| get_data_pack_format(version as i64));
+
+  let mut packs: Vec<(PathBuf, PackMeta, Option<RgbaImage>)> = Vec::new();
+  let valid_extensions = RegexBuilder::new(r"\.zip$")
+    .case_insensitive(true)
+    .build()
+    .unwrap();
+  for path in get_files_with_regex(&datapacks_dir, &valid_extensions).unwrap_or_default() {
+    if let Ok((meta, icon_src)) = load_pack_meta_from_zip(&path) {
+      packs.push((path, meta, icon_src));
+    }
+  }
+  for path in get_subdirectories(&datapacks_dir).unwrap_or_default() {
+    if let Ok((meta, icon_src)) = load_pack_meta_from_dir(&path).await {
+      packs.push((path, meta, icon_src));
+    }
+  }
+
+  let mut info_list = Vec::new();
+  for (path, meta, icon_src) in packs {
+    let file_name = path
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+    let name = match path.file_stem() {
+      Some(stem) if path.is_file() => stem.to_string_lossy().to_string(),
+      _ => file_name.clone(),
+    };
+    // packs not listed in level.dat yet are enabled by the game on next load.
+    let enabled = !disabled.contains(&get_pack_id(&file_name));
+    let potential_incompatibility = match expected_format {
+      Some(format) => meta.pack_format.is_some() && !meta.supports_format(format),
+      None => false,
+    };
+    info_list.push(DataPackInfo {
+      name,
+      description: meta.description,
+      icon_src: icon_src.map(ImageWrapper::from),
+      file_path: path,
+      pack_format: meta.pack_format,
+      enabled,
+      potential_incompatibility,
+    });
+  }
+  info_list.sort_by_key(|info| info.name.to_lowercase());
+  Ok(info_list)
+}
+
+/// Moves a data pack between the `Enabled` and `Disabled` lists of level.dat.
+/// A newly enabled pack is appended to the end, which gives it the highest priority.
+pub fn set_world_datapack_enabled(
+  world_dir: &Path,
+  file_name: &str,
+  enabled: bool,
+) -> SJMCLResult<()> {
+  let level_path = world_dir.join("level.dat");
+  let (mut root, root_name) = load_level_nbt_from_path(&level_path)?;
+  let data = root
+    .get_mut::<_, &mut NbtCompound>("Data")
+    .map_err(|_| InstanceError::LevelParseError)?;
+  if !data.contains_key("DataPacks") {
+    data.insert("DataPacks", NbtCompound::new());
+  }
+  let data_packs = data
+    .get_mut::<_, &mut NbtCompound>("DataPacks")
+    .map_err(|_| InstanceError::LevelParseError)?;
+
+  let pack_id = get_pack_id(file_name);
+  let mut enabled_list = get_pack_id_list(Some(&*data_packs), "Enabled");
+  let mut disabled_list = get_pack_id_list(Some(&*data_packs), "Disabled");
+  enabled_list.retain(|id| id != &pack_id);
+  disabled_list.retain(|id| id != &pack_id);
+  if enabled {
+    enabled_list.push(pack_id);
+  } else {
+    disabled_list.push(pack_id);
+  }
+
+  data_packs.insert("Enabled", NbtList::from(enabled_list));
+  data_packs.insert("Disabled", NbtList::from(disabled_list));
+  save_level_nbt_to_path(&level_path, &root_name, &root)
+}
@@ -0,0 +1,198 @@
+use crate::{
+  error::{SJMCLError, SJMCLResult},
+  instance::{
+    constants::INSTANCE_CFG_FILE_NAME,
+    models::misc::{DuplicateInstanceOptions, Instance, InstanceError},
+  },
+  utils::fs::{copy_file_with_progress, get_dir_size, hard_link_or_copy},
+};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+// generated by the game at runtime, never carried over to a duplicated instance.
+const DUPLICATE_EXCLUDED_DIRS: &[&str] = &["logs", "crash-reports"];
+
+/// Returns the top-level entries of the version directory that should not be copied.
+/// The optional subdirs only live in the version directory when version isolation is on.
+pub fn get_duplicate_excluded_entries(options: &DuplicateInstanceOptions) -> Vec<String> {
+  let mut excluded: Vec<String> = DUPLICATE_EXCLUDED_DIRS
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+  for (name, carried) in [
+    ("saves", options.saves),
+    ("backups", options.saves), // world snapshots go together with the worlds
+    ("mods", options.mods),
+    ("config", options.config),
+    ("screenshots", options.screenshots),
+  ] {
+    if !carried {
+      excluded.push(name.to_string());
+    }
+  }
+  excluded
+}
+
+/// Sums up the size of the files that `duplicate_version_dir` is going to write.
+pub fn get_duplicate_size(src_version_path: &Path, excluded: &[String]) -> SJMCLResult<u64> {
+  let mut size = 0;
+  for entry in fs::read_dir(src_version_path)? {
+    let entry = entry?;
+    if excluded.contains(&entry.file_name().to_string_lossy().to_string()) {
+      continue;
+    }
+    let file_type = entry.file_type()?;
+    if file_type.is_dir() {
+      size += get_dir_size(&entry.path())?;
+    } else if file_type.is_file() {
+      size += entry.metadata()?.len();
+    }
+  }
+  Ok(size)
+}
+
+fn copy_dir_with_progress<F>(
+  src: &Path,
+  dst: &Path,
+  hard_link: bool,
+  on_progress: &mut F,
+) -> SJMCLResult<()>
+where
+  F: FnMut(usize) -> bool,
+{
+  fs::create_dir_all(dst)?;
+  for entry in fs::read_dir(src)? {
+    let entry = entry?;
+    let src_path = entry.path();
+    let dst_path = dst.join(entry.file_name());
+    let file_type = entry.file_type()?;
+    if file_type.is_dir() {
+      copy_dir_with_progress(&src_path, &dst_path, hard_link, on_progress)?;
+    } else if hard_link {
+      hard_link_or_copy(&src_path, &dst_path)?;
+      if !on_progress(entry.metadata()?.len() as usize) {
+        return Err(SJMCLError("File copy aborted".to_string()));
+      }
+    } else {
+      copy_file_with_progress(&src_path, &dst_path, on_progress)?;
+    }
+  }
+  Ok(())
+}
+
+/// Copies the version directory of an instance into `dst_version_path` synchronously.
+///
+/// The client jar and the extracted natives are read-only for the game, so they are hard
+/// linked instead of copied. The client json and the instance config are left out here,
+/// see `write_duplicated_instance_files`.
+pub fn duplicate_version_dir<F>(
+  src_version_path: &Path,
+  dst_version_path: &Path,
+  excluded: &[String],
+  mut on_progress: F,
+) -> SJMCLResult<()>
+where
+  F: FnMut(usize) -> bool,
+{
+  let src_name = src_version_path
+    .file_name()
+    .ok_or(InstanceError::InvalidSourcePath)?
+    .to_string_lossy()
+    .to_string();
+  let tgt_name = dst_version_path
+    .file_name()
+    .ok_or(InstanceError::InvalidNameError)?
+    .to_string_lossy()
+    .to_string();
+  fs::create_dir_all(dst_version_path).map_err(|_| InstanceError::FolderCreationFailed)?;
+
+  for entry in fs::read_dir(src_version_path)? {
+    let entry = entry?;
+    let name = entry.file_name().to_string_lossy().to_string();
+    if excluded.contains(&name) {
+      continue;
+    }
+    let src_path = entry.path();
+    let file_type = entry.file_type()?;
+
+    if name == format!("{}.json", src_name) || name == INSTANCE_CFG_FILE_NAME {
+      // rewritten afterwards, still counted in the total size
+      if !on_progress(entry.metadata()?.len() as usize) {
+        return Err(SJMCLError("File copy aborted".to_string()));
+      }
+    } else if name == format!("{}.jar", src_name) {
+      hard_link_or_copy(
+        &src_path,
+        &dst_version_path.join(format!("{}.jar", tgt_name)),
+      )?;
+      if !on_progress(entry.metadata()?.len() as usize) {
+        return Err(SJMCLError("File copy aborted".to_string()));
+      }
+    } else if file_type.is_dir() {
+      let hard_link = name.starts_with("natives-");
+      copy_dir_with_progress(
+        &src_path,
+        &dst_version_path.join(&name),
+        hard_link,
+        &mut on_progress,
+      )?;
+    } else if file_type.is_file() {
+      copy_file_with_progress(&src_path, &dst_version_path.join(&name), &mut on_progress)?;
+    }
+  }
+  Ok(())
+}
+
+/// Writes the instance config and the client json of the duplicated instance, with the
+/// same `id` rewriting as `unify_instance_name`. The client json is written last, so the
+/// new instance is not recognized by `refresh_instances` until everything is in place.
+pub async fn write_duplicated_instance_files(
+  src_version_path: &Path,
+  dst_version_path: &Path,
+) -> SJMCLResult<()> {
+  let src_name = src_version_path
+    .file_name()
+    .ok_or(InstanceError::InvalidSourcePath)?
+    .to_string_lossy()
+    .to_string();
+  let tgt_name = dst_version_path
+    .file_name()
+    .ok_or(InstanceError::InvalidNameError)?
+    .to_string_lossy()
+    .to_string();
+
+  let src_instance = Instance {
+    version_path: src_version_path.to_path_buf(),
+    ..Default::default()
+  };
+  if let Ok(instance) = src_instance.load_json_cfg().await {
+    let instance = Instance {
+      name: tgt_name.clone(),
+      version_path: dst_version_path.to_path_buf(),
+      play_time: 0,
+      ..instance
+    };
+    instance
+      .save_json_cfg()
+      .await
+      .map_err(|_| InstanceError::FileCreationFailed)?;
+  }
+
+  let src_json = src_version_path.join(format!("{}.json", src_name));
+  let mut json_value: Value = serde_json::from_str(&tokio::fs::read_to_string(&src_json).await?)
+    .map_err(|_| InstanceError::ClientJsonParseError)?;
+  if let Some(obj) = json_value.as_object_mut() {
+    obj.insert("id".to_string(), Value::String(tgt_name.clone()));
+    // some clients point `jar` to their own id, keep it consistent with the new jar name
+    if obj.get("jar").and_then(|jar| jar.as_str()) == Some(src_name.as_str()) {
+      obj.insert("jar".to_string(), Value::String(tgt_name.clone()));
+    }
+  }
+  tokio::fs::write(
+    dst_version_path.join(format!("{}.json", tgt_name)),
+    json_value.to_string(),
+  )
+  .await?;
+  Ok(())
+}
@@ -0,0 +1,217 @@
+use crate::launcher_config::models::{
+  FileValidatePolicy, GameConfig, ImportedInstanceSettings, LauncherVisiablity, ProcessPriority,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+pub const HMCL_VERSION_CFG_FILE_NAME: &str = "hmclversion.cfg";
+
+// https://github.com/HMCL-dev/HMCL/blob/main/HMCL/src/main/java/org/jackhuang/hmcl/setting/VersionSetting.java
+// enums are written by name in recent versions, but as ordinals in some older ones.
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HmclVersionSetting {
+  pub uses_global: bool,
+  pub java: Option<String>,
+  pub java_version_type: Option<String>,
+  pub java_dir: Option<String>,
+  pub default_java_path: Option<String>,
+  pub java_args: Option<String>,
+  pub minecraft_args: Option<String>,
+  pub environment_variables: Option<String>,
+  pub perm_size: Option<String>,
+  pub max_memory: Option<u32>,
+  pub auto_memory: Option<bool>,
+  pub width: Option<u32>,
+  pub height: Option<u32>,
+  pub fullscreen: Option<bool>,
+  pub precalled_command: Option<String>,
+  pub wrapper: Option<String>,
+  pub post_exit_command: Option<String>,
+  pub server_ip: Option<String>,
+  pub game_dir_type: Option<Value>,
+  pub not_check_game: Option<bool>,
+  #[serde(rename = "notCheckJVM")]
+  pub not_check_jvm: Option<bool>,
+  pub not_patch_natives: Option<bool>,
+  #[serde(rename = "noJVMArgs")]
+  pub no_jvm_args: Option<bool>,
+  #[serde(rename = "useNativeGLFW")]
+  pub use_native_glfw: Option<bool>,
+  #[serde(rename = "useNativeOpenAL")]
+  pub use_native_openal: Option<bool>,
+  pub show_logs: Option<bool>,
+  pub launcher_visibility: Option<Value>,
+  pub process_priority: Option<Value>,
+  pub version_icon: Option<String>,
+}
+
+fn enum_name(value: &Value, names: &[&str]) -> Option<String> {
+  match value {
+    Value::String(name) => Some(name.to_uppercase()),
+    Value::Number(index) => index
+      .as_u64()
+      .and_then(|i| names.get(i as usize))
+      .map(|name| name.to_string()),
+    _ => None,
+  }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+  value.clone().filter(|s| !s.trim().is_empty())
+}
+
+impl HmclVersionSetting {
+  pub fn to_game_config(&self, base: &GameConfig) -> GameConfig {
+    let mut config = base.clone();
+
+    let is_custom_java =
+      self.java.as_deref() == Some("Custom") || self.java_version_type.as_deref() == Some("CUSTOM");
+    if let Some(exec_path) = non_empty(&self.java_dir).or(non_empty(&self.default_java_path)) {
+      if is_custom_java {
+        config.game_java.auto = false;
+        config.game_java.exec_path = exec_path;
+      }
+    }
+
+    if let Some(auto_memory) = self.auto_memory {
+      config.performance.auto_mem_allocation = auto_memory;
+    }
+    if let Some(max_memory) = self.max_memory {
+      config.performance.max_mem_allocation = max_memory;
+    }
+    if let Some(priority) = self.process_priority.as_ref().and_then(|v| {
+      enum_name(
+        v,
+        &["LOW", "BELOW_NORMAL", "NORMAL", "ABOVE_NORMAL", "HIGH"],
+      )
+    }) {
+      config.performance.process_priority = match priority.as_str() {
+        "LOW" => ProcessPriority::Low,
+        "BELOW_NORMAL" => ProcessPriority::BelowNormal,
+        "ABOVE_NORMAL" => ProcessPriority::AboveNormal,
+        "HIGH" => ProcessPriority::High,
+        _ => ProcessPriority::Normal,
+      };
+    }
+
+    if let (Some(width), Some(height)) = (self.width, self.height) {
+      config.game_window.resolution.width = width;
+      config.game_window.resolution.height = height;
+    }
+    if let Some(fullscreen) = self.fullscreen {
+      config.game_window.resolution.fullscreen = fullscreen;
+    }
+
+    if let Some(server_ip) = non_empty(&self.server_ip) {
+      config.game_server.auto_join = true;
+      config.game_server.server_url = server_ip;
+    }
+    if let Some(dir_type) = self
+      .game_dir_type
+      .as_ref()
+      .and_then(|v| enum_name(v, &["ROOT_FOLDER", "VERSION_FOLDER", "CUSTOM"]))
+    {
+      // custom game directories are not supported, keep the current isolation setting
+      match dir_type.as_str() {
+        "ROOT_FOLDER" => config.version_isolation = false,
+        "VERSION_FOLDER" => config.version_isolation = true,
+        _ => {}
+      }
+    }
+    if let Some(visibility) = self
+      .launcher_visibility
+      .as_ref()
+      .and_then(|v| enum_name(v, &["CLOSE", "HIDE", "KEEP", "HIDE_AND_REOPEN"]))
+    {
+      config.launcher_visibility = match visibility.as_str() {
+        "CLOSE" | "HIDE" => LauncherVisiablity::StartHidden,
+        "HIDE_AND_REOPEN" => LauncherVisiablity::RunningHidden,
+        _ => LauncherVisiablity::Always,
+      };
+    }
+    if let Some(show_logs) = self.show_logs {
+      config.display_game_log = show_logs;
+    }
+
+    let advanced = &mut config.advanced;
+    if let Some(args) = non_empty(&self.java_args) {
+      advanced.jvm.args = args;
+    }
+    if let Some(perm_size) = non_empty(&self.perm_size).and_then(|s| s.trim().parse().ok()) {
+      advanced.jvm.java_permanent_generation_space = perm_size;
+    }
+    if let Some(env) = non_empty(&self.environment_variables) {
+      advanced.jvm.environment_variable = env;
+    }
+    if let Some(args) = non_empty(&self.minecraft_args) {
+      advanced.custom_commands.minecraft_argument = args;
+    }
+    if let Some(command) = non_empty(&self.precalled_command) {
+      advanced.custom_commands.precall_command = command;
+    }
+    if let Some(wrapper) = non_empty(&self.wrapper) {
+      advanced.custom_commands.wrapper_launcher = wrapper;
+    }
+    if let Some(command) = non_empty(&self.post_exit_command) {
+      advanced.custom_commands.post_exit_command = command;
+    }
+    if self.not_check_game == Some(true) {
+      advanced.workaround.game_file_validate_policy = FileValidatePolicy::Disable;
+    }
+    if let Some(v) = self.not_check_jvm {
+      advanced.workaround.dont_check_jvm_validity = v;
+    }
+    if let Some(v) = self.not_patch_natives {
+      advanced.workaround.dont_patch_natives = v;
+    }
+    if let Some(v) = self.no_jvm_args {
+      advanced.workaround.no_jvm_args = v;
+    }
+    if let Some(v) = self.use_native_glfw {
+      advanced.workaround.use_native_glfw = v;
+    }
+    if let Some(v) = self.use_native_openal {
+      advanced.workaround.use_native_openal = v;
+    }
+    if config.advanced != base.advanced {
+      config.advanced_options.enabled = true;
+    }
+    config
+  }
+
+  pub fn to_icon_src(&self) -> Option<String> {
+    let icon = match self.version_icon.as_deref()? {
+      "GRASS" => "GrassBlock.png",
+      "COMMAND" => "CommandBlock.png",
+      "CRAFT_TABLE" => "CraftingTable.png",
+      "FABRIC" => "Fabric.png",
+      "FORGE" => "Forge.png",
+      "NEO_FORGE" => "NeoForge.png",
+      "QUILT" => "Quilt.png",
+      _ => return None,
+    };
+    Some(format!("/images/icons/{}", icon))
+  }
+}
+
+pub fn load_hmcl_version_settings(
+  version_path: &Path,
+  global_config: &GameConfig,
+) -> Option<ImportedInstanceSettings> {
+  let contents = std::fs::read_to_string(version_path.join(HMCL_VERSION_CFG_FILE_NAME)).ok()?;
+  let setting: HmclVersionSetting = serde_json::from_str(&contents).ok()?;
+
+  let spec_game_config = if setting.uses_global {
+    None
+  } else {
+    Some(setting.to_game_config(global_config)).filter(|config| config != global_config)
+  };
+  Some(ImportedInstanceSettings {
+    source_launchers: vec!["HMCL".to_string()],
+    spec_game_config,
+    icon_src: setting.to_icon_src(),
+    ..Default::default()
+  })
+}
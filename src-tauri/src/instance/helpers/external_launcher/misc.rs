@@ -0,0 +1,29 @@
+use crate::{
+  instance::helpers::external_launcher::{
+    hmcl::load_hmcl_version_settings, pcl::load_pcl_version_settings,
+  },
+  launcher_config::models::{GameConfig, ImportedInstanceSettings},
+};
+use std::path::Path;
+
+/// Reads the settings of a version folder left by HMCL and PCL, None if there is nothing to import.
+pub fn load_external_instance_settings(
+  version_path: &Path,
+  global_config: &GameConfig,
+) -> Option<ImportedInstanceSettings> {
+  let mut settings = ImportedInstanceSettings {
+    instance_name: version_path.file_name()?.to_string_lossy().to_string(),
+    ..Default::default()
+  };
+  // PCL takes precedence, since HMCL does not keep descriptions and stars at all
+  for loaded in [
+    load_pcl_version_settings(version_path, global_config),
+    load_hmcl_version_settings(version_path, global_config),
+  ]
+  .into_iter()
+  .flatten()
+  {
+    settings.merge(loaded);
+  }
+  Some(settings).filter(|s| !s.is_empty())
+}
@@ -0,0 +1,3 @@
+pub mod hmcl;
+pub mod misc;
+pub mod pcl;
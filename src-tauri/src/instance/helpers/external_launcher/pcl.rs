@@ -0,0 +1,113 @@
+use crate::launcher_config::models::{GameConfig, ImportedInstanceSettings};
+use std::collections::HashMap;
+use std::path::Path;
+
+// PCL keeps per-version settings in `<version>/PCL/Setup.ini`, one `Key:Value` per line.
+pub const PCL_SETUP_FILE_PATH: &str = "PCL/Setup.ini";
+
+pub fn parse_pcl_setup(contents: &str) -> HashMap<String, String> {
+  contents
+    .lines()
+    .filter_map(|line| {
+      let (key, value) = line.split_once(':')?;
+      Some((key.trim().to_string(), value.trim().to_string()))
+    })
+    .collect()
+}
+
+// PCL's memory slider is not linear, convert the slider value back to MB.
+fn pcl_ram_slider_to_mb(value: f64) -> u32 {
+  let gb = if value <= 12.0 {
+    value * 0.1 + 0.3
+  } else if value <= 25.0 {
+    (value - 12.0) * 0.5 + 1.5
+  } else if value <= 33.0 {
+    (value - 25.0) + 8.0
+  } else {
+    (value - 33.0) * 2.0 + 16.0
+  };
+  (gb * 1024.0).round() as u32
+}
+
+fn pcl_java_exec_path(java_select: &str) -> Option<String> {
+  let entry: serde_json::Value = serde_json::from_str(java_select).ok()?;
+  let path = entry.get("Path")?.as_str()?.to_string();
+  if path.ends_with('\\') || path.ends_with('/') {
+    // older PCL versions only keep the bin folder
+    Some(format!("{}javaw.exe", path))
+  } else {
+    Some(path)
+  }
+}
+
+fn pcl_logo_to_icon_src(logo: &str) -> Option<String> {
+  // built-in logos look like `pack://application:,,,/images/Blocks/Grass.png`
+  let file_name = logo.rsplit(['/', '\\']).next()?;
+  let icon = match file_name {
+    "Grass.png" => "GrassBlock.png",
+    "CommandBlock.png" => "CommandBlock.png",
+    "CraftingTable.png" => "CraftingTable.png",
+    "Anvil.png" => "Anvil.png",
+    "Fabric.png" => "Fabric.png",
+    "NeoForge.png" => "NeoForge.png",
+    "Quilt.png" => "Quilt.png",
+    _ => return None,
+  };
+  Some(format!("/images/icons/{}", icon))
+}
+
+pub fn load_pcl_version_settings(
+  version_path: &Path,
+  global_config: &GameConfig,
+) -> Option<ImportedInstanceSettings> {
+  let bytes = std::fs::read(version_path.join(PCL_SETUP_FILE_PATH)).ok()?;
+  let setup = parse_pcl_setup(&String::from_utf8_lossy(&bytes));
+  let get = |key: &str| setup.get(key).filter(|v| !v.is_empty());
+
+  let mut config = global_config.clone();
+  if let Some(indie) = get("VersionArgumentIndieV2") {
+    config.version_isolation = indie == "True";
+  }
+  match get("VersionRamType").map(|v| v.as_str()) {
+    Some("0") => config.performance.auto_mem_allocation = true,
+    Some("1") => {
+      if let Some(value) = get("VersionRamCustom").and_then(|v| v.parse::<f64>().ok()) {
+        config.performance.auto_mem_allocation = false;
+        config.performance.max_mem_allocation = pcl_ram_slider_to_mb(value);
+      }
+    }
+    _ => {} // follow the global setting
+  }
+  if let Some(exec_path) = get("VersionArgumentJavaSelect").and_then(|v| pcl_java_exec_path(v)) {
+    config.game_java.auto = false;
+    config.game_java.exec_path = exec_path;
+  }
+  if let Some(title) = get("VersionArgumentTitle") {
+    config.game_window.custom_title = title.clone();
+  }
+  if let Some(server) = get("VersionServerEnter") {
+    config.game_server.auto_join = true;
+    config.game_server.server_url = server.clone();
+  }
+  if let Some(args) = get("VersionAdvanceJvm") {
+    config.advanced.jvm.args = args.clone();
+  }
+  if let Some(args) = get("VersionAdvanceGame") {
+    config.advanced.custom_commands.minecraft_argument = args.clone();
+  }
+  if let Some(command) = get("VersionAdvanceRun") {
+    config.advanced.custom_commands.precall_command = command.clone();
+  }
+  if config.advanced != global_config.advanced {
+    config.advanced_options.enabled = true;
+  }
+
+  Some(ImportedInstanceSettings {
+    source_launchers: vec!["PCL".to_string()],
+    description: get("CustomInfo").cloned(),
+    icon_src: get("Logo").and_then(|logo| pcl_logo_to_icon_src(logo)),
+    starred: get("IsStar").map(|v| v == "True"),
+    spec_game_config: Some(config).filter(|config| config != global_config),
+    ..Default::default()
+  })
+}
@@ -0,0 +1,391 @@
+use crate::error::SJMCLResult;
+use crate::instance::helpers::game_version::compare_game_versions;
+use crate::instance::models::misc::{GameOptionsInfo, GameOptionsPatch, GameOptionsTemplate};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// The `options.txt` of an instance, kept as ordered `key:value` entries.
+/// Keys unknown to the launcher are kept as they are, so the file is written back without loss.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameOptions {
+  entries: Vec<(String, String)>,
+}
+
+impl GameOptions {
+  pub fn parse(content: &str) -> Self {
+    let entries = content
+      .lines()
+      .filter_map(|line| {
+        // values may contain `:` (e.g. key bindings of mods), split at the first one
+        let (key, value) = line.split_once(':')?;
+        Some((key.to_string(), value.to_string()))
+      })
+      .collect();
+    GameOptions { entries }
+  }
+
+  /// Returns empty options if the file does not exist yet, e.g. the game has never been launched.
+  pub fn load(path: &Path) -> SJMCLResult<Self> {
+    if !path.is_file() {
+      return Ok(GameOptions::default());
+    }
+    let bytes = fs::read(path)?;
+    Ok(Self::parse(&String::from_utf8_lossy(&bytes)))
+  }
+
+  pub fn save(&self, path: &Path) -> SJMCLResult<()> {
+    let content: String = self
+      .entries
+      .iter()
+      .map(|(key, value)| format!("{}:{}\n", key, value))
+      .collect();
+    fs::write(path, content)?;
+    Ok(())
+  }
+
+  pub fn get(&self, key: &str) -> Option<&str> {
+    self
+      .entries
+      .iter()
+      .find(|(k, _)| k == key)
+      .map(|(_, v)| v.as_str())
+  }
+
+  /// Updates the value in place, or appends a new entry.
+  pub fn set(&mut self, key: &str, value: String) {
+    match self.entries.iter_mut().find(|(k, _)| k == key) {
+      Some((_, v)) => *v = value,
+      None => self.entries.push((key.to_string(), value)),
+    }
+  }
+
+  /// Reads a list value, which the game saves as a json array of strings.
+  pub fn get_string_list(&self, key: &str) -> Vec<String> {
+    self
+      .get(key)
+      .and_then(|value| serde_json::from_str::<Vec<String>>(value).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn set_string_list(&mut self, key: &str, list: &[String]) {
+    self.set(key, serde_json::to_string(list).unwrap_or_default());
+  }
+}
+
+const FOV_KEY: &str = "fov";
+const SOUND_LEVEL_KEY_PREFIX: &str = "soundCategory_";
+const KEY_BINDING_KEY_PREFIX: &str = "key_";
+const DATA_VERSION_KEY: &str = "version";
+
+// the data versions of the game's options fixers (`OptionsLowerCaseLanguageFix` and
+// `OptionsKeyLwjgl3Fix`), since when `lang` is in lower case and key bindings are saved by name.
+const LOWER_CASE_LANG_DATA_VERSION: i64 = 816;
+const KEY_NAME_DATA_VERSION: i64 = 1466;
+
+// LWJGL 2 key codes used by the key bindings before 1.13, and their names since then.
+// Mouse buttons were saved as `button - 100`.
+const LEGACY_KEY_CODES: &[(i32, &str)] = &[
+  (-100, "key.mouse.left"),
+  (-99, "key.mouse.right"),
+  (-98, "key.mouse.middle"),
+  (-97, "key.mouse.4"),
+  (-96, "key.mouse.5"),
+  (0, "key.keyboard.unknown"),
+  (1, "key.keyboard.escape"),
+  (2, "key.keyboard.1"),
+  (3, "key.keyboard.2"),
+  (4, "key.keyboard.3"),
+  (5, "key.keyboard.4"),
+  (6, "key.keyboard.5"),
+  (7, "key.keyboard.6"),
+  (8, "key.keyboard.7"),
+  (9, "key.keyboard.8"),
+  (10, "key.keyboard.9"),
+  (11, "key.keyboard.0"),
+  (12, "key.keyboard.minus"),
+  (13, "key.keyboard.equal"),
+  (14, "key.keyboard.backspace"),
+  (15, "key.keyboard.tab"),
+  (16, "key.keyboard.q"),
+  (17, "key.keyboard.w"),
+  (18, "key.keyboard.e"),
+  (19, "key.keyboard.r"),
+  (20, "key.keyboard.t"),
+  (21, "key.keyboard.y"),
+  (22, "key.keyboard.u"),
+  (23, "key.keyboard.i"),
+  (24, "key.keyboard.o"),
+  (25, "key.keyboard.p"),
+  (26, "key.keyboard.left.bracket"),
+  (27, "key.keyboard.right.bracket"),
+  (28, "key.keyboard.enter"),
+  (29, "key.keyboard.left.control"),
+  (30, "key.keyboard.a"),
+  (31, "key.keyboard.s"),
+  (32, "key.keyboard.d"),
+  (33, "key.keyboard.f"),
+  (34, "key.keyboard.g"),
+  (35, "key.keyboard.h"),
+  (36, "key.keyboard.j"),
+  (37, "key.keyboard.k"),
+  (38, "key.keyboard.l"),
+  (39, "key.keyboard.semicolon"),
+  (40, "key.keyboard.apostrophe"),
+  (41, "key.keyboard.grave.accent"),
+  (42, "key.keyboard.left.shift"),
+  (43, "key.keyboard.backslash"),
+  (44, "key.keyboard.z"),
+  (45, "key.keyboard.x"),
+  (46, "key.keyboard.c"),
+  (47, "key.keyboard.v"),
+  (48, "key.keyboard.b"),
+  (49, "key.keyboard.n"),
+  (50, "key.keyboard.m"),
+  (51, "key.keyboard.comma"),
+  (52, "key.keyboard.period"),
+  (53, "key.keyboard.slash"),
+  (54, "key.keyboard.right.shift"),
+  (55, "key.keyboard.keypad.multiply"),
+  (56, "key.keyboard.left.alt"),
+  (57, "key.keyboard.space"),
+  (58, "key.keyboard.caps.lock"),
+  (59, "key.keyboard.f1"),
+  (60, "key.keyboard.f2"),
+  (61, "key.keyboard.f3"),
+  (62, "key.keyboard.f4"),
+  (63, "key.keyboard.f5"),
+  (64, "key.keyboard.f6"),
+  (65, "key.keyboard.f7"),
+  (66, "key.keyboard.f8"),
+  (67, "key.keyboard.f9"),
+  (68, "key.keyboard.f10"),
+  (69, "key.keyboard.num.lock"),
+  (70, "key.keyboard.scroll.lock"),
+  (71, "key.keyboard.keypad.7"),
+  (72, "key.keyboard.keypad.8"),
+  (73, "key.keyboard.keypad.9"),
+  (74, "key.keyboard.keypad.subtract"),
+  (75, "key.keyboard.keypad.4"),
+  (76, "key.keyboard.keypad.5"),
+  (77, "key.keyboard.keypad.6"),
+  (78, "key.keyboard.keypad.add"),
+  (79, "key.keyboard.keypad.1"),
+  (80, "key.keyboard.keypad.2"),
+  (81, "key.keyboard.keypad.3"),
+  (82, "key.keyboard.keypad.0"),
+  (83, "key.keyboard.keypad.decimal"),
+  (87, "key.keyboard.f11"),
+  (88, "key.keyboard.f12"),
+  (156, "key.keyboard.keypad.enter"),
+  (157, "key.keyboard.right.control"),
+  (181, "key.keyboard.keypad.divide"),
+  (183, "key.keyboard.print.screen"),
+  (184, "key.keyboard.right.alt"),
+  (197, "key.keyboard.pause"),
+  (199, "key.keyboard.home"),
+  (200, "key.keyboard.up"),
+  (201, "key.keyboard.page.up"),
+  (203, "key.keyboard.left"),
+  (205, "key.keyboard.right"),
+  (207, "key.keyboard.end"),
+  (208, "key.keyboard.down"),
+  (209, "key.keyboard.page.down"),
+  (210, "key.keyboard.insert"),
+  (211, "key.keyboard.delete"),
+  (219, "key.keyboard.left.win"),
+  (220, "key.keyboard.right.win"),
+];
+
+// key bindings renamed by the game: (data version since the new key, old key, new key).
+const RENAMED_KEY_BINDINGS: &[(i64, &str, &str)] = &[(
+  KEY_NAME_DATA_VERSION,
+  "key_key.swapHands",
+  "key_key.swapOffhand",
+)];
+
+/// Whether `key` matches a key pattern, which is either a key or a prefix ending with `*`.
+pub fn match_option_key(pattern: &str, key: &str) -> bool {
+  match pattern.strip_suffix('*') {
+    Some(prefix) => key.starts_with(prefix),
+    None => pattern == key,
+  }
+}
+
+/// Converts an option value saved by the game of `from_version` (data version) to the format of
+/// `to_version`: key bindings by LWJGL 2 key code or by name, and the case of the `lang` region.
+/// Values are kept as they are if either version is unknown.
+pub fn convert_option_value(
+  key: &str,
+  value: &str,
+  from_version: Option<i64>,
+  to_version: Option<i64>,
+) -> String {
+  let (Some(from_version), Some(to_version)) = (from_version, to_version) else {
+    return value.to_string();
+  };
+  let crosses = |threshold: i64| (from_version >= threshold) != (to_version >= threshold);
+
+  if key.starts_with(KEY_BINDING_KEY_PREFIX) && crosses(KEY_NAME_DATA_VERSION) {
+    let converted = if to_version >= KEY_NAME_DATA_VERSION {
+      value.parse::<i32>().ok().and_then(|code| {
+        LEGACY_KEY_CODES
+          .iter()
+          .find(|(c, _)| *c == code)
+          .map(|(_, name)| name.to_string())
+      })
+    } else {
+      LEGACY_KEY_CODES
+        .iter()
+        .find(|(_, name)| *name == value)
+        .map(|(code, _)| code.to_string())
+    };
+    return converted.unwrap_or_else(|| value.to_string());
+  }
+
+  if key == "lang" && crosses(LOWER_CASE_LANG_DATA_VERSION) {
+    if to_version >= LOWER_CASE_LANG_DATA_VERSION {
+      return value.to_lowercase();
+    }
+    return match value.split_once('_') {
+      Some((language, region)) => format!("{}_{}", language, region.to_uppercase()),
+      None => value.to_string(),
+    };
+  }
+  value.to_string()
+}
+
+/// Converts an option key saved by the game of `from_version` (data version) to its name in
+/// `to_version`, for the key bindings renamed by the game.
+pub fn convert_option_key(key: &str, from_version: Option<i64>, to_version: Option<i64>) -> String {
+  let (Some(from_version), Some(to_version)) = (from_version, to_version) else {
+    return key.to_string();
+  };
+  for (version, old_key, new_key) in RENAMED_KEY_BINDINGS {
+    if key == *old_key && from_version < *version && to_version >= *version {
+      return new_key.to_string();
+    }
+    if key == *new_key && from_version >= *version && to_version < *version {
+      return old_key.to_string();
+    }
+  }
+  key.to_string()
+}
+
+impl GameOptions {
+  pub fn entries(&self) -> &[(String, String)] {
+    &self.entries
+  }
+
+  /// The data version of the game which saved the file.
+  pub fn data_version(&self) -> Option<i64> {
+    self.get(DATA_VERSION_KEY)?.parse().ok()
+  }
+
+  pub fn to_info(&self) -> GameOptionsInfo {
+    let get_number = |key: &str| self.get(key).and_then(|v| v.parse::<u32>().ok());
+    GameOptionsInfo {
+      data_version: self.data_version(),
+      lang: self.get("lang").map(String::from),
+      // saved as (fov - 70) / 40 in [-1, 1]
+      fov: self
+        .get(FOV_KEY)
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|v| (70.0 + v * 40.0).round() as u32),
+      gui_scale: get_number("guiScale"),
+      render_distance: get_number("renderDistance"),
+      max_fps: get_number("maxFps"),
+      sound_levels: self
+        .entries
+        .iter()
+        .filter_map(|(key, value)| {
+          let category = key.strip_prefix(SOUND_LEVEL_KEY_PREFIX)?;
+          Some((category.to_string(), value.parse().ok()?))
+        })
+        .collect(),
+      key_bindings: self
+        .entries
+        .iter()
+        .filter_map(|(key, value)| {
+          let binding = key.strip_prefix(KEY_BINDING_KEY_PREFIX)?;
+          Some((binding.to_string(), value.clone()))
+        })
+        .collect(),
+      entries: self.entries.clone(),
+    }
+  }
+
+  pub fn apply_patch(&mut self, patch: &GameOptionsPatch) {
+    if let Some(lang) = &patch.lang {
+      self.set("lang", lang.clone());
+    }
+    if let Some(fov) = patch.fov {
+      self.set(FOV_KEY, ((fov as f64 - 70.0) / 40.0).to_string());
+    }
+    if let Some(gui_scale) = patch.gui_scale {
+      self.set("guiScale", gui_scale.to_string());
+    }
+    if let Some(render_distance) = patch.render_distance {
+      self.set("renderDistance", render_distance.to_string());
+    }
+    if let Some(max_fps) = patch.max_fps {
+      self.set("maxFps", max_fps.to_string());
+    }
+    for (category, level) in &patch.sound_levels {
+      let key = format!("{}{}", SOUND_LEVEL_KEY_PREFIX, category);
+      self.set(&key, level.to_string());
+    }
+    for (binding, value) in &patch.key_bindings {
+      let key = format!("{}{}", KEY_BINDING_KEY_PREFIX, binding);
+      self.set(&key, value.clone());
+    }
+    for (key, value) in &patch.others {
+      self.set(key, value.clone());
+    }
+  }
+
+  /// Applies the template entries whose keys match `key_patterns` (all if empty), with values
+  /// converted to the format of this file, or of `target_version` if the file has no data version.
+  /// If both are unknown, a new file takes the values as they are with the data version of the
+  /// template, and the game upgrades them on load.
+  pub fn apply_template(
+    &mut self,
+    template: &GameOptionsTemplate,
+    key_patterns: &[String],
+    target_version: Option<i64>,
+  ) {
+    let to_version = self.data_version().or(target_version);
+    let is_new_file = self.entries.is_empty();
+    for (key, value) in &template.entries {
+      if key == DATA_VERSION_KEY
+        || !(key_patterns.is_empty() || key_patterns.iter().any(|p| match_option_key(p, key)))
+      {
+        continue;
+      }
+      let value = convert_option_value(key, value, template.data_version, to_version);
+      let key = convert_option_key(key, template.data_version, to_version);
+      self.set(&key, value);
+    }
+    if is_new_file && to_version.is_none() {
+      if let Some(version) = template.data_version {
+        // the game reads the data version first
+        self
+          .entries
+          .insert(0, (DATA_VERSION_KEY.to_string(), version.to_string()));
+      }
+    }
+  }
+}
+
+/// Estimates a data version in the legacy options format for games before 1.13,
+/// whose `options.txt` may have no data version. Returns `None` for newer games.
+pub async fn get_legacy_options_data_version(app: &AppHandle, game_version: &str) -> Option<i64> {
+  if compare_game_versions(app, game_version, "1.11", false).await == Ordering::Less {
+    Some(LOWER_CASE_LANG_DATA_VERSION - 1)
+  } else if compare_game_versions(app, game_version, "1.13", false).await == Ordering::Less {
+    Some(KEY_NAME_DATA_VERSION - 1)
+  } else {
+    None
+  }
+}
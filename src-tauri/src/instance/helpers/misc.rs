@@ -50,6 +50,7 @@ pub fn get_instance_subdir_paths(
     .map(|directory_type| {
       let path_buf = match directory_type {
         InstanceSubdirType::Assets => game_dir.join("assets"),
+        InstanceSubdirType::Backups => path.join("backups"),
         InstanceSubdirType::Libraries => game_dir.join("libraries"),
         InstanceSubdirType::Mods => path.join("mods"),
         InstanceSubdirType::ResourcePacks => path.join("resourcepacks"),
@@ -1,11 +1,19 @@
 pub mod asset_index;
 pub mod client_jar;
 pub mod client_json;
+pub mod datapack;
+pub mod duplicate;
+pub mod external_launcher;
+pub mod game_options;
 pub mod game_version;
 pub mod loader;
 pub mod misc;
 pub mod modpack;
 pub mod mods;
 pub mod resourcepack;
+pub mod schematic;
+pub mod screenshot;
 pub mod server;
+pub mod shaderpack;
 pub mod world;
+pub mod world_backup;
@@ -1,4 +1,6 @@
 use crate::error::{SJMCLError, SJMCLResult};
+use crate::instance::helpers::game_options::GameOptions;
+use crate::instance::models::misc::PackOverlay;
 use crate::utils::image::{load_image_from_dir_async, load_image_from_jar};
 use image::RgbaImage;
 use std::fs;
@@ -85,3 +87,231 @@ pub async fn load_resourcepack_from_dir(path: &Path) -> SJMCLResult<(String, Opt
   let icon_src = load_image_from_dir_async(&path.join("pack.png")).await;
   Ok((description, icon_src))
 }
+
+/// Metadata in `pack.mcmeta` shared by resource packs and data packs.
+#[derive(Debug, Clone, Default)]
+pub struct PackMeta {
+  pub description: String,
+  pub pack_format: Option<i64>,
+  // inclusive range of `supported_formats` (1.20.2+) or `min_format`/`max_format` (1.21.9+)
+  pub supported_formats: Option<(i64, i64)>,
+  pub overlays: Vec<PackOverlay>,
+}
+
+impl PackMeta {
+  pub fn supports_format(&self, format: i64) -> bool {
+    match self.supported_formats {
+      Some((min, max)) => min <= format && format <= max,
+      None => self.pack_format == Some(format),
+    }
+  }
+}
+
+// description may be a plain string or a text component (object / array).
+fn flatten_text_component(value: &serde_json::Value) -> String {
+  match value {
+    serde_json::Value::String(text) => text.clone(),
+    serde_json::Value::Array(parts) => parts.iter().map(flatten_text_component).collect(),
+    serde_json::Value::Object(obj) => {
+      let mut text = obj
+        .get("text")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string();
+      if let Some(extra) = obj.get("extra") {
+        text.push_str(&flatten_text_component(extra));
+      }
+      text
+    }
+    serde_json::Value::Null => String::new(),
+    other => other.to_string(),
+  }
+}
+
+// format versions are integers, or `[major, minor]` since 1.21.9, only the major one is kept.
+fn parse_format_version(value: &serde_json::Value) -> Option<i64> {
+  match value {
+    serde_json::Value::Array(parts) => parts.first().and_then(|v| v.as_i64()),
+    _ => value.as_i64(),
+  }
+}
+
+// inclusive format range, in `supported_formats`/`formats` (a single format, `[min, max]` or
+// `{min_inclusive, max_inclusive}`), or in `min_format`/`max_format` since 1.21.9.
+fn parse_format_range(data: &serde_json::Value, key: &str) -> Option<(i64, i64)> {
+  match data.get(key) {
+    Some(serde_json::Value::Array(range)) if range.len() == 2 => {
+      range[0].as_i64().zip(range[1].as_i64())
+    }
+    Some(serde_json::Value::Object(range)) => range
+      .get("min_inclusive")
+      .and_then(|v| v.as_i64())
+      .zip(range.get("max_inclusive").and_then(|v| v.as_i64())),
+    Some(value) => value.as_i64().map(|format| (format, format)),
+    None => data
+      .get("min_format")
+      .and_then(parse_format_version)
+      .zip(data.get("max_format").and_then(parse_format_version)),
+  }
+}
+
+pub fn parse_pack_mcmeta(contents: &str) -> SJMCLResult<PackMeta> {
+  // Check for and remove the UTF-8 BOM if present
+  let contents = contents.strip_prefix('\u{FEFF}').unwrap_or(contents);
+  let json_result = serde_json::from_str::<serde_json::Value>(contents)?;
+  let Some(pack_data) = json_result.get("pack") else {
+    return Ok(PackMeta::default());
+  };
+
+  let supported_formats = parse_format_range(pack_data, "supported_formats");
+  let overlays = json_result
+    .get("overlays")
+    .and_then(|overlays| overlays.get("entries"))
+    .and_then(|entries| entries.as_array())
+    .map(|entries| {
+      entries
+        .iter()
+        .filter_map(|entry| {
+          Some(PackOverlay {
+            directory: entry.get("directory")?.as_str()?.to_string(),
+            formats: parse_format_range(entry, "formats"),
+          })
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+
+  Ok(PackMeta {
+    description: pack_data
+      .get("description")
+      .map(flatten_text_component)
+      .unwrap_or_default(),
+    pack_format: pack_data
+      .get("pack_format")
+      .and_then(parse_format_version)
+      .or(supported_formats.map(|(_, max)| max)),
+    supported_formats,
+    overlays,
+  })
+}
+
+pub fn load_pack_meta_from_zip(path: &Path) -> SJMCLResult<(PackMeta, Option<RgbaImage>)> {
+  let file = fs::File::open(path)?;
+  let mut zip = ZipArchive::new(file)?;
+  let meta = {
+    let mut file = zip.by_name("pack.mcmeta").map_err(|_| {
+      SJMCLError(format!(
+        "pack.mcmeta not found in zip file '{}'",
+        path.to_str().unwrap_or("")
+      ))
+    })?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    parse_pack_mcmeta(&contents)?
+  };
+
+  let icon_src = load_image_from_jar(&mut zip, "pack.png");
+  Ok((meta, icon_src))
+}
+
+pub async fn load_pack_meta_from_dir(path: &Path) -> SJMCLResult<(PackMeta, Option<RgbaImage>)> {
+  let contents = tokio::fs::read_to_string(path.join("pack.mcmeta"))
+    .await
+    .map_err(|_| {
+      SJMCLError(format!(
+        "pack.mcmeta not found in '{}'",
+        path.to_str().unwrap_or("")
+      ))
+    })?;
+  let meta = parse_pack_mcmeta(&contents)?;
+
+  let icon_src = load_image_from_dir_async(&path.join("pack.png")).await;
+  Ok((meta, icon_src))
+}
+
+const RESOURCE_PACKS_KEY: &str = "resourcePacks";
+const INCOMPATIBLE_RESOURCE_PACKS_KEY: &str = "incompatibleResourcePacks";
+const FILE_PACK_ID_PREFIX: &str = "file/";
+
+// since 1.13 (pack format 4), packs in the resourcepacks folder are listed as `file/<file name>`,
+// before that only the file name is listed.
+fn use_file_pack_id_prefix(options: &GameOptions, expected_format: Option<i64>) -> bool {
+  match expected_format {
+    Some(format) => format >= 4,
+    None => {
+      let pack_ids = options.get_string_list(RESOURCE_PACKS_KEY);
+      pack_ids.is_empty()
+        || pack_ids
+          .iter()
+          .any(|id| id == "vanilla" || id.starts_with(FILE_PACK_ID_PREFIX))
+    }
+  }
+}
+
+/// Returns the file name if the pack id refers to a pack in the resourcepacks folder.
+/// Other ids are built-in packs (e.g. `vanilla`) or provided by mods.
+fn get_folder_pack_file_name(pack_id: &str, prefixed: bool) -> Option<&str> {
+  if prefixed {
+    pack_id.strip_prefix(FILE_PACK_ID_PREFIX)
+  } else {
+    Some(pack_id)
+  }
+}
+
+/// Returns the file names of the active resource packs in the resourcepacks folder,
+/// from the highest priority (the top one in game) to the lowest.
+pub fn get_active_resource_packs(options: &GameOptions) -> Vec<String> {
+  let prefixed = use_file_pack_id_prefix(options, None);
+  options
+    .get_string_list(RESOURCE_PACKS_KEY)
+    .iter()
+    .rev()
+    .filter_map(|id| get_folder_pack_file_name(id, prefixed))
+    .map(|name| name.to_string())
+    .collect()
+}
+
+/// Sets the active resource packs by their file names, from the highest priority to the lowest.
+/// Other packs (built-in or provided by mods) keep their positions, the folder packs take the
+/// places of the previously active ones and the rest are put on the top. The packs in
+/// `incompatible_packs` are also confirmed in `incompatibleResourcePacks`, or the game would
+/// deactivate them on load.
+pub fn set_active_resource_packs(
+  options: &mut GameOptions,
+  file_names: &[String],
+  incompatible_packs: &[String],
+  expected_format: Option<i64>,
+) {
+  let prefixed = use_file_pack_id_prefix(options, expected_format);
+  let to_pack_id = |file_name: &String| {
+    if prefixed {
+      format!("{}{}", FILE_PACK_ID_PREFIX, file_name)
+    } else {
+      file_name.clone()
+    }
+  };
+  let current_prefixed = use_file_pack_id_prefix(options, None);
+
+  // the game lists packs from the bottom to the top
+  let mut folder_pack_ids = file_names.iter().rev().map(&to_pack_id);
+  let mut pack_ids = Vec::new();
+  for id in options.get_string_list(RESOURCE_PACKS_KEY) {
+    if get_folder_pack_file_name(&id, current_prefixed).is_some() {
+      pack_ids.extend(folder_pack_ids.next());
+    } else {
+      pack_ids.push(id);
+    }
+  }
+  pack_ids.extend(folder_pack_ids);
+  if prefixed && !pack_ids.iter().any(|id| id == "vanilla") {
+    pack_ids.insert(0, "vanilla".to_string());
+  }
+  options.set_string_list(RESOURCE_PACKS_KEY, &pack_ids);
+
+  let incompatible_ids: Vec<String> = file_names
+    .iter()
+    .filter(|name| incompatible_packs.contains(*name))
+    .map(&to_pack_id)
+    .collect();
+  options.set_string_list(INCOMPATIBLE_RESOURCE_PACKS_KEY, &incompatible_ids);
+}
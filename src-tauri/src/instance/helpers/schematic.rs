@@ -0,0 +1,424 @@
+use crate::{
+  error::SJMCLResult,
+  instance::models::misc::{InstanceError, SchematicFormat, SchematicInfo, SchematicMaterial},
+};
+use quartz_nbt::{
+  io::{read_nbt, Flavor},
+  NbtCompound, NbtList, NbtTag,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+// not counted in the material list
+const AIR_BLOCKS: &[&str] = &[
+  "minecraft:air",
+  "minecraft:cave_air",
+  "minecraft:void_air",
+  "minecraft:structure_void",
+];
+
+type BlockCounts = HashMap<String, u64>;
+
+// integers are stored as byte, short or int depending on the format and the writer
+fn get_int(compound: &NbtCompound, key: &str) -> Option<i64> {
+  match compound.inner().get(key)? {
+    NbtTag::Byte(v) => Some(*v as i64),
+    NbtTag::Short(v) => Some(*v as i64),
+    NbtTag::Int(v) => Some(*v as i64),
+    NbtTag::Long(v) => Some(*v),
+    _ => None,
+  }
+}
+
+fn get_string(compound: &NbtCompound, key: &str) -> Option<String> {
+  compound
+    .get::<_, &str>(key)
+    .ok()
+    .map(str::to_string)
+    .filter(|s| !s.is_empty())
+}
+
+fn get_vec3(compound: &NbtCompound, key: &str) -> Option<(i64, i64, i64)> {
+  let vec = compound.get::<_, &NbtCompound>(key).ok()?;
+  Some((get_int(vec, "x")?, get_int(vec, "y")?, get_int(vec, "z")?))
+}
+
+// `minecraft:oak_stairs[facing=north]` -> `minecraft:oak_stairs`
+fn get_block_name(block_state: &str) -> String {
+  let name = block_state.split('[').next().unwrap_or(block_state);
+  if name.contains(':') {
+    name.to_string()
+  } else {
+    format!("minecraft:{}", name)
+  }
+}
+
+fn get_palette_names(palette: &NbtList) -> Vec<String> {
+  palette
+    .iter()
+    .map(|tag| match tag {
+      NbtTag::Compound(state) => get_string(state, "Name")
+        .map(|name| get_block_name(&name))
+        .unwrap_or_default(),
+      _ => String::new(),
+    })
+    .collect()
+}
+
+fn add_block_count(counts: &mut BlockCounts, name: &str, count: u64) {
+  if count == 0 || name.is_empty() || AIR_BLOCKS.contains(&name) {
+    return;
+  }
+  *counts.entry(name.to_string()).or_default() += count;
+}
+
+// Litematica packs the palette indexes tightly, an entry may span two longs.
+fn count_litematica_block_states(
+  palette: &[String],
+  block_states: &[i64],
+  volume: u64,
+  counts: &mut BlockCounts,
+) {
+  let bits = (usize::BITS - palette.len().saturating_sub(1).leading_zeros()).max(2) as u64;
+  let mask = (1u64 << bits) - 1;
+  let mut index_counts = vec![0u64; palette.len()];
+  for i in 0..volume {
+    let start_bit = i * bits;
+    let start_long = (start_bit / 64) as usize;
+    let end_long = ((start_bit + bits - 1) / 64) as usize;
+    let offset = start_bit % 64;
+    let (Some(&start), Some(&end)) = (block_states.get(start_long), block_states.get(end_long))
+    else {
+      break;
+    };
+    let value = if start_long == end_long {
+      ((start as u64) >> offset) & mask
+    } else {
+      (((start as u64) >> offset) | ((end as u64) << (64 - offset))) & mask
+    };
+    if let Some(count) = index_counts.get_mut(value as usize) {
+      *count += 1;
+    }
+  }
+  for (name, count) in palette.iter().zip(index_counts) {
+    add_block_count(counts, name, count);
+  }
+}
+
+fn parse_litematica(root: &NbtCompound, counts: Option<&mut BlockCounts>) -> Option<SchematicInfo> {
+  let metadata = root.get::<_, &NbtCompound>("Metadata").ok()?;
+  let regions = root.get::<_, &NbtCompound>("Regions").ok()?;
+  let (width, height, length) = get_vec3(metadata, "EnclosingSize").unwrap_or_default();
+
+  if let Some(counts) = counts {
+    for (_, region) in regions.inner().iter() {
+      let NbtTag::Compound(region) = region else {
+        continue;
+      };
+      let (Some((x, y, z)), Ok(palette), Ok(block_states)) = (
+        get_vec3(region, "Size"),
+        region.get::<_, &NbtList>("BlockStatePalette"),
+        region.get::<_, &[i64]>("BlockStates"),
+      ) else {
+        continue;
+      };
+      // the size is negative if the region is selected backwards, a broken file may overflow it
+      let volume = x
+        .unsigned_abs()
+        .checked_mul(y.unsigned_abs())
+        .and_then(|xy| xy.checked_mul(z.unsigned_abs()))?;
+      count_litematica_block_states(&get_palette_names(palette), block_states, volume, counts);
+    }
+  }
+
+  Some(SchematicInfo {
+    format: SchematicFormat::Litematica,
+    version: get_int(root, "Version"),
+    width,
+    height,
+    length,
+    region_count: get_int(metadata, "RegionCount").unwrap_or(regions.len() as i64),
+    display_name: get_string(metadata, "Name"),
+    author: get_string(metadata, "Author"),
+    description: get_string(metadata, "Description"),
+    created_at: get_int(metadata, "TimeCreated").map(|t| t / 1000),
+    ..Default::default()
+  })
+}
+
+// Sponge block data is a sequence of varints, one palette index per block.
+fn count_sponge_block_data(palette: &NbtCompound, block_data: &[i8], counts: &mut BlockCounts) {
+  let mut names: HashMap<i64, String> = HashMap::new();
+  for (name, id) in palette.inner().iter() {
+    if let NbtTag::Int(id) = id {
+      names.insert(*id as i64, get_block_name(name));
+    }
+  }
+
+  let mut index_counts: HashMap<i64, u64> = HashMap::new();
+  let mut value: i64 = 0;
+  let mut shift = 0;
+  for byte in block_data.iter().map(|b| *b as u8) {
+    value |= ((byte & 0x7f) as i64) << shift;
+    if byte & 0x80 != 0 {
+      shift += 7;
+      if shift > 35 {
+        return; // malformed varint
+      }
+      continue;
+    }
+    *index_counts.entry(value).or_default() += 1;
+    value = 0;
+    shift = 0;
+  }
+  for (index, count) in index_counts {
+    if let Some(name) = names.get(&index) {
+      add_block_count(counts, name, count);
+    }
+  }
+}
+
+fn parse_sponge(root: &NbtCompound, counts: Option<&mut BlockCounts>) -> Option<SchematicInfo> {
+  // version 3 wraps everything in a `Schematic` compound
+  let schematic = root.get::<_, &NbtCompound>("Schematic").unwrap_or(root);
+  let version = get_int(schematic, "Version")?;
+  let metadata = schematic.get::<_, &NbtCompound>("Metadata").ok();
+
+  if let Some(counts) = counts {
+    let (palette, block_data) = if version >= 3 {
+      let blocks = schematic.get::<_, &NbtCompound>("Blocks").ok();
+      (
+        blocks.and_then(|b| b.get::<_, &NbtCompound>("Palette").ok()),
+        blocks.and_then(|b| b.get::<_, &[i8]>("Data").ok()),
+      )
+    } else {
+      (
+        schematic.get::<_, &NbtCompound>("Palette").ok(),
+        schematic.get::<_, &[i8]>("BlockData").ok(),
+      )
+    };
+    if let (Some(palette), Some(block_data)) = (palette, block_data) {
+      count_sponge_block_data(palette, block_data, counts);
+    }
+  }
+
+  Some(SchematicInfo {
+    format: SchematicFormat::Sponge,
+    version: Some(version),
+    width: get_int(schematic, "Width")?,
+    height: get_int(schematic, "Height")?,
+    length: get_int(schematic, "Length")?,
+    region_count: 1,
+    display_name: metadata.and_then(|m| get_string(m, "Name")),
+    author: metadata.and_then(|m| get_string(m, "Author")),
+    created_at: metadata.and_then(|m| get_int(m, "Date")).map(|t| t / 1000),
+    ..Default::default()
+  })
+}
+
+// MCEdit stores the legacy numeric block ids, the names are only known if Schematica
+// wrote its id mapping.
+// 4 extra high bits of the block id, two blocks per byte: the even block in the low nibble
+// and the odd block in the high nibble, same as WorldEdit.
+fn get_add_block_bits(add_blocks: &[i8], index: usize) -> u8 {
+  let add = add_blocks.get(index >> 1).map_or(0, |b| *b as u8);
+  if index & 1 == 0 {
+    add & 0x0f
+  } else {
+    add >> 4
+  }
+}
+
+fn parse_mcedit(root: &NbtCompound, counts: Option<&mut BlockCounts>) -> Option<SchematicInfo> {
+  let blocks = root.get::<_, &[i8]>("Blocks").ok()?;
+
+  if let Some(counts) = counts {
+    let add_blocks = root.get::<_, &[i8]>("AddBlocks").ok();
+    let mut names: HashMap<i64, String> = HashMap::new();
+    if let Ok(mapping) = root.get::<_, &NbtCompound>("SchematicaMapping") {
+      for (name, id) in mapping.inner().iter() {
+        if let NbtTag::Short(id) = id {
+          names.insert(*id as i64, get_block_name(name));
+        }
+      }
+    }
+    let mut id_counts: HashMap<i64, u64> = HashMap::new();
+    for (i, block) in blocks.iter().enumerate() {
+      let mut id = *block as u8 as i64;
+      if let Some(add_blocks) = add_blocks {
+        id |= (get_add_block_bits(add_blocks, i) as i64) << 8;
+      }
+      *id_counts.entry(id).or_default() += 1;
+    }
+    for (id, count) in id_counts {
+      let name = match names.get(&id) {
+        Some(name) => name.clone(),
+        None if id == 0 => "minecraft:air".to_string(),
+        None => format!("legacy:{}", id),
+      };
+      add_block_count(counts, &name, count);
+    }
+  }
+
+  Some(SchematicInfo {
+    format: SchematicFormat::MCEdit,
+    width: get_int(root, "Width")?,
+    height: get_int(root, "Height")?,
+    length: get_int(root, "Length")?,
+    region_count: 1,
+    ..Default::default()
+  })
+}
+
+fn parse_structure(root: &NbtCompound, counts: Option<&mut BlockCounts>) -> Option<SchematicInfo> {
+  let size = root.get::<_, &NbtList>("size").ok()?;
+  let size: Vec<i64> = size
+    .iter()
+    .filter_map(|tag| match tag {
+      NbtTag::Int(v) => Some(*v as i64),
+      _ => None,
+    })
+    .collect();
+  let [width, height, length] = size[..] else {
+    return None;
+  };
+
+  if let Some(counts) = counts {
+    // structures with random variants (e.g. shipwrecks) have several palettes, count the first
+    let palette = root.get::<_, &NbtList>("palette").ok().or_else(|| {
+      root
+        .get::<_, &NbtList>("palettes")
+        .ok()
+        .and_then(|palettes| palettes.get::<&NbtList>(0).ok())
+    });
+    if let (Some(palette), Ok(blocks)) = (palette, root.get::<_, &NbtList>("blocks")) {
+      let names = get_palette_names(palette);
+      let mut index_counts = vec![0u64; names.len()];
+      for block in blocks.iter() {
+        if let NbtTag::Compound(block) = block {
+          if let Some(count) =
+            get_int(block, "state").and_then(|s| index_counts.get_mut(s as usize))
+          {
+            *count += 1;
+          }
+        }
+      }
+      for (name, count) in names.iter().zip(index_counts) {
+        add_block_count(counts, name, count);
+      }
+    }
+  }
+
+  Some(SchematicInfo {
+    format: SchematicFormat::Structure,
+    version: get_int(root, "DataVersion"),
+    width,
+    height,
+    length,
+    region_count: 1,
+    author: get_string(root, "author"),
+    ..Default::default()
+  })
+}
+
+fn parse_schematic(path: &Path, counts: Option<&mut BlockCounts>) -> SJMCLResult<SchematicInfo> {
+  let bytes = fs::read(path).map_err(|_| InstanceError::FileNotFoundError)?;
+  // the formats are gzipped by convention, but some tools write them uncompressed
+  let flavor = if bytes.starts_with(&[0x1f, 0x8b]) {
+    Flavor::GzCompressed
+  } else {
+    Flavor::Uncompressed
+  };
+  let (root, _) =
+    read_nbt(&mut Cursor::new(bytes), flavor).map_err(|_| InstanceError::SchematicParseError)?;
+
+  let extension = path
+    .extension()
+    .map(|e| e.to_string_lossy().to_lowercase())
+    .unwrap_or_default();
+  let info = match extension.as_str() {
+    "litematic" => parse_litematica(&root, counts),
+    "schem" => parse_sponge(&root, counts),
+    "nbt" => parse_structure(&root, counts),
+    // some tools save sponge schematics with the legacy extension
+    "schematic" if root.contains_key("Blocks") => parse_mcedit(&root, counts),
+    "schematic" => parse_sponge(&root, counts),
+    _ => None,
+  }
+  .ok_or(InstanceError::SchematicParseError)?;
+
+  Ok(SchematicInfo {
+    name: path
+      .file_stem()
+      .unwrap_or_default()
+      .to_string_lossy()
+      .to_string(),
+    file_path: path.to_path_buf(),
+    width: info.width.abs(),
+    height: info.height.abs(),
+    length: info.length.abs(),
+    ..info
+  })
+}
+
+pub fn load_schematic_info(path: &Path) -> SJMCLResult<SchematicInfo> {
+  parse_schematic(path, None)
+}
+
+/// Builds the list entry of a schematic that can not be parsed, with the format guessed
+/// from its extension and the other metadata left empty.
+pub fn get_unparsed_schematic_info(path: &Path) -> SchematicInfo {
+  let format = match path
+    .extension()
+    .map(|e| e.to_string_lossy().to_lowercase())
+    .as_deref()
+  {
+    Some("schem") => SchematicFormat::Sponge,
+    Some("schematic") => SchematicFormat::MCEdit,
+    Some("nbt") => SchematicFormat::Structure,
+    _ => SchematicFormat::Litematica,
+  };
+  SchematicInfo {
+    name: path
+      .file_stem()
+      .unwrap_or_default()
+      .to_string_lossy()
+      .to_string(),
+    file_path: path.to_path_buf(),
+    format,
+    parse_failed: true,
+    ..Default::default()
+  }
+}
+
+/// Counts the blocks of a schematic by block id (without states), most used first.
+/// Air and structure voids are left out.
+pub fn load_schematic_materials(path: &Path) -> SJMCLResult<Vec<SchematicMaterial>> {
+  let mut counts = BlockCounts::new();
+  parse_schematic(path, Some(&mut counts))?;
+  let mut materials: Vec<SchematicMaterial> = counts
+    .into_iter()
+    .map(|(name, count)| SchematicMaterial { name, count })
+    .collect();
+  materials.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(&b.name)));
+  Ok(materials)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_get_add_block_bits() {
+    // 0x21: block 0 -> 0x1, block 1 -> 0x2
+    let add_blocks = [0x21u8 as i8, 0xf0u8 as i8];
+    assert_eq!(get_add_block_bits(&add_blocks, 0), 0x1);
+    assert_eq!(get_add_block_bits(&add_blocks, 1), 0x2);
+    assert_eq!(get_add_block_bits(&add_blocks, 2), 0x0);
+    assert_eq!(get_add_block_bits(&add_blocks, 3), 0xf);
+    // blocks past the end of AddBlocks have no extra bits
+    assert_eq!(get_add_block_bits(&add_blocks, 4), 0x0);
+  }
+}
@@ -0,0 +1,175 @@
+use crate::{
+  error::SJMCLResult,
+  instance::{
+    constants::{SCREENSHOT_THUMBNAIL_DIR_NAME, SCREENSHOT_THUMBNAIL_READY_EVENT},
+    models::misc::{InstanceError, ScreenshotInfo, ScreenshotThumbnailPayload},
+  },
+  utils::fs::{generate_unique_filename, get_files_with_regex},
+};
+use image::{imageops::FilterType, ImageFormat};
+use regex::RegexBuilder;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::{path::BaseDirectory, AppHandle, Emitter, Manager};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+// the longer side of a thumbnail, in pixels
+const SCREENSHOT_THUMBNAIL_SIZE: u32 = 320;
+
+pub fn get_screenshot_paths(screenshots_dir: &Path) -> SJMCLResult<Vec<PathBuf>> {
+  if !screenshots_dir.exists() {
+    return Ok(Vec::new());
+  }
+  // The default screenshot format in Minecraft is PNG. For broader compatibility, JPG and JPEG formats are also included here.
+  let valid_extensions = RegexBuilder::new(r"\.(jpg|jpeg|png)$")
+    .case_insensitive(true)
+    .build()
+    .unwrap();
+  get_files_with_regex(screenshots_dir, &valid_extensions)
+}
+
+pub fn get_screenshot_thumbnail_dir(app: &AppHandle) -> SJMCLResult<PathBuf> {
+  Ok(app.path().resolve::<PathBuf>(
+    SCREENSHOT_THUMBNAIL_DIR_NAME.into(),
+    BaseDirectory::AppCache,
+  )?)
+}
+
+/// The thumbnail is keyed by the hash of the path, size and modification time of the screenshot,
+/// so an edited or replaced screenshot gets a new thumbnail without reading the whole file.
+fn get_screenshot_thumbnail_path(
+  thumbnail_dir: &Path,
+  path: &Path,
+  size: u64,
+  time: u64,
+) -> PathBuf {
+  let mut hasher = Sha1::new();
+  hasher.update(path.to_string_lossy().as_bytes());
+  hasher.update(size.to_le_bytes());
+  hasher.update(time.to_le_bytes());
+  thumbnail_dir.join(format!("{}.jpg", hex::encode(hasher.finalize())))
+}
+
+/// Reads the metadata and the dimensions (only the image header) of a screenshot.
+pub fn get_screenshot_info(path: &Path, thumbnail_dir: &Path) -> SJMCLResult<ScreenshotInfo> {
+  let metadata = path.metadata()?;
+  let time = metadata
+    .modified()?
+    .duration_since(SystemTime::UNIX_EPOCH)?
+    .as_secs();
+  let (width, height) = image::image_dimensions(path).unwrap_or_default();
+  Ok(ScreenshotInfo {
+    file_name: path.file_stem().unwrap().to_string_lossy().to_string(),
+    file_path: path.to_path_buf(),
+    time,
+    size: metadata.len(),
+    width,
+    height,
+    thumbnail_path: get_screenshot_thumbnail_path(thumbnail_dir, path, metadata.len(), time),
+  })
+}
+
+fn generate_screenshot_thumbnail(src: &Path, dst: &Path) -> SJMCLResult<()> {
+  let thumbnail = image::open(src)
+    .map_err(|_| InstanceError::ScreenshotParseError)?
+    .resize(
+      SCREENSHOT_THUMBNAIL_SIZE,
+      SCREENSHOT_THUMBNAIL_SIZE,
+      FilterType::Triangle,
+    )
+    .to_rgb8();
+  if let Some(parent) = dst.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  // the list may be retrieved again while generating, never expose half of a thumbnail
+  let temp_path = dst.with_extension("jpg.tmp");
+  thumbnail
+    .save_with_format(&temp_path, ImageFormat::Jpeg)
+    .map_err(|_| InstanceError::FileCreationFailed)?;
+  fs::rename(&temp_path, dst)?;
+  Ok(())
+}
+
+/// Generates the missing thumbnails on a background task, and notifies the frontend of each one.
+pub fn generate_missing_screenshot_thumbnails(app: &AppHandle, screenshots: &[ScreenshotInfo]) {
+  let missing: Vec<(PathBuf, PathBuf)> = screenshots
+    .iter()
+    .filter(|s| !s.thumbnail_path.exists())
+    .map(|s| (s.file_path.clone(), s.thumbnail_path.clone()))
+    .collect();
+  if missing.is_empty() {
+    return;
+  }
+
+  let app = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    for (file_path, thumbnail_path) in missing {
+      // generated by a previous call in the meantime
+      if thumbnail_path.exists() {
+        continue;
+      }
+      if let Err(e) = generate_screenshot_thumbnail(&file_path, &thumbnail_path) {
+        log::error!(
+          "Failed to generate thumbnail of {}: {}",
+          file_path.display(),
+          e.0
+        );
+        continue;
+      }
+      let _ = app.emit(
+        SCREENSHOT_THUMBNAIL_READY_EVENT,
+        ScreenshotThumbnailPayload {
+          file_path,
+          thumbnail_path,
+        },
+      );
+    }
+  });
+}
+
+/// Moves a screenshot into `target_dir`, renaming it if the name is taken.
+pub fn move_screenshot(file_path: &Path, target_dir: &Path) -> SJMCLResult<PathBuf> {
+  let file_name = file_path
+    .file_name()
+    .ok_or(InstanceError::FileNotFoundError)?;
+  fs::create_dir_all(target_dir).map_err(|_| InstanceError::FolderCreationFailed)?;
+  let dest_path = generate_unique_filename(target_dir, file_name);
+  if fs::rename(file_path, &dest_path).is_err() {
+    // across file systems
+    fs::copy(file_path, &dest_path).map_err(|_| InstanceError::FileMoveFailed)?;
+    fs::remove_file(file_path).map_err(|_| InstanceError::FileMoveFailed)?;
+  }
+  Ok(dest_path)
+}
+
+/// Collects the screenshots into `save_path`, grouped by the instance name.
+/// Writes a zip file if `as_zip`, or copies them into the `save_path` folder otherwise.
+pub fn write_screenshots_export(
+  screenshots: &[(String, PathBuf)], // (instance name, screenshot path)
+  save_path: &Path,
+  as_zip: bool,
+) -> SJMCLResult<()> {
+  if as_zip {
+    let zip_file = fs::File::create(save_path).map_err(|_| InstanceError::FileCreationFailed)?;
+    let mut zip = ZipWriter::new(zip_file);
+    // screenshots are compressed already
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    for (instance_name, path) in screenshots {
+      let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+      zip.start_file(format!("{}/{}", instance_name, file_name), options)?;
+      zip.write_all(&fs::read(path)?)?;
+    }
+    zip.finish()?;
+  } else {
+    for (instance_name, path) in screenshots {
+      let dest_dir = save_path.join(instance_name);
+      fs::create_dir_all(&dest_dir).map_err(|_| InstanceError::FolderCreationFailed)?;
+      let dest_path = generate_unique_filename(&dest_dir, path.file_name().unwrap_or_default());
+      fs::copy(path, dest_path).map_err(|_| InstanceError::FileCopyFailed)?;
+    }
+  }
+  Ok(())
+}
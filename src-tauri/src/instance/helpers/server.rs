@@ -1,6 +1,9 @@
 use crate::error::{SJMCLError, SJMCLResult};
-use quartz_nbt::io::Flavor;
+use crate::instance::models::misc::{GameServerEntry, InstanceError};
+use quartz_nbt::io::{read_nbt, write_nbt, Flavor};
+use quartz_nbt::{NbtCompound, NbtList, NbtTag};
 use serde::{self, Deserialize, Serialize};
+use std::fs;
 use std::path::Path;
 use tauri_plugin_http::reqwest;
 
@@ -9,6 +12,9 @@ pub struct NbtServerInfo {
   pub ip: String,
   pub icon: Option<String>,
   pub name: String,
+  #[serde(rename = "acceptTextures")]
+  pub accept_textures: Option<i8>, // nbt has no boolean type, saved as bytes
+  pub hidden: Option<i8>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -26,6 +32,113 @@ pub async fn load_servers_info_from_path(path: &Path) -> SJMCLResult<Vec<NbtServ
   Ok(servers_info.servers)
 }
 
+// read servers.dat as raw nbt compounds, so that unknown tags can be kept when writing back.
+pub fn load_servers_nbt_from_path(path: &Path) -> SJMCLResult<Vec<NbtCompound>> {
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let mut file = fs::File::open(path)?;
+  let (root, _) =
+    read_nbt(&mut file, Flavor::Uncompressed).map_err(|_| InstanceError::ServerNbtReadError)?;
+  let servers = match root.get::<_, &NbtList>("servers") {
+    Ok(list) => list
+      .iter()
+      .filter_map(|tag| match tag {
+        NbtTag::Compound(compound) => Some(compound.clone()),
+        _ => None,
+      })
+      .collect(),
+    Err(_) => Vec::new(),
+  };
+  Ok(servers)
+}
+
+/// Writes servers.dat the same way as the game does: write to a temporary file first,
+/// keep the previous one as `servers.dat_old`, then replace it.
+pub fn save_servers_nbt_to_path(path: &Path, servers: &[NbtCompound]) -> SJMCLResult<()> {
+  let dir = path.parent().ok_or(InstanceError::ServerNbtSaveError)?;
+  fs::create_dir_all(dir).map_err(|_| InstanceError::ServerNbtSaveError)?;
+  let mut root = NbtCompound::new();
+  root.insert(
+    "servers",
+    NbtList::from(
+      servers
+        .iter()
+        .cloned()
+        .map(NbtTag::from)
+        .collect::<Vec<_>>(),
+    ),
+  );
+
+  let temp_path = dir.join("servers.dat_new");
+  {
+    let mut file = fs::File::create(&temp_path).map_err(|_| InstanceError::ServerNbtSaveError)?;
+    write_nbt(&mut file, Some(""), &root, Flavor::Uncompressed)
+      .map_err(|_| InstanceError::ServerNbtSaveError)?;
+    file.sync_all()?;
+  }
+
+  if path.exists() {
+    fs::copy(path, dir.join("servers.dat_old")).map_err(|_| InstanceError::ServerNbtSaveError)?;
+  }
+  fs::rename(&temp_path, path).map_err(|_| InstanceError::ServerNbtSaveError)?;
+  Ok(())
+}
+
+pub fn get_server_address(server: &NbtCompound) -> &str {
+  server.get::<_, &str>("ip").unwrap_or_default()
+}
+
+/// Normalizes a server address to find duplicates, e.g. `Example.com:25565` -> `example.com`.
+pub fn normalize_server_address(address: &str) -> String {
+  let address = address.trim().to_lowercase();
+  match address.strip_suffix(":25565") {
+    Some(host) => host.to_string(),
+    None => address,
+  }
+}
+
+pub fn create_server_nbt(entry: &GameServerEntry) -> NbtCompound {
+  let mut server = NbtCompound::new();
+  apply_server_entry(&mut server, entry);
+  server
+}
+
+/// Writes the fields of `entry` into a server compound, keeping the other tags.
+/// The icon is kept unless a new one is given, and dropped if the address changes without one,
+/// as the game does.
+pub fn apply_server_entry(server: &mut NbtCompound, entry: &GameServerEntry) {
+  let address_changed =
+    normalize_server_address(get_server_address(server)) != normalize_server_address(&entry.ip);
+  server.insert("name", entry.name.clone());
+  server.insert("ip", entry.ip.clone());
+  match &entry.icon {
+    Some(icon) if !icon.is_empty() => {
+      server.insert("icon", icon.clone());
+    }
+    Some(_) => {
+      server.inner_mut().remove("icon");
+    }
+    None if address_changed => {
+      server.inner_mut().remove("icon");
+    }
+    None => {}
+  }
+  match entry.accept_textures {
+    Some(accept) => {
+      server.insert("acceptTextures", accept as i8);
+    }
+    None => {
+      server.inner_mut().remove("acceptTextures"); // prompt on joining
+    }
+  }
+  if entry.hidden {
+    server.insert("hidden", 1i8);
+  } else {
+    server.inner_mut().remove("hidden");
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SjmcServerQueryResult {
   pub online: bool,
@@ -0,0 +1,265 @@
+use crate::error::SJMCLResult;
+use crate::instance::helpers::client_json::McClientInfo;
+use crate::instance::models::misc::{
+  InstanceError, LocalModInfo, ShaderLoaderType, ShaderPackInfo, ShaderPackIssue,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// Parses the content of a Java properties file, as used by shaders.properties and the option files.
+/// Line continuations (trailing `\`) are joined, and comments (`#` or `!`) are skipped,
+/// which also covers the preprocessor directives in shaders.properties.
+pub fn parse_properties(content: &str) -> Vec<(String, String)> {
+  let mut entries = Vec::new();
+  let mut logical_line = String::new();
+  for line in content.lines() {
+    let line = line.trim_start();
+    if logical_line.is_empty() && (line.is_empty() || line.starts_with(['#', '!'])) {
+      continue;
+    }
+    if let Some(stripped) = line.strip_suffix('\\') {
+      logical_line.push_str(stripped);
+      continue;
+    }
+    logical_line.push_str(line);
+
+    let Some(pos) = logical_line.find(['=', ':']) else {
+      logical_line.clear();
+      continue;
+    };
+    let key = logical_line[..pos].trim().to_string();
+    let value = logical_line[pos + 1..].trim().to_string();
+    if !key.is_empty() {
+      entries.push((key, value));
+    }
+    logical_line.clear();
+  }
+  entries
+}
+
+/// The files inside a shader pack, which is either a zip file or an extracted folder.
+trait ShaderPackSource {
+  fn read_to_string(&mut self, name: &str) -> Option<String>;
+  fn entry_names(&mut self) -> Vec<String>; // relative paths, using `/` as separator
+}
+
+struct ZipShaderPack(ZipArchive<fs::File>);
+
+impl ShaderPackSource for ZipShaderPack {
+  fn read_to_string(&mut self, name: &str) -> Option<String> {
+    let mut file = self.0.by_name(name).ok()?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).ok()?;
+    // some packs are saved in Latin-1
+    Some(String::from_utf8_lossy(&bytes).to_string())
+  }
+
+  fn entry_names(&mut self) -> Vec<String> {
+    self.0.file_names().map(|name| name.to_string()).collect()
+  }
+}
+
+struct DirShaderPack(PathBuf);
+
+impl ShaderPackSource for DirShaderPack {
+  fn read_to_string(&mut self, name: &str) -> Option<String> {
+    let bytes = fs::read(self.0.join(name)).ok()?;
+    Some(String::from_utf8_lossy(&bytes).to_string())
+  }
+
+  fn entry_names(&mut self) -> Vec<String> {
+    // only the direct children of `shaders` are needed
+    let Ok(entries) = fs::read_dir(self.0.join("shaders")) else {
+      return Vec::new();
+    };
+    entries
+      .flatten()
+      .map(|entry| {
+        let suffix = if entry.path().is_dir() { "/" } else { "" };
+        format!("shaders/{}{}", entry.file_name().to_string_lossy(), suffix)
+      })
+      .collect()
+  }
+}
+
+fn is_dimension_folder(name: &str) -> bool {
+  name
+    .strip_prefix("world")
+    .map_or(false, |id| id.parse::<i32>().is_ok())
+}
+
+fn load_shaderpack_info_from_source(
+  source: &mut dyn ShaderPackSource,
+  path: &Path,
+  is_dir: bool,
+) -> ShaderPackInfo {
+  let file_name = if is_dir {
+    path.file_name().unwrap_or_default()
+  } else {
+    path.file_stem().unwrap_or_default()
+  }
+  .to_string_lossy()
+  .to_string();
+  let mut info = ShaderPackInfo {
+    file_name,
+    file_path: path.to_path_buf(),
+    is_dir,
+    has_options_file: get_shaderpack_options_path(path).is_file(),
+    ..Default::default()
+  };
+
+  let entry_names = source.entry_names();
+  if !entry_names.iter().any(|name| name.starts_with("shaders/")) {
+    info.issue = Some(ShaderPackIssue::InvalidPack);
+    return info;
+  }
+
+  let mut dimensions: Vec<String> = entry_names
+    .iter()
+    .filter_map(|name| name.strip_prefix("shaders/")?.split('/').next())
+    .filter(|name| is_dimension_folder(name))
+    .map(|name| name.to_string())
+    .collect();
+  dimensions.sort();
+  dimensions.dedup();
+  info.dimensions = dimensions;
+
+  if let Some(content) = source.read_to_string("shaders/shaders.properties") {
+    for (key, value) in parse_properties(&content) {
+      if let Some(profile) = key.strip_prefix("profile.") {
+        info.profiles.push(profile.to_string());
+      } else if key == "iris.features.required" {
+        info.required_iris_features = value.split_whitespace().map(String::from).collect();
+      } else if key == "version" {
+        info.version = Some(value);
+      }
+    }
+  }
+  if info.version.is_none() {
+    // the version file shipped by some packs, its first line is the version
+    info.version = ["shaders/version.txt", "version.txt"]
+      .iter()
+      .find_map(|name| source.read_to_string(name))
+      .and_then(|content| content.lines().next().map(|l| l.trim().to_string()))
+      .filter(|version| !version.is_empty());
+  }
+  info
+}
+
+pub fn load_shaderpack_from_zip(path: &Path) -> SJMCLResult<ShaderPackInfo> {
+  let file = fs::File::open(path)?;
+  match ZipArchive::new(file) {
+    Ok(zip) => Ok(load_shaderpack_info_from_source(
+      &mut ZipShaderPack(zip),
+      path,
+      false,
+    )),
+    // keep the broken archive in the list, marked as invalid
+    Err(_) => Ok(ShaderPackInfo {
+      file_name: path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string(),
+      file_path: path.to_path_buf(),
+      has_options_file: get_shaderpack_options_path(path).is_file(),
+      issue: Some(ShaderPackIssue::InvalidPack),
+      ..Default::default()
+    }),
+  }
+}
+
+pub fn load_shaderpack_from_dir(path: &Path) -> SJMCLResult<ShaderPackInfo> {
+  Ok(load_shaderpack_info_from_source(
+    &mut DirShaderPack(path.to_path_buf()),
+    path,
+    true,
+  ))
+}
+
+/// Detects the installed shader loaders, by the local mods and the OptiFine library
+/// which is merged into the client json when installed as a standalone version.
+pub fn detect_shader_loaders(
+  mods: &[LocalModInfo],
+  client_info: Option<&McClientInfo>,
+) -> Vec<ShaderLoaderType> {
+  let mut loaders = Vec::new();
+  for mod_info in mods.iter().filter(|m| m.enabled) {
+    let name = mod_info.name.to_lowercase();
+    let file_name = mod_info.file_name.to_lowercase();
+    let loader = if name == "iris" || name.starts_with("iris ") || file_name.starts_with("iris-") {
+      ShaderLoaderType::Iris
+    } else if name == "oculus" || file_name.starts_with("oculus-") {
+      ShaderLoaderType::Oculus
+    } else if name == "optifine" || file_name.starts_with("optifine") {
+      ShaderLoaderType::OptiFine
+    } else {
+      continue;
+    };
+    if !loaders.contains(&loader) {
+      loaders.push(loader);
+    }
+  }
+
+  let has_optifine_library = client_info.map_or(false, |info| {
+    info
+      .libraries
+      .iter()
+      .chain(info.patches.iter().flat_map(|patch| patch.libraries.iter()))
+      .any(|lib| lib.name.to_lowercase().starts_with("optifine:optifine:"))
+  });
+  if has_optifine_library && !loaders.contains(&ShaderLoaderType::OptiFine) {
+    loaders.push(ShaderLoaderType::OptiFine);
+  }
+  loaders
+}
+
+/// Flags the packs that can not be loaded by any of the installed shader loaders.
+pub fn check_shaderpack_compatibility(info: &mut ShaderPackInfo, loaders: &[ShaderLoaderType]) {
+  if info.issue.is_some() {
+    return;
+  }
+  info.issue = if loaders.is_empty() {
+    Some(ShaderPackIssue::NoShaderLoader)
+  } else if !info.required_iris_features.is_empty()
+    && !loaders
+      .iter()
+      .any(|l| matches!(l, ShaderLoaderType::Iris | ShaderLoaderType::Oculus))
+  {
+    Some(ShaderPackIssue::IrisFeaturesRequired)
+  } else {
+    None
+  };
+}
+
+/// The options of a pack are saved next to it, as `<pack file name>.txt` (e.g. `BSL.zip.txt`).
+pub fn get_shaderpack_options_path(pack_path: &Path) -> PathBuf {
+  let mut file_name = pack_path.file_name().unwrap_or_default().to_os_string();
+  file_name.push(".txt");
+  pack_path.with_file_name(file_name)
+}
+
+pub fn load_shaderpack_options(pack_path: &Path) -> SJMCLResult<BTreeMap<String, String>> {
+  let options_path = get_shaderpack_options_path(pack_path);
+  if !options_path.is_file() {
+    return Ok(BTreeMap::new());
+  }
+  let content = fs::read_to_string(options_path)?;
+  Ok(parse_properties(&content).into_iter().collect())
+}
+
+pub fn save_shaderpack_options(
+  pack_path: &Path,
+  options: &BTreeMap<String, String>,
+) -> SJMCLResult<()> {
+  let content: String = options
+    .iter()
+    .map(|(key, value)| format!("{}={}\n", key, value))
+    .collect();
+  fs::write(get_shaderpack_options_path(pack_path), content)
+    .map_err(|_| InstanceError::FileCreationFailed)?;
+  Ok(())
+}
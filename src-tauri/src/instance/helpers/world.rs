@@ -1,9 +1,21 @@
 use crate::{
   error::{SJMCLError, SJMCLResult},
-  instance::models::world::level::{Level, LevelData},
+  instance::models::{
+    misc::InstanceError,
+    world::level::{Level, LevelData, LevelDataPatch},
+  },
+  launch::models::LaunchingState,
 };
-use quartz_nbt::{io::Flavor, serde::deserialize};
-use std::path::PathBuf;
+use quartz_nbt::{
+  io::{read_nbt, write_nbt, Flavor},
+  serde::deserialize,
+  NbtCompound,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
 
 pub async fn load_level_data_from_path(path: &PathBuf) -> SJMCLResult<LevelData> {
   let nbt_bytes = tokio::fs::read(path).await?;
@@ -44,3 +56,151 @@ pub fn level_data_to_world_info(data: &LevelData) -> SJMCLResult<(i64, String, S
     GAMEMODE_STR[gametype as usize].to_string(),
   ))
 }
+
+/// Checks whether a world is currently opened by a game.
+///
+/// Minecraft keeps `session.lock` locked while the world is open. On Windows the lock
+/// is mandatory, so a failed read is enough. On unix the lock is an advisory `fcntl`
+/// lock (taken by Java's `FileChannel.tryLock`), which can be queried without taking it.
+/// As a fallback, we check whether the instance owning the world is still running.
+pub fn is_world_in_use(app: &AppHandle, instance_id: &str, world_dir: &Path) -> bool {
+  let lock_path = world_dir.join("session.lock");
+  if !lock_path.exists() {
+    return false;
+  }
+
+  #[cfg(target_os = "windows")]
+  {
+    if fs::read(&lock_path).is_err() {
+      return true;
+    }
+  }
+
+  #[cfg(unix)]
+  {
+    if is_session_locked(&lock_path) {
+      return true;
+    }
+  }
+
+  let launching_queue_state = app.state::<Mutex<HashMap<u64, LaunchingState>>>();
+  let launching_queue = launching_queue_state.lock().unwrap();
+  launching_queue
+    .values()
+    .any(|state| state.pid != 0 && state.selected_instance.id == instance_id)
+}
+
+#[cfg(unix)]
+fn is_session_locked(lock_path: &Path) -> bool {
+  use std::os::unix::io::AsRawFd;
+
+  let Ok(file) = fs::OpenOptions::new()
+    .read(true)
+    .write(true)
+    .open(lock_path)
+  else {
+    return false;
+  };
+  // F_GETLK only reports a conflicting lock held by another process, it never locks the file.
+  let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+  lock.l_type = libc::F_WRLCK as _;
+  lock.l_whence = libc::SEEK_SET as _;
+  let ret = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut lock) };
+  ret == 0 && lock.l_type != libc::F_UNLCK as _
+}
+
+// read level.dat as a raw nbt tree, so that unknown tags can be kept when writing back.
+pub fn load_level_nbt_from_path(path: &Path) -> SJMCLResult<(NbtCompound, String)> {
+  let mut file = fs::File::open(path).map_err(|_| InstanceError::LevelNotExistError)?;
+  read_nbt(&mut file, Flavor::GzCompressed).map_err(|_| InstanceError::LevelParseError.into())
+}
+
+/// Writes level.dat the same way as the game does: write to a temporary file first,
+/// keep the previous one as `level.dat_old`, then replace it.
+pub fn save_level_nbt_to_path(path: &Path, root_name: &str, root: &NbtCompound) -> SJMCLResult<()> {
+  let dir = path.parent().ok_or(InstanceError::WorldNotExistError)?;
+  let temp_path = dir.join("level.dat_new");
+  {
+    let mut file = fs::File::create(&temp_path).map_err(|_| InstanceError::LevelSaveError)?;
+    write_nbt(&mut file, Some(root_name), root, Flavor::GzCompressed)
+      .map_err(|_| InstanceError::LevelSaveError)?;
+    file.sync_all()?;
+  }
+
+  if path.exists() {
+    fs::copy(path, dir.join("level.dat_old")).map_err(|_| InstanceError::LevelSaveError)?;
+  }
+  fs::rename(&temp_path, path).map_err(|_| InstanceError::LevelSaveError)?;
+  Ok(())
+}
+
+pub fn apply_level_data_patch(root: &mut NbtCompound, patch: &LevelDataPatch) -> SJMCLResult<()> {
+  let data = root
+    .get_mut::<_, &mut NbtCompound>("Data")
+    .map_err(|_| InstanceError::LevelParseError)?;
+
+  if let Some(ref level_name) = patch.level_name {
+    data.insert("LevelName", level_name.clone());
+  }
+  if let Some(difficulty) = patch.difficulty {
+    if difficulty > 3 {
+      return Err(SJMCLError(format!(
+        "difficulty = {}, which is greater than 3",
+        difficulty
+      )));
+    }
+    data.insert("Difficulty", difficulty as i8);
+  }
+  if let Some(difficulty_locked) = patch.difficulty_locked {
+    data.insert("DifficultyLocked", difficulty_locked);
+  }
+  if let Some(hardcore) = patch.hardcore {
+    data.insert("hardcore", hardcore);
+  }
+  if let Some(allow_commands) = patch.allow_commands {
+    data.insert("allowCommands", allow_commands);
+  }
+  if let Some(spawn_x) = patch.spawn_x {
+    data.insert("SpawnX", spawn_x);
+  }
+  if let Some(spawn_y) = patch.spawn_y {
+    data.insert("SpawnY", spawn_y);
+  }
+  if let Some(spawn_z) = patch.spawn_z {
+    data.insert("SpawnZ", spawn_z);
+  }
+  if let Some(time) = patch.time {
+    data.insert("Time", time);
+  }
+  if let Some(daytime) = patch.daytime {
+    data.insert("DayTime", daytime);
+  }
+  if let Some(clear_weather_time) = patch.clear_weather_time {
+    data.insert("clearWeatherTime", clear_weather_time);
+  }
+  if let Some(raining) = patch.raining {
+    data.insert("raining", raining);
+  }
+  if let Some(rain_time) = patch.rain_time {
+    data.insert("rainTime", rain_time);
+  }
+  if let Some(thundering) = patch.thundering {
+    data.insert("thundering", thundering);
+  }
+  if let Some(thunder_time) = patch.thunder_time {
+    data.insert("thunderTime", thunder_time);
+  }
+  if let Some(ref game_rules) = patch.game_rules {
+    // game rules are all stored as strings in level.dat
+    if !data.contains_key("GameRules") {
+      data.insert("GameRules", NbtCompound::new());
+    }
+    let rules = data
+      .get_mut::<_, &mut NbtCompound>("GameRules")
+      .map_err(|_| InstanceError::LevelParseError)?;
+    for (key, value) in game_rules {
+      rules.insert(key.clone(), value.clone());
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,303 @@
+use crate::{
+  error::SJMCLResult,
+  instance::{
+    helpers::{misc::get_instance_subdir_paths, world::is_world_in_use},
+    models::{
+      misc::{Instance, InstanceError, InstanceSubdirType},
+      world::backup::WorldBackupInfo,
+    },
+  },
+  launcher_config::models::WorldBackupRetention,
+  tasks::{
+    commands::schedule_progressive_task_group, events::GEventStatus, monitor::TaskMonitor,
+    world_backup::WorldBackupParam, PTaskParam,
+  },
+  utils::fs::{create_zip_from_dir, get_subdirectories},
+};
+use chrono::{Datelike, Local, NaiveDateTime, TimeZone};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+use zip::ZipArchive;
+
+// same naming rule as the vanilla "Make Backup" button: `<yyyy-MM-dd_HH-mm-ss>_<world>.zip`
+const BACKUP_TIME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+// files that should never be put into a snapshot.
+pub const BACKUP_EXCLUDED_FILES: &[&str] = &["session.lock"];
+
+lazy_static! {
+  static ref BACKUP_NAME_REGEX: Regex =
+    Regex::new(r"^(\d{4}-\d{2}-\d{2}_\d{2}-\d{2}-\d{2})_(.+)\.zip$").unwrap();
+}
+
+pub fn get_backup_file_name(world_name: &str) -> String {
+  format!(
+    "{}_{}.zip",
+    Local::now().format(BACKUP_TIME_FORMAT),
+    world_name
+  )
+}
+
+/// Compresses the world directory into `backup_path` synchronously.
+/// `on_progress` receives the byte count of each written chunk, see `create_zip_from_dir`.
+pub fn create_world_snapshot<F>(
+  world_dir: &Path,
+  backup_path: &Path,
+  on_progress: F,
+) -> SJMCLResult<()>
+where
+  F: FnMut(usize) -> bool,
+{
+  let world_name = world_dir
+    .file_name()
+    .ok_or(InstanceError::WorldNotExistError)?
+    .to_string_lossy()
+    .to_string();
+  create_zip_from_dir(
+    world_dir,
+    backup_path,
+    &world_name,
+    BACKUP_EXCLUDED_FILES,
+    on_progress,
+  )
+  .map_err(|_| InstanceError::BackupCreationFailed.into())
+}
+
+pub fn list_world_backups(
+  backups_dir: &Path,
+  world_name: Option<&str>,
+) -> SJMCLResult<Vec<WorldBackupInfo>> {
+  if !backups_dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut backups = Vec::new();
+  for entry in fs::read_dir(backups_dir)? {
+    let entry = entry?;
+    let path = entry.path();
+    if !path.is_file() {
+      continue;
+    }
+    let file_name = entry.file_name().to_string_lossy().to_string();
+    let Some(captures) = BACKUP_NAME_REGEX.captures(&file_name) else {
+      continue;
+    };
+    let backup_world_name = captures[2].to_string();
+    if world_name.is_some_and(|name| name != backup_world_name) {
+      continue;
+    }
+
+    let metadata = entry.metadata()?;
+    let created_at = NaiveDateTime::parse_from_str(&captures[1], BACKUP_TIME_FORMAT)
+      .ok()
+      .and_then(|time| Local.from_local_datetime(&time).single())
+      .map(|time| time.timestamp())
+      .unwrap_or_else(|| {
+        metadata
+          .modified()
+          .ok()
+          .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+          .map(|d| d.as_secs() as i64)
+          .unwrap_or_default()
+      });
+
+    backups.push(WorldBackupInfo {
+      world_name: backup_world_name,
+      file_name,
+      file_path: path,
+      size: metadata.len(),
+      created_at,
+    });
+  }
+
+  // newest first
+  backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+  Ok(backups)
+}
+
+/// Removes the snapshots of a world which are not kept by any retention policy.
+pub fn apply_backup_retention(
+  backups_dir: &Path,
+  world_name: &str,
+  retention: &WorldBackupRetention,
+) -> SJMCLResult<()> {
+  if retention.keep_last == 0 && retention.keep_daily == 0 && retention.keep_weekly == 0 {
+    return Ok(());
+  }
+
+  let backups = list_world_backups(backups_dir, Some(world_name))?; // newest first
+  let mut kept = HashSet::new();
+
+  for backup in backups.iter().take(retention.keep_last) {
+    kept.insert(backup.file_path.clone());
+  }
+
+  // keep the newest snapshot of each of the latest N days / weeks that have snapshots.
+  let mut days = Vec::new();
+  let mut weeks = Vec::new();
+  for backup in backups.iter() {
+    let Some(time) = Local.timestamp_opt(backup.created_at, 0).single() else {
+      continue;
+    };
+    let day = time.date_naive();
+    if days.len() < retention.keep_daily && !days.contains(&day) {
+      days.push(day);
+      kept.insert(backup.file_path.clone());
+    }
+    let week = (time.iso_week().year(), time.iso_week().week());
+    if weeks.len() < retention.keep_weekly && !weeks.contains(&week) {
+      weeks.push(week);
+      kept.insert(backup.file_path.clone());
+    }
+  }
+
+  for backup in backups {
+    if !kept.contains(&backup.file_path) {
+      fs::remove_file(&backup.file_path)?;
+    }
+  }
+  Ok(())
+}
+
+/// Extracts a snapshot into `saves_dir/target_name`, the top-level folder of the zip
+/// is replaced by `target_name`. If the target world already exists, it is replaced
+/// only after the extraction succeeds.
+pub fn restore_world_snapshot(
+  backup_path: &Path,
+  saves_dir: &Path,
+  target_name: &str,
+) -> SJMCLResult<PathBuf> {
+  let file = fs::File::open(backup_path).map_err(|_| InstanceError::BackupNotExistError)?;
+  let mut archive = ZipArchive::new(file).map_err(|_| InstanceError::ZipFileProcessFailed)?;
+
+  let target_dir = saves_dir.join(target_name);
+  let temp_dir = saves_dir.join(format!(".{}.restoring", target_name));
+  if temp_dir.exists() {
+    fs::remove_dir_all(&temp_dir)?;
+  }
+  fs::create_dir_all(&temp_dir).map_err(|_| InstanceError::FolderCreationFailed)?;
+
+  let extract_result = (|| -> SJMCLResult<()> {
+    for i in 0..archive.len() {
+      let mut entry = archive.by_index(i)?;
+      let Some(entry_path) = entry.enclosed_name() else {
+        continue;
+      };
+      // strip the top-level world folder
+      let relative: PathBuf = entry_path
+        .components()
+        .skip(1)
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect();
+      if relative.as_os_str().is_empty() {
+        continue;
+      }
+      let out_path = temp_dir.join(relative);
+      if entry.is_dir() {
+        fs::create_dir_all(&out_path)?;
+      } else {
+        if let Some(parent) = out_path.parent() {
+          fs::create_dir_all(parent)?;
+        }
+        let mut out_file = fs::File::create(&out_path)?;
+        std::io::copy(&mut entry, &mut out_file)?;
+      }
+    }
+    Ok(())
+  })();
+
+  if extract_result.is_err() {
+    let _ = fs::remove_dir_all(&temp_dir);
+    return Err(InstanceError::BackupRestoreFailed.into());
+  }
+
+  if target_dir.exists() {
+    fs::remove_dir_all(&target_dir).map_err(|_| InstanceError::BackupRestoreFailed)?;
+  }
+  fs::rename(&temp_dir, &target_dir).map_err(|_| InstanceError::BackupRestoreFailed)?;
+  Ok(target_dir)
+}
+
+/// Reads the name of the world folder stored in a snapshot.
+pub fn get_snapshot_world_name(backup_path: &Path) -> SJMCLResult<String> {
+  let file = fs::File::open(backup_path).map_err(|_| InstanceError::BackupNotExistError)?;
+  let mut archive = ZipArchive::new(file).map_err(|_| InstanceError::ZipFileProcessFailed)?;
+  for i in 0..archive.len() {
+    let entry = archive.by_index(i)?;
+    let name = entry
+      .enclosed_name()
+      .and_then(|path| match path.components().next() {
+        Some(Component::Normal(name)) => Some(name.to_string_lossy().to_string()),
+        _ => None,
+      });
+    if let Some(name) = name {
+      return Ok(name);
+    }
+  }
+  Err(InstanceError::ZipFileProcessFailed.into())
+}
+
+/// Snapshots every world of an instance (except the ones in use) before launching,
+/// then applies the retention policies of its game config.
+// snapshot all the idle worlds as a task group, and wait for it to finish
+// so the game does not touch a world while it is being compressed.
+pub async fn backup_instance_worlds(app: &AppHandle, instance: &Instance) -> SJMCLResult<()> {
+  let dirs = get_instance_subdir_paths(
+    app,
+    instance,
+    &[&InstanceSubdirType::Saves, &InstanceSubdirType::Backups],
+  )
+  .ok_or(InstanceError::InstanceNotFoundByID)?;
+  let [saves_dir, backups_dir] = dirs.as_slice() else {
+    return Err(InstanceError::InstanceNotFoundByID.into());
+  };
+
+  let params: Vec<PTaskParam> = get_subdirectories(saves_dir)
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|world_dir| {
+      world_dir.join("level.dat").exists() && !is_world_in_use(app, &instance.id, world_dir)
+    })
+    .map(|world_dir| {
+      let world_name = world_dir.file_name().unwrap().to_string_lossy().to_string();
+      PTaskParam::WorldBackup(WorldBackupParam {
+        instance_id: instance.id.clone(),
+        dest: backups_dir.join(get_backup_file_name(&world_name)),
+        src: world_dir,
+        filename: None,
+      })
+    })
+    .collect();
+  if params.is_empty() {
+    return Ok(());
+  }
+
+  let task_group = schedule_progressive_task_group(
+    app.clone(),
+    format!("world-backup?{}", instance.name),
+    params,
+    true,
+  )
+  .await?
+  .task_group;
+  loop {
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let is_running = app
+      .state::<Pin<Box<TaskMonitor>>>()
+      .state_list()
+      .iter()
+      .any(|group| {
+        group.task_group == task_group
+          && matches!(group.status, GEventStatus::Started | GEventStatus::Stopped)
+      });
+    if !is_running {
+      break;
+    }
+  }
+  Ok(())
+}
@@ -1,20 +1,29 @@
 use crate::{
-  instance::constants::INSTANCE_CFG_FILE_NAME,
+  instance::constants::{
+    GAME_OPTIONS_TEMPLATE_FILE_NAME, INSTANCE_CFG_FILE_NAME, INSTANCE_CFG_SCHEMA_VERSION,
+  },
   launcher_config::models::GameConfig,
-  storage::{load_json_async, save_json_async},
+  storage::{
+    introduce_schema_version, load_json_with_migration_async, save_json_async, MigrationStep,
+    SchemaMigration, Storage,
+  },
   utils::image::ImageWrapper,
+  APP_DATA_DIR,
 };
 use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
 use std::{
   cmp::{Ord, Ordering, PartialOrd},
+  collections::BTreeMap,
   path::PathBuf,
   str::FromStr,
 };
 use strum_macros::Display;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub enum InstanceSubdirType {
   Assets,
+  Backups,
   Libraries,
   Mods,
   NativeLibraries,
@@ -69,6 +78,28 @@ impl ModLoaderType {
   }
 }
 
+// the least stable release type accepted when looking for resource updates
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ReleaseChannel {
+  #[default]
+  Release,
+  Beta,
+  Alpha,
+}
+
+impl ReleaseChannel {
+  // `release_type` is the one of `OtherResourceFileInfo`: release, beta or alpha
+  pub fn accepts(&self, release_type: &str) -> bool {
+    let channel = match release_type.to_lowercase().as_str() {
+      "release" => ReleaseChannel::Release,
+      "beta" => ReleaseChannel::Beta,
+      _ => ReleaseChannel::Alpha,
+    };
+    channel <= *self
+  }
+}
+
 #[derive(Debug, PartialEq, Eq, Deserialize, Clone, Serialize, Default)]
 pub enum ModLoaderStatus {
   NotDownloaded,
@@ -79,9 +110,11 @@ pub enum ModLoaderStatus {
 }
 
 structstruck::strike! {
-  #[strikethrough[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]]
+  #[strikethrough[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, SmartDefault)]]
   #[strikethrough[serde(rename_all = "camelCase", deny_unknown_fields, default)]]
   pub struct Instance {
+    #[default(INSTANCE_CFG_SCHEMA_VERSION)]
+    pub schema_version: u32,
     pub id: String,
     pub name: String,
     pub description: String,
@@ -100,9 +133,17 @@ structstruck::strike! {
     pub use_spec_game_config: bool,
     // if use_spec_game_config is false, this field is ignored
     pub spec_game_config: Option<GameConfig>,
+    pub update_channel: ReleaseChannel,
+    // local resources skipped by the update checker, as `<subdir>/<file name>` (e.g. `mods/foo.jar`)
+    pub pinned_resources: Vec<String>,
   }
 }
 
+impl SchemaMigration for Instance {
+  const SCHEMA_VERSION: u32 = INSTANCE_CFG_SCHEMA_VERSION;
+  const MIGRATIONS: &'static [MigrationStep] = &[introduce_schema_version];
+}
+
 impl Instance {
   pub fn get_json_cfg_path(&self) -> PathBuf {
     self.version_path.join(INSTANCE_CFG_FILE_NAME)
@@ -112,7 +153,7 @@ impl Instance {
   where
     Self: Sized + serde::de::DeserializeOwned + Send,
   {
-    load_json_async::<Self>(&self.get_json_cfg_path()).await
+    load_json_with_migration_async::<Self>(&self.get_json_cfg_path()).await
   }
 
   pub async fn save_json_cfg(&self) -> Result<(), std::io::Error> {
@@ -136,6 +177,8 @@ pub struct InstanceSummary {
   pub support_quick_play: bool,
   pub use_spec_game_config: bool,
   pub is_version_isolated: bool,
+  pub update_channel: ReleaseChannel,
+  pub pinned_resources: Vec<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
@@ -149,6 +192,20 @@ pub struct GameServerInfo {
   pub players_online: usize,
   pub players_max: usize,
   pub online: bool, // if false, it may be offline in the query result or failed in the query.
+  pub accept_textures: Option<bool>, // server resource packs, `None` to prompt on joining
+  pub hidden: bool,
+}
+
+// a server entry in servers.dat, to add or edit
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GameServerEntry {
+  pub name: String,
+  pub ip: String,
+  pub icon: Option<String>, // base64 png, `None` to keep the current one
+  pub accept_textures: Option<bool>,
+  #[serde(default)]
+  pub hidden: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
@@ -196,6 +253,30 @@ pub struct ResourcePackInfo {
   // TODO: is Option necessary?
   pub icon_src: Option<ImageWrapper>,
   pub file_path: PathBuf,
+  pub pack_format: Option<i64>,
+  pub supported_formats: Option<(i64, i64)>,
+  pub overlays: Vec<PackOverlay>,
+  pub enabled: bool,                   // listed in `resourcePacks` of options.txt
+  pub potential_incompatibility: bool, // pack format does not match the game version
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PackOverlay {
+  pub directory: String,
+  pub formats: Option<(i64, i64)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DataPackInfo {
+  pub name: String,
+  pub description: String,
+  pub icon_src: Option<ImageWrapper>,
+  pub file_path: PathBuf,
+  pub pack_format: Option<i64>,
+  pub enabled: bool,
+  pub potential_incompatibility: bool, // pack format does not match the world's data version
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
@@ -203,6 +284,35 @@ pub struct ResourcePackInfo {
 pub struct SchematicInfo {
   pub name: String,
   pub file_path: PathBuf,
+  pub format: SchematicFormat,
+  pub version: Option<i64>, // format version, or DataVersion of structure files
+  pub width: i64,
+  pub height: i64,
+  pub length: i64,
+  pub region_count: i64,
+  pub display_name: Option<String>,
+  pub author: Option<String>,
+  pub description: Option<String>,
+  pub created_at: Option<i64>, // unix timestamp in seconds
+  pub parse_failed: bool,      // the metadata above is left empty
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SchematicFormat {
+  #[default]
+  Litematica,
+  Sponge,
+  #[serde(rename = "mcedit")]
+  MCEdit,
+  Structure,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SchematicMaterial {
+  pub name: String, // block id, e.g. `minecraft:stone`, or `legacy:<id>` for unmapped MCEdit ids
+  pub count: u64,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
@@ -210,6 +320,28 @@ pub struct SchematicInfo {
 pub struct ShaderPackInfo {
   pub file_name: String,
   pub file_path: PathBuf,
+  pub is_dir: bool,
+  pub version: Option<String>,
+  pub dimensions: Vec<String>, // e.g. `world0`, `world-1`, `world1`
+  pub profiles: Vec<String>,   // `profile.<name>` in shaders.properties
+  pub required_iris_features: Vec<String>,
+  pub has_options_file: bool, // `shaderpacks/<pack>.txt`
+  pub issue: Option<ShaderPackIssue>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShaderPackIssue {
+  InvalidPack,          // no `shaders` folder inside
+  NoShaderLoader,       // neither Iris, Oculus nor OptiFine is installed
+  IrisFeaturesRequired, // only OptiFine is installed, but the pack needs Iris features
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+pub enum ShaderLoaderType {
+  Iris,
+  Oculus,
+  OptiFine,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
@@ -218,6 +350,75 @@ pub struct ScreenshotInfo {
   pub file_name: String,
   pub file_path: PathBuf,
   pub time: u64,
+  pub size: u64,
+  pub width: u32,
+  pub height: u32,
+  pub thumbnail_path: PathBuf, // may not be generated yet
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ScreenshotThumbnailPayload {
+  pub file_path: PathBuf,
+  pub thumbnail_path: PathBuf,
+}
+
+// isolated subdirs carried over to the duplicated instance
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct DuplicateInstanceOptions {
+  pub saves: bool,
+  pub mods: bool,
+  pub config: bool,
+  pub screenshots: bool,
+}
+
+// typed view of `options.txt`, `entries` keeps all the keys in the order of the file
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GameOptionsInfo {
+  pub data_version: Option<i64>,
+  pub lang: Option<String>,
+  pub fov: Option<u32>, // in degrees
+  pub gui_scale: Option<u32>,
+  pub render_distance: Option<u32>,
+  pub max_fps: Option<u32>,
+  pub sound_levels: BTreeMap<String, f64>, // `soundCategory_<category>`, in [0, 1]
+  pub key_bindings: BTreeMap<String, String>, // `key_<binding>`, e.g. `key.jump` -> `key.keyboard.space`
+  pub entries: Vec<(String, String)>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct GameOptionsPatch {
+  pub lang: Option<String>,
+  pub fov: Option<u32>,
+  pub gui_scale: Option<u32>,
+  pub render_distance: Option<u32>,
+  pub max_fps: Option<u32>,
+  pub sound_levels: BTreeMap<String, f64>,
+  pub key_bindings: BTreeMap<String, String>,
+  pub others: BTreeMap<String, String>, // raw `options.txt` keys
+}
+
+// a saved set of options, applied to new instances and optionally synced to existing ones
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct GameOptionsTemplate {
+  pub entries: Vec<(String, String)>,
+  pub data_version: Option<i64>, // of the instance the template was saved from
+  pub source_instance_name: Option<String>,
+  pub apply_to_new_instances: bool,
+  pub sync_keys: Vec<String>, // keys or prefixes ending with `*`, e.g. `key_*`
+}
+
+impl Storage for GameOptionsTemplate {
+  fn file_path() -> PathBuf {
+    APP_DATA_DIR
+      .get()
+      .unwrap()
+      .join(GAME_OPTIONS_TEMPLATE_FILE_NAME)
+  }
 }
 
 #[derive(Debug, Display)]
@@ -225,6 +426,9 @@ pub struct ScreenshotInfo {
 pub enum InstanceError {
   InstanceNotFoundByID,
   ServerNbtReadError,
+  ServerNbtSaveError,
+  ServerNotExistError,
+  ServerAlreadyExistError,
   FileNotFoundError,
   InvalidSourcePath,
   FileCreationFailed,
@@ -236,6 +440,7 @@ pub enum InstanceError {
   WorldNotExistError,
   LevelParseError,
   LevelNotExistError,
+  LevelSaveError,
   ConflictNameError,
   InvalidNameError,
   ClientJsonParseError,
@@ -249,6 +454,13 @@ pub enum InstanceError {
   MainClassNotFound,
   InstallationDuplicated,
   ProcessorExecutionFailed,
+  WorldInUseError,
+  BackupNotExistError,
+  BackupCreationFailed,
+  BackupRestoreFailed,
+  ScreenshotParseError,
+  SchematicParseError,
+  ShaderPackParseError,
 }
 
 impl std::error::Error for InstanceError {}
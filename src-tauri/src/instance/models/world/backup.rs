@@ -0,0 +1,12 @@
+use serde::{self, Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WorldBackupInfo {
+  pub world_name: String,
+  pub file_name: String,
+  pub file_path: PathBuf,
+  pub size: u64,
+  pub created_at: i64,
+}
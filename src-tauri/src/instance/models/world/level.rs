@@ -66,6 +66,28 @@ pub struct LevelData {
   pub was_modded: u8,
 }
 
+// Fields of level.dat which can be edited offline, `None` means leaving the field untouched.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct LevelDataPatch {
+  pub level_name: Option<String>,
+  pub difficulty: Option<u8>, // 0: peaceful, 1: easy, 2: normal, 3: hard
+  pub difficulty_locked: Option<bool>,
+  pub hardcore: Option<bool>,
+  pub allow_commands: Option<bool>,
+  pub spawn_x: Option<i32>,
+  pub spawn_y: Option<i32>,
+  pub spawn_z: Option<i32>,
+  pub time: Option<i64>,
+  pub daytime: Option<i64>,
+  pub clear_weather_time: Option<i32>,
+  pub raining: Option<bool>,
+  pub rain_time: Option<i32>,
+  pub thundering: Option<bool>,
+  pub thunder_time: Option<i32>,
+  pub game_rules: Option<HashMap<String, String>>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 #[serde(rename_all = "PascalCase")]
 pub struct Version {
@@ -1,3 +1,4 @@
+pub mod backup;
 pub mod base;
 pub mod level;
 pub mod player;
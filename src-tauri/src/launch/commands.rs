@@ -3,13 +3,16 @@ use super::{
     command_generator::{export_full_launch_command, generate_launch_command},
     file_validator::{extract_native_libraries, get_invalid_library_files},
     jre_selector::select_java_runtime,
+    play_history::{export_play_sessions, filter_play_sessions, get_play_statistics},
     process_monitor::{kill_process, monitor_process, set_process_priority},
   },
-  models::LaunchingState,
+  models::{LaunchingState, PlayHistory, PlayHistoryExportFormat, PlaySession, PlayStatistics},
 };
 use crate::{
   account::{
-    helpers::{authlib_injector, microsoft, misc::get_selected_player_info},
+    helpers::{
+      authlib_injector, local_yggdrasil, maintenance, microsoft, misc::get_selected_player_info,
+    },
     models::PlayerType,
   },
   error::SJMCLResult,
@@ -17,6 +20,7 @@ use crate::{
     helpers::{
       client_json::{replace_native_libraries, McClientInfo},
       misc::{get_instance_game_config, get_instance_subdir_paths},
+      world_backup::backup_instance_worlds,
     },
     models::misc::{Instance, InstanceError, InstanceSubdirType, ModLoaderStatus},
   },
@@ -42,7 +46,10 @@ use std::{
   process::{Command, Stdio},
 };
 use std::{
-  sync::{mpsc, Mutex},
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    mpsc, Mutex,
+  },
   time::{SystemTime, UNIX_EPOCH},
 };
 use tauri::{path::BaseDirectory, AppHandle, Manager, State};
@@ -50,15 +57,19 @@ use tauri::{path::BaseDirectory, AppHandle, Manager, State};
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
+// launching ids are strictly increasing, so two launches never share a game log file
+static LAST_LAUNCHING_ID: AtomicU64 = AtomicU64::new(0);
+
 // Step 1: select suitable java runtime environment.
+// returns the launching id, which is passed to all the following steps.
 #[tauri::command]
 pub async fn select_suitable_jre(
   app: AppHandle,
   instance_id: String,
   instances_state: State<'_, Mutex<HashMap<String, Instance>>>,
   javas_state: State<'_, Mutex<Vec<JavaInfo>>>,
-  launching_queue_state: State<'_, Mutex<Vec<LaunchingState>>>,
-) -> SJMCLResult<()> {
+  launching_queue_state: State<'_, Mutex<HashMap<u64, LaunchingState>>>,
+) -> SJMCLResult<u64> {
   let instance = instances_state
     .lock()?
     .get(&instance_id)
@@ -83,31 +94,42 @@ pub async fn select_suitable_jre(
   )
   .await?;
 
-  let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-  let mut launching = launching_queue_state.lock()?;
-  launching.push(LaunchingState {
-    id: timestamp,
-    game_config,
-    client_info,
-    selected_java,
-    selected_instance: instance,
-    ..LaunchingState::default()
-  });
+  let mut launching_queue = launching_queue_state.lock()?;
+  // the id is also the launch timestamp, bump it past the last one if two launches start
+  // in the same second, even if the earlier one has already left the queue
+  let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+  let id = LAST_LAUNCHING_ID
+    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+      Some(now.max(last + 1))
+    })
+    .map_or(now, |last| now.max(last + 1));
+  launching_queue.insert(
+    id,
+    LaunchingState {
+      id,
+      game_config,
+      client_info,
+      selected_java,
+      selected_instance: instance,
+      ..LaunchingState::default()
+    },
+  );
 
-  Ok(())
+  Ok(id)
 }
 
 // Step 2: extract native libraries, validate game and dependency files.
 #[tauri::command]
 pub async fn validate_game_files(
   app: AppHandle,
+  launching_id: u64,
   launcher_config_state: State<'_, Mutex<LauncherConfig>>,
-  launching_queue_state: State<'_, Mutex<Vec<LaunchingState>>>,
+  launching_queue_state: State<'_, Mutex<HashMap<u64, LaunchingState>>>,
 ) -> SJMCLResult<()> {
   let (instance, mut client_info, validate_policy) = {
     let mut launching_queue = launching_queue_state.lock()?;
     let launching = launching_queue
-      .last_mut()
+      .get_mut(&launching_id)
       .ok_or(LaunchError::LaunchingStateNotFound)?;
     launching.current_step = 2;
     (
@@ -133,7 +155,7 @@ pub async fn validate_game_files(
   {
     let mut launching_queue = launching_queue_state.lock()?;
     let launching = launching_queue
-      .last_mut()
+      .get_mut(&launching_id)
       .ok_or(LaunchError::LaunchingStateNotFound)?;
 
     launching.client_info = client_info.clone();
@@ -193,14 +215,15 @@ pub async fn validate_game_files(
 #[tauri::command]
 pub async fn validate_selected_player(
   app: AppHandle,
-  launching_queue_state: State<'_, Mutex<Vec<LaunchingState>>>,
+  launching_id: u64,
+  launching_queue_state: State<'_, Mutex<HashMap<u64, LaunchingState>>>,
 ) -> SJMCLResult<bool> {
   let player = get_selected_player_info(&app)?;
 
   {
     let mut launching_queue = launching_queue_state.lock()?;
     let launching = launching_queue
-      .last_mut()
+      .get_mut(&launching_id)
       .ok_or(LaunchError::LaunchingStateNotFound)?;
     launching.current_step = 3;
     launching.selected_player = Some(player.clone());
@@ -217,29 +240,74 @@ pub async fn validate_selected_player(
     }
   }
 
-  match player.player_type {
+  let is_valid = match player.player_type {
     PlayerType::ThirdParty => {
       authlib_injector::jar::check_authlib_jar(&app).await?;
-      authlib_injector::common::validate(&app, &player).await
+      authlib_injector::common::validate(&app, &player).await?
+    }
+    PlayerType::Microsoft => microsoft::oauth::validate(&app, &player).await?,
+    PlayerType::Offline => {
+      // serve the skins of offline players by the local yggdrasil server if possible,
+      // otherwise launch without it as before.
+      if let Err(e) = prepare_local_yggdrasil(&app, launching_id, &launching_queue_state).await {
+        log::error!("failed to prepare the local yggdrasil server: {}", e.0);
+      }
+      true
+    }
+  };
+  if is_valid {
+    return Ok(true);
+  }
+
+  // try to refresh the expired token silently before asking the user to log in again
+  match maintenance::refresh_and_save_player(&app, &player.id).await {
+    Ok(refreshed_player) => {
+      let mut launching_queue = launching_queue_state.lock()?;
+      let launching = launching_queue
+        .get_mut(&launching_id)
+        .ok_or(LaunchError::LaunchingStateNotFound)?;
+      launching.selected_player = Some(refreshed_player);
+      Ok(true)
     }
-    PlayerType::Microsoft => microsoft::oauth::validate(&app, &player).await,
-    PlayerType::Offline => Ok(true),
+    Err(_) => Ok(false),
   }
 }
 
+async fn prepare_local_yggdrasil(
+  app: &AppHandle,
+  launching_id: u64,
+  launching_queue_state: &State<'_, Mutex<HashMap<u64, LaunchingState>>>,
+) -> SJMCLResult<()> {
+  authlib_injector::jar::check_authlib_jar(app).await?;
+  let server = local_yggdrasil::get_or_start_server(app).await?;
+
+  let mut launching_queue = launching_queue_state.lock()?;
+  let launching = launching_queue
+    .get_mut(&launching_id)
+    .ok_or(LaunchError::LaunchingStateNotFound)?;
+  if let Some(player) = launching.selected_player.as_mut() {
+    player.auth_server_url = Some(server.api_root.clone());
+    player.access_token = Some(server.issue_access_token(player.uuid));
+  }
+  launching.auth_server_meta = server.metadata().to_string();
+  Ok(())
+}
+
 #[tauri::command]
 pub async fn launch_game(
   app: AppHandle,
-  launching_queue_state: State<'_, Mutex<Vec<LaunchingState>>>,
+  launching_id: u64,
+  launching_queue_state: State<'_, Mutex<HashMap<u64, LaunchingState>>>,
   quick_play_singleplayer: Option<String>,
   quick_play_multiplayer: Option<String>,
 ) -> SJMCLResult<()> {
   let (id, selected_java, game_config, instance) = {
     let mut launching_queue = launching_queue_state.lock()?;
     let launching = launching_queue
-      .last_mut()
+      .get_mut(&launching_id)
       .ok_or(LaunchError::LaunchingStateNotFound)?;
     launching.current_step = 4;
+    launching.quick_play_server = quick_play_multiplayer.clone();
     (
       launching.id,
       launching.selected_java.clone(),
@@ -255,11 +323,28 @@ pub async fn launch_game(
     .ok_or(InstanceError::InstanceNotFoundByID)?
     .clone();
 
+  // snapshot worlds before launching if enabled (if error, keep slient and continue launching)
+  if game_config.world_backup.auto_backup_before_launch {
+    if let Err(e) = backup_instance_worlds(&app, &instance).await {
+      log::error!("Failed to backup worlds before launch: {}", e.0);
+    }
+  }
+
   // generate launch command
   let LaunchCommand {
     class_paths,
     args: cmd_args,
-  } = generate_launch_command(&app, quick_play_singleplayer, quick_play_multiplayer).await?;
+  } = generate_launch_command(
+    &app,
+    launching_id,
+    quick_play_singleplayer,
+    quick_play_multiplayer,
+  )
+  .await?;
+  if take_cancelled_launching(&launching_queue_state, launching_id)? {
+    return Ok(());
+  }
+
   let mut cmd_base = Command::new(selected_java.exec_path.clone());
 
   let full_cmd = export_full_launch_command(&class_paths, &cmd_args, &selected_java.exec_path);
@@ -269,7 +354,7 @@ pub async fn launch_game(
   #[cfg(target_os = "windows")]
   cmd_base.creation_flags(0x08000000);
 
-  let child = cmd_base
+  let mut child = cmd_base
     .current_dir(&work_dir)
     .env("CLASSPATH", class_paths.join(get_separator()))
     .args(cmd_args)
@@ -281,8 +366,15 @@ pub async fn launch_game(
   {
     let mut launching_queue = launching_queue_state.lock()?;
     let launching = launching_queue
-      .last_mut()
+      .get_mut(&launching_id)
       .ok_or(LaunchError::LaunchingStateNotFound)?;
+    if launching.cancelled {
+      // cancelled while spawning, the process is not monitored yet
+      launching_queue.remove(&launching_id);
+      let _ = child.kill();
+      let _ = child.wait();
+      return Ok(());
+    }
     launching.pid = pid;
     launching.full_command = full_cmd;
   }
@@ -317,21 +409,42 @@ pub async fn launch_game(
 
 #[tauri::command]
 pub fn cancel_launch_process(
-  launching_queue_state: State<'_, Mutex<Vec<LaunchingState>>>,
+  launching_queue_state: State<'_, Mutex<HashMap<u64, LaunchingState>>>,
+  launching_id: u64,
 ) -> SJMCLResult<()> {
   let mut launching_queue = launching_queue_state.lock()?;
 
-  // kill process if pid exists
-  if let Some(launching) = launching_queue.last_mut() {
+  // kill process if pid exists, the state is removed after the process exits
+  if let Some(launching) = launching_queue.get_mut(&launching_id) {
     if launching.pid != 0 {
       launching.current_step = 0; // mark as manually cancelled to avoid game error window popping up
       kill_process(launching.pid)?;
+    } else if launching.current_step >= 4 {
+      // `launch_game` is running, let it stop before or right after spawning and remove the state
+      launching.cancelled = true;
+    } else {
+      launching_queue.remove(&launching_id);
     }
   }
 
   Ok(())
 }
 
+// remove the launching state if it has been cancelled, returns whether it was cancelled
+fn take_cancelled_launching(
+  launching_queue_state: &State<'_, Mutex<HashMap<u64, LaunchingState>>>,
+  launching_id: u64,
+) -> SJMCLResult<bool> {
+  let mut launching_queue = launching_queue_state.lock()?;
+  let cancelled = launching_queue
+    .get(&launching_id)
+    .map_or(false, |launching| launching.cancelled);
+  if cancelled {
+    launching_queue.remove(&launching_id);
+  }
+  Ok(cancelled)
+}
+
 #[tauri::command]
 pub async fn open_game_log_window(app: AppHandle, launching_id: u64) -> SJMCLResult<()> {
   create_webview_window(&app, &format!("game_log_{launching_id}"), "game_log", None).await?;
@@ -355,21 +468,35 @@ pub fn retrieve_game_log(app: AppHandle, launching_id: u64) -> SJMCLResult<Vec<S
 
 #[tauri::command]
 pub fn retrieve_game_launching_state(
-  launching_queue_state: State<'_, Mutex<Vec<LaunchingState>>>,
+  launching_queue_state: State<'_, Mutex<HashMap<u64, LaunchingState>>>,
   launching_id: u64,
 ) -> SJMCLResult<LaunchingState> {
   let launching_queue = launching_queue_state.lock()?;
-  if let Some(launching) = launching_queue.iter().find(|l| l.id == launching_id) {
+  if let Some(launching) = launching_queue.get(&launching_id) {
     Ok(launching.clone())
   } else {
     Err(LaunchError::LaunchingStateNotFound.into())
   }
 }
 
+#[tauri::command]
+pub fn retrieve_running_games(
+  launching_queue_state: State<'_, Mutex<HashMap<u64, LaunchingState>>>,
+) -> SJMCLResult<Vec<LaunchingState>> {
+  let launching_queue = launching_queue_state.lock()?;
+  let mut running: Vec<LaunchingState> = launching_queue
+    .values()
+    .filter(|l| l.pid != 0)
+    .cloned()
+    .collect();
+  running.sort_by_key(|l| l.id);
+  Ok(running)
+}
+
 #[tauri::command]
 pub fn export_game_crash_info(
   app: AppHandle,
-  launching_queue_state: State<'_, Mutex<Vec<LaunchingState>>>,
+  launching_queue_state: State<'_, Mutex<HashMap<u64, LaunchingState>>>,
   launching_id: u64,
   save_path: String,
 ) -> SJMCLResult<String> {
@@ -381,8 +508,7 @@ pub fn export_game_crash_info(
 
   let launching_queue = launching_queue_state.lock()?;
   let launching = launching_queue
-    .iter()
-    .find(|l| l.id == launching_id)
+    .get(&launching_id)
     .ok_or(LaunchError::LaunchingStateNotFound)?;
   // version json and sjmcl instance config
   let version_info_path = launching
@@ -402,6 +528,18 @@ pub fn export_game_crash_info(
   )?;
   fs::write(&launch_script_path, &launching.full_command)?;
 
+  // memory and cpu usage before the crash
+  let resource_usage_path = app
+    .path()
+    .resolve::<PathBuf>("resource-usage.json".into(), BaseDirectory::Temp)?;
+  fs::write(
+    &resource_usage_path,
+    serde_json::to_string_pretty(&serde_json::json!({
+      "samples": launching.resource_history,
+      "gcEvents": launching.gc_history,
+    }))?,
+  )?;
+
   let zip_file_path = PathBuf::from(save_path);
   create_zip_from_dirs(
     vec![
@@ -409,7 +547,62 @@ pub fn export_game_crash_info(
       version_info_path,
       version_config_path,
       launch_script_path,
+      resource_usage_path,
     ],
     zip_file_path.clone(),
   )
 }
+
+#[tauri::command]
+pub fn retrieve_play_sessions(
+  play_history_state: State<'_, Mutex<PlayHistory>>,
+  instance_id: Option<String>,
+  start_time: Option<i64>,
+  end_time: Option<i64>,
+) -> SJMCLResult<Vec<PlaySession>> {
+  let history = play_history_state.lock()?;
+  let mut sessions = filter_play_sessions(
+    &history.sessions,
+    instance_id.as_deref(),
+    start_time,
+    end_time,
+  );
+  sessions.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+  Ok(sessions)
+}
+
+#[tauri::command]
+pub fn retrieve_play_statistics(
+  play_history_state: State<'_, Mutex<PlayHistory>>,
+  instance_id: Option<String>,
+  start_time: Option<i64>,
+  end_time: Option<i64>,
+) -> SJMCLResult<PlayStatistics> {
+  let history = play_history_state.lock()?;
+  let sessions = filter_play_sessions(
+    &history.sessions,
+    instance_id.as_deref(),
+    start_time,
+    end_time,
+  );
+  Ok(get_play_statistics(&sessions))
+}
+
+#[tauri::command]
+pub fn export_play_history(
+  play_history_state: State<'_, Mutex<PlayHistory>>,
+  instance_id: Option<String>,
+  start_time: Option<i64>,
+  end_time: Option<i64>,
+  format: PlayHistoryExportFormat,
+  save_path: String,
+) -> SJMCLResult<()> {
+  let history = play_history_state.lock()?;
+  let sessions = filter_play_sessions(
+    &history.sessions,
+    instance_id.as_deref(),
+    start_time,
+    end_time,
+  );
+  export_play_sessions(&sessions, &PathBuf::from(save_path), format)
+}
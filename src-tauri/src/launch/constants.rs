@@ -1,3 +1,11 @@
 pub const GAME_PROCESS_OUTPUT_EVENT: &str = "launch:game-process-output";
+pub const GAME_PROCESS_RESOURCE_EVENT: &str = "launch:game-process-resource";
+pub const GAME_PROCESS_GC_EVENT: &str = "launch:game-process-gc";
+
+// about 5 minutes with the 2s polling interval
+pub const RESOURCE_HISTORY_MAX_LEN: usize = 150;
+pub const GC_HISTORY_MAX_LEN: usize = 100;
 
 pub const READY_FLAG: &[&str] = &["render thread", "lwjgl version", "lwjgl openal"];
+
+pub const PLAY_HISTORY_FILE_NAME: &str = "sjmcl.play-history.json";
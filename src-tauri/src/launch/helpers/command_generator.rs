@@ -102,17 +102,21 @@ pub struct LaunchCommand {
 
 pub async fn generate_launch_command(
   app: &AppHandle,
+  launching_id: u64,
   quick_play_singleplayer: Option<String>,
   quick_play_multiplayer: Option<String>,
 ) -> SJMCLResult<LaunchCommand> {
   let launcher_config = { app.state::<Mutex<LauncherConfig>>().lock()?.clone() };
-  let launching_queue = { app.state::<Mutex<Vec<LaunchingState>>>().lock()?.clone() };
+  let launching = {
+    let launching_queue_state = app.state::<Mutex<HashMap<u64, LaunchingState>>>();
+    let launching_queue = launching_queue_state.lock()?;
+    launching_queue
+      .get(&launching_id)
+      .ok_or(LaunchError::LaunchingStateNotFound)?
+      .clone()
+  };
 
   let LauncherConfig { basic_info, .. } = launcher_config;
-  let launching = launching_queue
-    .last()
-    .ok_or(LaunchError::LaunchingStateNotFound)?
-    .clone();
   let LaunchingState {
     selected_java,
     selected_instance,
@@ -274,8 +278,10 @@ pub async fn generate_launch_command(
 
   // TODO: lwjgl non-ASCII path fix (HMCL DefaultLauncher.java#L236)
 
-  // authlib-injector login
-  if selected_player.player_type == PlayerType::ThirdParty {
+  // authlib-injector login, offline players use the local yggdrasil server if it is prepared
+  if selected_player.player_type == PlayerType::ThirdParty
+    || (selected_player.player_type == PlayerType::Offline && !auth_server_meta.is_empty())
+  {
     cmd.push(format!(
       "-javaagent:{}={}",
       get_authlib_injector_jar_path(app)?.to_string_lossy(),
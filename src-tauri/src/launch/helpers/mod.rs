@@ -2,4 +2,6 @@ pub mod command_generator;
 pub mod file_validator;
 pub mod jre_selector;
 pub mod misc;
+pub mod play_history;
 pub mod process_monitor;
+pub mod resource_monitor;
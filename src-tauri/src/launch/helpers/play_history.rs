@@ -0,0 +1,193 @@
+use crate::error::SJMCLResult;
+use crate::launch::models::{
+  InstancePlayStats, LaunchingState, PlayHistory, PlayHistoryExportFormat, PlaySession,
+  PlayStatistics, PlayTimePeriodStats,
+};
+use crate::storage::Storage;
+use chrono::{Local, TimeZone};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+const PLAY_HISTORY_CSV_HEADER: &str = "id,instanceId,instanceName,playerId,playerName,startTime,endTime,playTime,exitCode,crashed,server,javaName,javaPath";
+
+/// Appends the session of an exited game process to the play history.
+/// `ready_time` is when the game window showed up, `None` if the game exited before that.
+pub fn record_play_session(
+  app: &AppHandle,
+  launching: &LaunchingState,
+  ready_time: Option<Instant>,
+  exit_code: Option<i32>,
+  crashed: bool,
+) -> SJMCLResult<()> {
+  let end_time = Local::now().timestamp();
+  let play_time = ready_time.map(|t| t.elapsed().as_secs()).unwrap_or(0);
+  let (player_id, player_name) = launching
+    .selected_player
+    .as_ref()
+    .map(|p| (p.id.clone(), p.name.clone()))
+    .unwrap_or_default();
+  let session = PlaySession {
+    id: launching.id,
+    instance_id: launching.selected_instance.id.clone(),
+    instance_name: launching.selected_instance.name.clone(),
+    player_id,
+    player_name,
+    // the launching id is the unix timestamp of the launch
+    start_time: launching.id as i64,
+    end_time,
+    play_time,
+    exit_code,
+    crashed,
+    server: launching.quick_play_server.clone(),
+    java_name: launching.selected_java.name.clone(),
+    java_path: launching.selected_java.exec_path.clone(),
+  };
+
+  let binding = app.state::<Mutex<PlayHistory>>();
+  let mut history = binding.lock()?;
+  history.sessions.push(session);
+  history.save()?;
+  Ok(())
+}
+
+/// Returns the sessions of the given instance (or all if `None`) which started in `[start, end)`.
+pub fn filter_play_sessions(
+  sessions: &[PlaySession],
+  instance_id: Option<&str>,
+  start: Option<i64>,
+  end: Option<i64>,
+) -> Vec<PlaySession> {
+  sessions
+    .iter()
+    .filter(|s| instance_id.map_or(true, |id| id == s.instance_id))
+    .filter(|s| start.map_or(true, |t| s.start_time >= t))
+    .filter(|s| end.map_or(true, |t| s.start_time < t))
+    .cloned()
+    .collect()
+}
+
+fn group_play_time_by<F>(sessions: &[PlaySession], get_period: F) -> Vec<PlayTimePeriodStats>
+where
+  F: Fn(i64) -> Option<String>,
+{
+  let mut periods: BTreeMap<String, PlayTimePeriodStats> = BTreeMap::new();
+  for session in sessions {
+    let Some(period) = get_period(session.start_time) else {
+      continue;
+    };
+    let stats = periods
+      .entry(period.clone())
+      .or_insert_with(|| PlayTimePeriodStats {
+        period,
+        ..Default::default()
+      });
+    stats.play_time += session.play_time;
+    stats.session_count += 1;
+  }
+  periods.into_values().collect()
+}
+
+/// Aggregates the sessions by local day, ISO week and instance.
+/// A session is counted in the period it started in.
+pub fn get_play_statistics(sessions: &[PlaySession]) -> PlayStatistics {
+  let get_local_time = |timestamp: i64| Local.timestamp_opt(timestamp, 0).single();
+  let daily = group_play_time_by(sessions, |t| {
+    get_local_time(t).map(|d| d.format("%Y-%m-%d").to_string())
+  });
+  let weekly = group_play_time_by(sessions, |t| {
+    get_local_time(t).map(|d| d.format("%G-W%V").to_string())
+  });
+
+  let mut instances: HashMap<String, InstancePlayStats> = HashMap::new();
+  for session in sessions {
+    let stats = instances
+      .entry(session.instance_id.clone())
+      .or_insert_with(|| InstancePlayStats {
+        instance_id: session.instance_id.clone(),
+        ..Default::default()
+      });
+    if session.start_time >= stats.last_played_at {
+      // keep the latest name, the instance may have been renamed
+      stats.instance_name = session.instance_name.clone();
+      stats.last_played_at = session.start_time;
+    }
+    stats.play_time += session.play_time;
+    stats.session_count += 1;
+    if session.crashed {
+      stats.crash_count += 1;
+    }
+  }
+  let mut instances: Vec<InstancePlayStats> = instances
+    .into_values()
+    .map(|mut stats| {
+      stats.crash_rate = stats.crash_count as f64 / stats.session_count as f64;
+      stats
+    })
+    .collect();
+  instances.sort_by(|a, b| {
+    b.play_time
+      .cmp(&a.play_time)
+      .then(b.session_count.cmp(&a.session_count))
+  });
+
+  PlayStatistics {
+    total_play_time: sessions.iter().map(|s| s.play_time).sum(),
+    session_count: sessions.len(),
+    daily,
+    weekly,
+    instances,
+  }
+}
+
+fn escape_csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+fn get_play_sessions_csv(sessions: &[PlaySession]) -> String {
+  let mut lines = vec![PLAY_HISTORY_CSV_HEADER.to_string()];
+  for s in sessions {
+    let fields = [
+      s.id.to_string(),
+      s.instance_id.clone(),
+      s.instance_name.clone(),
+      s.player_id.clone(),
+      s.player_name.clone(),
+      s.start_time.to_string(),
+      s.end_time.to_string(),
+      s.play_time.to_string(),
+      s.exit_code.map(|c| c.to_string()).unwrap_or_default(),
+      s.crashed.to_string(),
+      s.server.clone().unwrap_or_default(),
+      s.java_name.clone(),
+      s.java_path.clone(),
+    ];
+    lines.push(
+      fields
+        .iter()
+        .map(|f| escape_csv_field(f))
+        .collect::<Vec<_>>()
+        .join(","),
+    );
+  }
+  lines.join("\n") + "\n"
+}
+
+pub fn export_play_sessions(
+  sessions: &[PlaySession],
+  save_path: &Path,
+  format: PlayHistoryExportFormat,
+) -> SJMCLResult<()> {
+  let content = match format {
+    PlayHistoryExportFormat::Csv => get_play_sessions_csv(sessions),
+    PlayHistoryExportFormat::Json => serde_json::to_string_pretty(sessions)?,
+  };
+  std::fs::write(save_path, content)?;
+  Ok(())
+}
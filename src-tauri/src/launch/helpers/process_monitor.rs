@@ -1,6 +1,8 @@
 use crate::error::SJMCLResult;
 use crate::instance::models::misc::Instance;
 use crate::launch::constants::*;
+use crate::launch::helpers::play_history::record_play_session;
+use crate::launch::helpers::resource_monitor::{parse_gc_log_line, ProcessSampler};
 use crate::launch::models::{LaunchError, LaunchingState};
 use crate::launcher_config::models::{LauncherVisiablity, ProcessPriority};
 use crate::utils::window::create_webview_window;
@@ -25,6 +27,7 @@ const POLLING_OPERATION_INTERVAL_MS: u64 = 2000;
 
 struct OutputPipe<T: Read + Send + 'static> {
   app: AppHandle,
+  id: u64,
   out: T,
   label: String,
   start_time: Arc<Mutex<Option<Instant>>>,
@@ -45,6 +48,15 @@ impl<T: Read + Send + 'static> OutputPipe<T> {
             .emit_to(&self.label, GAME_PROCESS_OUTPUT_EVENT, &line);
         }
         writeln!(self.log_file.lock().unwrap(), "{line}").unwrap();
+        // gc log lines, if `-Xlog:gc` or `-verbose:gc` is in the jvm args
+        if line.contains("GC") {
+          if let Some(event) = parse_gc_log_line(&line) {
+            let _ = self.app.emit_to(&self.label, GAME_PROCESS_GC_EVENT, &event);
+            update_launching_state(&self.app, self.id, |launching| {
+              push_with_max_len(&mut launching.gc_history, event, GC_HISTORY_MAX_LEN);
+            });
+          }
+        }
         // the first time when log contains 'render thread', 'lwjgl version', or 'lwjgl openal', send signal to launch command, close frontend modal.
         if !self.game_ready_flag.load(Ordering::SeqCst)
           && READY_FLAG.iter().any(|p| line.to_lowercase().contains(p))
@@ -62,6 +74,24 @@ impl<T: Read + Send + 'static> OutputPipe<T> {
   }
 }
 
+fn update_launching_state<F>(app: &AppHandle, id: u64, f: F)
+where
+  F: FnOnce(&mut LaunchingState),
+{
+  let launching_queue_state = app.state::<Mutex<HashMap<u64, LaunchingState>>>();
+  let mut launching_queue = launching_queue_state.lock().unwrap();
+  if let Some(launching) = launching_queue.get_mut(&id) {
+    f(launching);
+  }
+}
+
+fn push_with_max_len<T>(history: &mut Vec<T>, item: T, max_len: usize) {
+  history.push(item);
+  if history.len() > max_len {
+    history.drain(..history.len() - max_len);
+  }
+}
+
 pub async fn record_play_time(app: AppHandle, start_time: Instant, instance_id: String) {
   let instance_in_mem = {
     let binding = app.state::<Mutex<HashMap<String, Instance>>>();
@@ -125,6 +155,7 @@ pub async fn monitor_process(
   let stdout = child.stdout.take().map(|out| {
     (OutputPipe {
       app: app.clone(),
+      id,
       label: label.clone(),
       out,
       start_time: start_time.clone(),
@@ -140,6 +171,7 @@ pub async fn monitor_process(
   let stderr = child.stderr.take().map(|out| {
     (OutputPipe {
       app: app.clone(),
+      id,
       label: label.clone(),
       out,
       start_time: start_time.clone(),
@@ -151,18 +183,31 @@ pub async fn monitor_process(
     .listen_from_output()
   });
 
-  // polling thread (for changing window title, etc.)
+  // polling thread (for changing window title, sampling resource usage, etc.)
   let stop_polling_flag = Arc::new(AtomicBool::new(false));
   let _ = {
     let stop_polling_flag = stop_polling_flag.clone();
     let pid = child.id();
     let custom_title = custom_title.to_string();
+    let app = app.clone();
+    let label = label.clone();
     thread::spawn(move || {
+      let mut sampler = ProcessSampler::new(pid);
       while !stop_polling_flag.load(Ordering::SeqCst) {
         thread::sleep(std::time::Duration::from_millis(
           POLLING_OPERATION_INTERVAL_MS,
         ));
         let _ = change_process_window_title(pid, &custom_title).is_err();
+        if let Some(sample) = sampler.sample() {
+          let _ = app.emit_to(&label, GAME_PROCESS_RESOURCE_EVENT, &sample);
+          update_launching_state(&app, id, |launching| {
+            push_with_max_len(
+              &mut launching.resource_history,
+              sample,
+              RESOURCE_HISTORY_MAX_LEN,
+            );
+          });
+        }
       }
     });
   };
@@ -173,8 +218,10 @@ pub async fn monitor_process(
   let stop_polling_flag = stop_polling_flag.clone();
 
   tokio::spawn(async move {
+    let mut exit_code = None;
     let exit_ok = match child.wait() {
       Ok(status) => {
+        exit_code = status.code();
         if let Some(h) = stdout {
           let _ = h.join();
         }
@@ -203,6 +250,25 @@ pub async fn monitor_process(
 
     stop_polling_flag.store(true, Ordering::SeqCst);
     drop(log_file);
+    // record before the launcher may exit with the game below
+    let start_time_lock = *start_time.lock().unwrap();
+    if let Some(start_time) = start_time_lock {
+      record_play_time(app.clone(), start_time, instance_id_clone).await;
+    }
+
+    let launching_option = {
+      let launching_queue_state = app.state::<Mutex<HashMap<u64, LaunchingState>>>();
+      let launching_queue = launching_queue_state.lock().unwrap();
+      launching_queue.get(&id).cloned()
+    };
+    if let Some(launching) = &launching_option {
+      // a manually cancelled launch is not counted as a crash
+      let crashed = !exit_ok && launching.current_step != 0;
+      if let Err(e) = record_play_session(&app, launching, start_time_lock, exit_code, crashed) {
+        log::error!("Failed to record play session: {}", e.0);
+      }
+    }
+
     // handle launcher main window visiablity
     match launcher_visibility {
       LauncherVisiablity::RunningHidden => {
@@ -224,37 +290,24 @@ pub async fn monitor_process(
       _ => {}
     }
 
-    let start_time_lock = *start_time.lock().unwrap();
-    if let Some(start_time) = start_time_lock {
-      record_play_time(app.clone(), start_time, instance_id_clone).await;
-    }
-
     if exit_ok {
       if let Some(ref window) = log_window {
         let _ = window.destroy();
       }
 
-      let launching_queue_state = app.state::<Mutex<Vec<LaunchingState>>>();
+      let launching_queue_state = app.state::<Mutex<HashMap<u64, LaunchingState>>>();
       let mut launching_queue = launching_queue_state.lock().unwrap();
-      launching_queue.retain(|state| state.id != id);
-    } else {
-      let launching_option = {
-        let launching_queue_state = app.state::<Mutex<Vec<LaunchingState>>>();
-        let launching_queue = launching_queue_state.lock().unwrap();
-        launching_queue.iter().find(|s| s.id == id).cloned()
-      };
-
-      if let Some(launching) = launching_option {
-        if launching.current_step == 0 {
-          // it was marked as manually cancelled, then remove from launching_queue and not show game error window
-          let launching_queue_state = app.state::<Mutex<Vec<LaunchingState>>>();
-          let mut launching_queue = launching_queue_state.lock().unwrap();
-          launching_queue.retain(|state| state.id != id);
-        } else {
-          let _ = create_webview_window(&app, &format!("game_error_{id}"), "game_error", None)
-            .await
-            .unwrap();
-        }
+      launching_queue.remove(&id);
+    } else if let Some(launching) = launching_option {
+      if launching.current_step == 0 {
+        // it was marked as manually cancelled, then remove from launching_queue and not show game error window
+        let launching_queue_state = app.state::<Mutex<HashMap<u64, LaunchingState>>>();
+        let mut launching_queue = launching_queue_state.lock().unwrap();
+        launching_queue.remove(&id);
+      } else {
+        let _ = create_webview_window(&app, &format!("game_error_{id}"), "game_error", None)
+          .await
+          .unwrap();
       }
     }
   });
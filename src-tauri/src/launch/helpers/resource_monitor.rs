@@ -0,0 +1,474 @@
+use crate::launch::models::{GcEvent, ProcessResourceSample};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::time::Instant;
+
+/// Samples the resource usage of a game process and all of its descendants,
+/// e.g. the processes started by a wrapper command.
+pub struct ProcessSampler {
+  pid: u32,
+  last_cpu_time: Option<(u64, Instant)>, // total cpu time of the process tree at that instant
+}
+
+impl ProcessSampler {
+  pub fn new(pid: u32) -> Self {
+    ProcessSampler {
+      pid,
+      last_cpu_time: None,
+    }
+  }
+
+  /// Returns `None` if the process has exited or the platform is not supported.
+  /// The cpu usage of the first sample is always 0, as it needs two samples to compute.
+  pub fn sample(&mut self) -> Option<ProcessResourceSample> {
+    let now = Instant::now();
+    let (mut sample, cpu_time) = platform::sample_process_tree(self.pid)?;
+    if let Some((last_cpu_time, last_time)) = self.last_cpu_time {
+      let elapsed = now.duration_since(last_time).as_secs_f64();
+      if elapsed > 0.0 {
+        let used = cpu_time.saturating_sub(last_cpu_time) as f64 / platform::CPU_TIME_UNITS_PER_SEC;
+        sample.cpu_usage = used / elapsed * 100.0;
+      }
+    }
+    self.last_cpu_time = Some((cpu_time, now));
+    Some(sample)
+  }
+}
+
+#[cfg(target_os = "linux")]
+use linux as platform;
+#[cfg(target_os = "macos")]
+use macos as platform;
+#[cfg(target_os = "windows")]
+use windows as platform;
+
+#[cfg(target_os = "linux")]
+mod linux {
+  use crate::launch::models::ProcessResourceSample;
+  use std::collections::HashMap;
+  use std::fs;
+
+  // USER_HZ, the unit of the cpu times in /proc, is 100 on every architecture Linux exposes
+  pub const CPU_TIME_UNITS_PER_SEC: f64 = 100.0;
+
+  struct ProcStat {
+    ppid: u32,
+    cpu_ticks: u64, // utime + stime
+    thread_count: u32,
+  }
+
+  fn read_proc_stat(pid: u32) -> Option<ProcStat> {
+    let content = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // the command name may contain spaces and parentheses, skip to its last `)`
+    let fields: Vec<&str> = content
+      .get(content.rfind(')')? + 2..)?
+      .split_whitespace()
+      .collect();
+    // fields here start from the 3rd one (state) of proc_pid_stat(5)
+    Some(ProcStat {
+      ppid: fields.get(1)?.parse().ok()?,
+      cpu_ticks: fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?,
+      thread_count: fields.get(17)?.parse().ok()?,
+    })
+  }
+
+  fn read_rss_bytes(pid: u32) -> Option<u64> {
+    let content = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = content.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+  }
+
+  fn count_open_files(pid: u32) -> u32 {
+    fs::read_dir(format!("/proc/{}/fd", pid))
+      .map(|entries| entries.count() as u32)
+      .unwrap_or(0)
+  }
+
+  fn get_descendant_pids(root_pid: u32) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    if let Ok(entries) = fs::read_dir("/proc") {
+      for entry in entries.flatten() {
+        let Some(pid) = entry
+          .file_name()
+          .to_str()
+          .and_then(|s| s.parse::<u32>().ok())
+        else {
+          continue;
+        };
+        if let Some(stat) = read_proc_stat(pid) {
+          children.entry(stat.ppid).or_default().push(pid);
+        }
+      }
+    }
+
+    let mut pids = vec![root_pid];
+    let mut i = 0;
+    while i < pids.len() {
+      if let Some(child_pids) = children.get(&pids[i]) {
+        pids.extend(child_pids);
+      }
+      i += 1;
+    }
+    pids
+  }
+
+  /// Returns the summed up sample (without cpu usage) and the total cpu ticks.
+  pub fn sample_process_tree(root_pid: u32) -> Option<(ProcessResourceSample, u64)> {
+    // the root process is gone, there is nothing to sample
+    read_proc_stat(root_pid)?;
+
+    let mut sample = ProcessResourceSample {
+      timestamp: chrono::Utc::now().timestamp_millis(),
+      ..Default::default()
+    };
+    let mut cpu_ticks = 0;
+    for pid in get_descendant_pids(root_pid) {
+      // a child may exit while sampling
+      let Some(stat) = read_proc_stat(pid) else {
+        continue;
+      };
+      cpu_ticks += stat.cpu_ticks;
+      sample.thread_count += stat.thread_count;
+      sample.memory += read_rss_bytes(pid).unwrap_or(0);
+      sample.open_file_count += count_open_files(pid);
+      sample.process_count += 1;
+    }
+    Some((sample, cpu_ticks))
+  }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+  use crate::launch::models::ProcessResourceSample;
+  use std::collections::{HashMap, HashSet};
+  use std::mem;
+  use winapi::shared::minwindef::{DWORD, FALSE, FILETIME};
+  use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+  use winapi::um::processthreadsapi::{GetProcessHandleCount, GetProcessTimes, OpenProcess};
+  use winapi::um::psapi::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+  use winapi::um::tlhelp32::{
+    CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+  };
+  use winapi::um::winnt::PROCESS_QUERY_LIMITED_INFORMATION;
+
+  // FILETIME counts in 100-nanosecond intervals
+  pub const CPU_TIME_UNITS_PER_SEC: f64 = 10_000_000.0;
+
+  struct ProcEntry {
+    pid: u32,
+    ppid: u32,
+    thread_count: u32,
+  }
+
+  struct ProcUsage {
+    cpu_time: u64, // kernel + user
+    memory: u64,   // working set
+    handle_count: u32,
+  }
+
+  fn list_processes() -> Vec<ProcEntry> {
+    let mut processes = Vec::new();
+    unsafe {
+      let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+      if snapshot == INVALID_HANDLE_VALUE {
+        return processes;
+      }
+      let mut entry: PROCESSENTRY32W = mem::zeroed();
+      entry.dwSize = mem::size_of::<PROCESSENTRY32W>() as DWORD;
+      let mut has_entry = Process32FirstW(snapshot, &mut entry) != FALSE;
+      while has_entry {
+        processes.push(ProcEntry {
+          pid: entry.th32ProcessID,
+          ppid: entry.th32ParentProcessID,
+          thread_count: entry.cntThreads,
+        });
+        has_entry = Process32NextW(snapshot, &mut entry) != FALSE;
+      }
+      CloseHandle(snapshot);
+    }
+    processes
+  }
+
+  fn filetime_to_u64(time: &FILETIME) -> u64 {
+    ((time.dwHighDateTime as u64) << 32) | time.dwLowDateTime as u64
+  }
+
+  fn read_process_usage(pid: u32) -> Option<ProcUsage> {
+    unsafe {
+      let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, pid);
+      if handle.is_null() {
+        return None;
+      }
+      let mut creation_time: FILETIME = mem::zeroed();
+      let mut exit_time: FILETIME = mem::zeroed();
+      let mut kernel_time: FILETIME = mem::zeroed();
+      let mut user_time: FILETIME = mem::zeroed();
+      let usage = if GetProcessTimes(
+        handle,
+        &mut creation_time,
+        &mut exit_time,
+        &mut kernel_time,
+        &mut user_time,
+      ) != FALSE
+      {
+        let mut counters: PROCESS_MEMORY_COUNTERS = mem::zeroed();
+        let mut handle_count: DWORD = 0;
+        GetProcessMemoryInfo(
+          handle,
+          &mut counters,
+          mem::size_of::<PROCESS_MEMORY_COUNTERS>() as DWORD,
+        );
+        GetProcessHandleCount(handle, &mut handle_count);
+        Some(ProcUsage {
+          cpu_time: filetime_to_u64(&kernel_time) + filetime_to_u64(&user_time),
+          memory: counters.WorkingSetSize as u64,
+          handle_count,
+        })
+      } else {
+        None
+      };
+      CloseHandle(handle);
+      usage
+    }
+  }
+
+  /// Returns the summed up sample (without cpu usage) and the total cpu time.
+  pub fn sample_process_tree(root_pid: u32) -> Option<(ProcessResourceSample, u64)> {
+    let processes = list_processes();
+    // the root process is gone, there is nothing to sample
+    let root = processes.iter().find(|p| p.pid == root_pid)?;
+
+    let mut children: HashMap<u32, Vec<&ProcEntry>> = HashMap::new();
+    for process in &processes {
+      children.entry(process.ppid).or_default().push(process);
+    }
+
+    let mut sample = ProcessResourceSample {
+      timestamp: chrono::Utc::now().timestamp_millis(),
+      ..Default::default()
+    };
+    let mut cpu_time = 0;
+    // parent pids may be stale and reused on Windows, guard against cycles
+    let mut visited = HashSet::from([root_pid]);
+    let mut queue = vec![root];
+    while let Some(process) = queue.pop() {
+      if let Some(child_processes) = children.get(&process.pid) {
+        queue.extend(
+          child_processes
+            .iter()
+            .filter(|child| visited.insert(child.pid)),
+        );
+      }
+      // a child may exit while sampling
+      let Some(usage) = read_process_usage(process.pid) else {
+        continue;
+      };
+      cpu_time += usage.cpu_time;
+      sample.thread_count += process.thread_count;
+      sample.memory += usage.memory;
+      sample.open_file_count += usage.handle_count; // all kinds of handles, not only files
+      sample.process_count += 1;
+    }
+    Some((sample, cpu_time))
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+  use crate::launch::models::ProcessResourceSample;
+  use libc::{c_int, c_void, proc_taskallinfo, PROC_PIDTASKALLINFO};
+  use std::collections::HashMap;
+  use std::mem;
+  use std::sync::OnceLock;
+
+  // the cpu times are converted from mach absolute time units to nanoseconds
+  pub const CPU_TIME_UNITS_PER_SEC: f64 = 1_000_000_000.0;
+
+  // from <sys/proc_info.h>, not exposed by libc
+  const PROC_PIDLISTFDS: c_int = 1;
+  const PROC_FDINFO_SIZE: c_int = 8; // struct proc_fdinfo { int32_t proc_fd; uint32_t proc_fdtype; }
+
+  #[allow(deprecated)]
+  fn mach_time_to_nanos(time: u64) -> u64 {
+    static TIMEBASE: OnceLock<(u64, u64)> = OnceLock::new();
+    let (numer, denom) = *TIMEBASE.get_or_init(|| {
+      let mut info = libc::mach_timebase_info { numer: 0, denom: 0 };
+      if unsafe { libc::mach_timebase_info(&mut info) } == 0 && info.denom != 0 {
+        (info.numer as u64, info.denom as u64)
+      } else {
+        (1, 1)
+      }
+    });
+    (time as u128 * numer as u128 / denom as u128) as u64
+  }
+
+  fn list_all_pids() -> Vec<u32> {
+    unsafe {
+      let count = libc::proc_listallpids(std::ptr::null_mut(), 0);
+      if count <= 0 {
+        return Vec::new();
+      }
+      // leave some room for the processes started in between
+      let mut pids: Vec<c_int> = vec![0; count as usize + 64];
+      let count = libc::proc_listallpids(
+        pids.as_mut_ptr() as *mut c_void,
+        (pids.len() * mem::size_of::<c_int>()) as c_int,
+      );
+      pids.truncate(count.max(0) as usize);
+      pids
+        .into_iter()
+        .filter(|pid| *pid > 0)
+        .map(|pid| pid as u32)
+        .collect()
+    }
+  }
+
+  fn read_task_all_info(pid: u32) -> Option<proc_taskallinfo> {
+    unsafe {
+      let mut info: proc_taskallinfo = mem::zeroed();
+      let size = mem::size_of::<proc_taskallinfo>() as c_int;
+      let ret = libc::proc_pidinfo(
+        pid as c_int,
+        PROC_PIDTASKALLINFO,
+        0,
+        &mut info as *mut proc_taskallinfo as *mut c_void,
+        size,
+      );
+      (ret == size).then_some(info)
+    }
+  }
+
+  fn count_open_files(pid: u32) -> u32 {
+    unsafe {
+      let size = libc::proc_pidinfo(pid as c_int, PROC_PIDLISTFDS, 0, std::ptr::null_mut(), 0);
+      if size <= 0 {
+        return 0;
+      }
+      // the size above is only an upper bound, list them to get the actual count
+      let mut buf: Vec<u8> = vec![0; size as usize];
+      let size = libc::proc_pidinfo(
+        pid as c_int,
+        PROC_PIDLISTFDS,
+        0,
+        buf.as_mut_ptr() as *mut c_void,
+        size,
+      );
+      (size.max(0) / PROC_FDINFO_SIZE) as u32
+    }
+  }
+
+  /// Returns the summed up sample (without cpu usage) and the total cpu time.
+  pub fn sample_process_tree(root_pid: u32) -> Option<(ProcessResourceSample, u64)> {
+    // the root process is gone, there is nothing to sample
+    read_task_all_info(root_pid)?;
+
+    let mut infos: HashMap<u32, proc_taskallinfo> = HashMap::new();
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for pid in list_all_pids() {
+      // a process may exit while listing
+      if let Some(info) = read_task_all_info(pid) {
+        children.entry(info.pbsd.pbi_ppid).or_default().push(pid);
+        infos.insert(pid, info);
+      }
+    }
+
+    let mut pids = vec![root_pid];
+    let mut i = 0;
+    while i < pids.len() {
+      if let Some(child_pids) = children.get(&pids[i]) {
+        pids.extend(child_pids);
+      }
+      i += 1;
+    }
+
+    let mut sample = ProcessResourceSample {
+      timestamp: chrono::Utc::now().timestamp_millis(),
+      ..Default::default()
+    };
+    let mut cpu_time = 0;
+    for pid in pids {
+      let Some(info) = infos.get(&pid) else {
+        continue;
+      };
+      cpu_time += mach_time_to_nanos(info.ptinfo.pti_total_user + info.ptinfo.pti_total_system);
+      sample.thread_count += info.ptinfo.pti_threadnum.max(0) as u32;
+      sample.memory += info.ptinfo.pti_resident_size;
+      sample.open_file_count += count_open_files(pid);
+      sample.process_count += 1;
+    }
+    Some((sample, cpu_time))
+  }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod platform {
+  use crate::launch::models::ProcessResourceSample;
+
+  pub const CPU_TIME_UNITS_PER_SEC: f64 = 1.0;
+
+  pub fn sample_process_tree(_root_pid: u32) -> Option<(ProcessResourceSample, u64)> {
+    None
+  }
+}
+
+fn parse_size_with_unit(value: &str, unit: &str) -> Option<u64> {
+  let value: u64 = value.parse().ok()?;
+  let multiplier = match unit {
+    "B" => 1,
+    "K" => 1024,
+    "M" => 1024 * 1024,
+    "G" => 1024 * 1024 * 1024,
+    _ => return None,
+  };
+  Some(value * multiplier)
+}
+
+/// Parses a heap collection line of the gc log, both in the unified logging format
+/// (`-Xlog:gc`, Java 9+) and the legacy one (`-verbose:gc`, Java 8).
+///
+/// # Examples
+///
+/// ```text
+/// [2.345s][info][gc] GC(3) Pause Young (Normal) (G1 Evacuation Pause) 24M->4M(256M) 3.456ms
+/// [GC (Allocation Failure)  65536K->1234K(251392K), 0.0123456 secs]
+/// ```
+pub fn parse_gc_log_line(line: &str) -> Option<GcEvent> {
+  lazy_static! {
+    static ref UNIFIED_GC_REGEX: Regex = Regex::new(
+      r"(?:\[(?P<uptime>[\d.]+)s\].*?)?GC\((?P<id>\d+)\)\s+(?P<kind>.+?)\s+(?P<before>\d+)(?P<before_unit>[BKMG])->(?P<after>\d+)(?P<after_unit>[BKMG])\((?P<total>\d+)(?P<total_unit>[BKMG])\)\s+(?P<pause>[\d.]+)ms"
+    )
+    .unwrap();
+    static ref LEGACY_GC_REGEX: Regex = Regex::new(
+      r"\[(?P<kind>(?:Full )?GC(?: \([^)]*\))?)\s+(?:\[[^\]]*\]\s*)*(?P<before>\d+)K->(?P<after>\d+)K\((?P<total>\d+)K\),\s*(?P<pause>[\d.]+) secs\]"
+    )
+    .unwrap();
+  }
+
+  let timestamp = chrono::Utc::now().timestamp_millis();
+  if let Some(cap) = UNIFIED_GC_REGEX.captures(line) {
+    return Some(GcEvent {
+      timestamp,
+      uptime: cap.name("uptime").and_then(|m| m.as_str().parse().ok()),
+      gc_id: cap["id"].parse().ok(),
+      kind: cap["kind"].to_string(),
+      heap_before: parse_size_with_unit(&cap["before"], &cap["before_unit"])?,
+      heap_after: parse_size_with_unit(&cap["after"], &cap["after_unit"])?,
+      heap_capacity: parse_size_with_unit(&cap["total"], &cap["total_unit"])?,
+      pause_ms: cap["pause"].parse().ok()?,
+    });
+  }
+  if let Some(cap) = LEGACY_GC_REGEX.captures(line) {
+    return Some(GcEvent {
+      timestamp,
+      uptime: None,
+      gc_id: None,
+      kind: cap["kind"].to_string(),
+      heap_before: parse_size_with_unit(&cap["before"], "K")?,
+      heap_after: parse_size_with_unit(&cap["after"], "K")?,
+      heap_capacity: parse_size_with_unit(&cap["total"], "K")?,
+      pause_ms: cap["pause"].parse::<f64>().ok()? * 1000.0,
+    });
+  }
+  None
+}
@@ -1,9 +1,13 @@
 use crate::account::models::PlayerInfo;
 use crate::instance::helpers::client_json::McClientInfo;
 use crate::instance::models::misc::Instance;
+use crate::launch::constants::PLAY_HISTORY_FILE_NAME;
 use crate::launcher_config::models::{GameConfig, JavaInfo};
+use crate::storage::Storage;
+use crate::APP_DATA_DIR;
 use serde::{Deserialize, Serialize};
 use smart_default::SmartDefault;
+use std::path::PathBuf;
 use strum_macros::Display;
 
 #[derive(Debug, Display)]
@@ -37,4 +41,103 @@ pub struct LaunchingState {
   pub full_command: String, // for export and debug
   #[default = 0] // default means not set yet
   pub pid: u32,
+  pub cancelled: bool, // cancelled while launching, before the pid is set
+  pub quick_play_server: Option<String>, // multiplayer server joined by quick play
+  // recent samples of the running game, kept for the crash report
+  pub resource_history: Vec<ProcessResourceSample>,
+  pub gc_history: Vec<GcEvent>,
+}
+
+// resource usage of the game process and its children
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProcessResourceSample {
+  pub timestamp: i64, // unix timestamp in milliseconds
+  pub cpu_usage: f64, // percentage of one core, may exceed 100
+  pub memory: u64,    // resident set size in bytes
+  pub thread_count: u32,
+  pub open_file_count: u32,
+  pub process_count: u32,
+}
+
+// a heap collection parsed from the gc log
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GcEvent {
+  pub timestamp: i64,      // unix timestamp in milliseconds
+  pub uptime: Option<f64>, // seconds since the jvm started, not in the legacy format
+  pub gc_id: Option<u64>,
+  pub kind: String, // e.g. `Pause Young (Normal) (G1 Evacuation Pause)`
+  pub heap_before: u64,
+  pub heap_after: u64,
+  pub heap_capacity: u64,
+  pub pause_ms: f64,
+}
+
+// one game process, from launch to exit
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlaySession {
+  pub id: u64, // the launching id
+  pub instance_id: String,
+  pub instance_name: String,
+  pub player_id: String,
+  pub player_name: String,
+  pub start_time: i64, // unix timestamp in seconds
+  pub end_time: i64,
+  pub play_time: u64, // seconds since the game window showed up
+  pub exit_code: Option<i32>,
+  pub crashed: bool,
+  pub server: Option<String>,
+  pub java_name: String,
+  pub java_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlayHistory {
+  pub sessions: Vec<PlaySession>,
+}
+
+impl Storage for PlayHistory {
+  fn file_path() -> PathBuf {
+    APP_DATA_DIR.get().unwrap().join(PLAY_HISTORY_FILE_NAME)
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayTimePeriodStats {
+  pub period: String, // `2025-03-01` for a day, `2025-W09` for an ISO week
+  pub play_time: u64,
+  pub session_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstancePlayStats {
+  pub instance_id: String,
+  pub instance_name: String,
+  pub play_time: u64,
+  pub session_count: usize,
+  pub crash_count: usize,
+  pub crash_rate: f64,
+  pub last_played_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayStatistics {
+  pub total_play_time: u64,
+  pub session_count: usize,
+  pub daily: Vec<PlayTimePeriodStats>,
+  pub weekly: Vec<PlayTimePeriodStats>,
+  pub instances: Vec<InstancePlayStats>, // most played first
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PlayHistoryExportFormat {
+  Csv,
+  Json,
 }
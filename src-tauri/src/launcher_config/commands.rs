@@ -1,16 +1,29 @@
 use super::{
-  helpers::java::{
-    get_java_info_from_command, get_java_info_from_release_file, refresh_and_update_javas,
+  helpers::{
+    config_transfer::{
+      apply_export_payload, build_export_payload, diff_snapshots, extract_export_backgrounds,
+      get_payload_sections, read_export_file, write_export_file, LauncherConfigSnapshot,
+    },
+    java::{get_java_info_from_command, get_java_info_from_release_file, refresh_and_update_javas},
+  },
+  models::{
+    GameDirectory, ImportedInstanceSettings, JavaInfo, LauncherConfig, LauncherConfigError,
+    LauncherConfigImportMode, LauncherConfigImportPreview, LauncherConfigSection,
   },
-  models::{GameDirectory, JavaInfo, LauncherConfig, LauncherConfigError},
 };
 use crate::{
+  account::models::AccountInfo,
   error::SJMCLResult,
-  instance::helpers::misc::refresh_instances,
+  instance::{
+    helpers::{external_launcher::misc::load_external_instance_settings, misc::refresh_instances},
+    models::misc::Instance,
+  },
+  launcher_config::helpers::misc::get_global_game_config,
   tasks::monitor::TaskMonitor,
   utils::{fs::generate_unique_filename, string::camel_to_snake_case},
 };
 use crate::{storage::Storage, utils::fs::get_subdirectories};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::{fs, pin::Pin};
@@ -142,6 +155,122 @@ pub async fn import_launcher_config(
   }
 }
 
+fn get_launcher_config_snapshot(app: &AppHandle) -> SJMCLResult<LauncherConfigSnapshot> {
+  let config = app.state::<Mutex<LauncherConfig>>().lock()?.clone();
+  let mut instances: Vec<Instance> = app
+    .state::<Mutex<HashMap<String, Instance>>>()
+    .lock()?
+    .values()
+    .cloned()
+    .collect();
+  instances.sort_by(|a, b| a.id.cmp(&b.id));
+  let auth_servers = app
+    .state::<Mutex<AccountInfo>>()
+    .lock()?
+    .auth_servers
+    .clone();
+  Ok(LauncherConfigSnapshot {
+    config,
+    instances,
+    auth_servers,
+  })
+}
+
+#[tauri::command]
+pub fn export_launcher_config_to_file(
+  app: AppHandle,
+  sections: Vec<LauncherConfigSection>,
+  save_path: PathBuf,
+  as_zip: bool,
+) -> SJMCLResult<()> {
+  let snapshot = get_launcher_config_snapshot(&app)?;
+  let payload = build_export_payload(&snapshot, &sections)?;
+  let backgrounds_dir = app
+    .path()
+    .resolve::<PathBuf>("UserContent/Backgrounds".into(), BaseDirectory::AppData)?;
+  write_export_file(&payload, &save_path, as_zip, &backgrounds_dir)
+}
+
+#[tauri::command]
+pub fn retrieve_launcher_config_import_preview(
+  app: AppHandle,
+  file_path: PathBuf,
+  sections: Option<Vec<LauncherConfigSection>>, // all sections in the file if None
+  mode: LauncherConfigImportMode,
+) -> SJMCLResult<LauncherConfigImportPreview> {
+  let (schema_version, payload) = read_export_file(&file_path)?;
+  let file_sections = get_payload_sections(&payload);
+  let sections = sections.unwrap_or_else(|| file_sections.clone());
+
+  let current = get_launcher_config_snapshot(&app)?;
+  let mut incoming = current.clone();
+  apply_export_payload(&mut incoming, &payload, &sections, mode)?;
+
+  Ok(LauncherConfigImportPreview {
+    schema_version,
+    launcher_version: payload.launcher_version,
+    exported_at: payload.exported_at,
+    sections: file_sections,
+    changes: diff_snapshots(&current, &incoming, &sections)?,
+  })
+}
+
+#[tauri::command]
+pub async fn import_launcher_config_from_file(
+  app: AppHandle,
+  file_path: PathBuf,
+  sections: Vec<LauncherConfigSection>,
+  mode: LauncherConfigImportMode,
+) -> SJMCLResult<LauncherConfig> {
+  let (_, payload) = read_export_file(&file_path)?;
+  let current = get_launcher_config_snapshot(&app)?;
+  let mut incoming = current.clone();
+  apply_export_payload(&mut incoming, &payload, &sections, mode)?;
+
+  if sections.contains(&LauncherConfigSection::Appearance) {
+    let backgrounds_dir = app
+      .path()
+      .resolve::<PathBuf>("UserContent/Backgrounds".into(), BaseDirectory::AppData)?;
+    extract_export_backgrounds(&file_path, &backgrounds_dir)?;
+  }
+
+  if sections.contains(&LauncherConfigSection::AuthServers) {
+    let binding = app.state::<Mutex<AccountInfo>>();
+    let mut account_state = binding.lock()?;
+    account_state.auth_servers = incoming.auth_servers;
+    account_state.save()?;
+  }
+
+  for instance in incoming.instances {
+    if current.instances.contains(&instance) {
+      continue;
+    }
+    instance.save_json_cfg().await?;
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let mut state = binding.lock()?;
+    state.insert(instance.id.clone(), instance);
+  }
+
+  let binding = app.state::<Mutex<LauncherConfig>>();
+  let mut state = binding.lock()?;
+  // only the imported sections, other fields may have been updated in the meantime
+  for section in &sections {
+    match section {
+      LauncherConfigSection::Appearance => state.appearance = incoming.config.appearance.clone(),
+      LauncherConfigSection::Download => state.download = incoming.config.download.clone(),
+      LauncherConfigSection::GlobalGameConfig => {
+        state.global_game_config = incoming.config.global_game_config.clone()
+      }
+      LauncherConfigSection::GameDirectories => {
+        state.local_game_directories = incoming.config.local_game_directories.clone()
+      }
+      LauncherConfigSection::InstanceConfigs | LauncherConfigSection::AuthServers => {}
+    }
+  }
+  state.save()?;
+  Ok(state.clone())
+}
+
 #[tauri::command]
 pub fn retrieve_custom_background_list(app: AppHandle) -> SJMCLResult<Vec<String>> {
   let custom_bg_dir = app
@@ -230,8 +359,108 @@ pub async fn validate_java(java_path: String) -> SJMCLResult<()> {
   }
 }
 
+/// Candidates of the game directory picked by the user: itself, then its `.minecraft` subdirs.
+fn get_candidate_game_directories(directory: &Path) -> Vec<PathBuf> {
+  let sub_dirs = get_subdirectories(directory)
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|d| {
+      matches!(
+        d.file_name().and_then(|n| n.to_str()),
+        Some(".minecraft") | Some("minecraft")
+      )
+    });
+  std::iter::once(directory.to_path_buf())
+    .chain(sub_dirs)
+    .collect()
+}
+
+/// Refreshes the instances of `directory`, or of its `.minecraft` subdir if it has none.
+/// Returns the game directory holding the instances, together with the instances.
+async fn find_instances_in_game_directory(
+  app: &AppHandle,
+  directory: &Path,
+) -> Option<(PathBuf, Vec<Instance>)> {
+  for game_dir in get_candidate_game_directories(directory) {
+    let instances = refresh_instances(
+      app,
+      &GameDirectory {
+        dir: game_dir.clone(),
+        name: "".to_string(),
+      },
+      false,
+    )
+    .await
+    .unwrap_or_default();
+    if !instances.is_empty() {
+      return Some((game_dir, instances));
+    }
+  }
+  None
+}
+
+/// Same lookup as `find_instances_in_game_directory`, but only looks for `versions/<name>/<name>.json`
+/// without touching any file, so it is safe for previews.
+fn find_game_directory_readonly(directory: &Path) -> Option<PathBuf> {
+  get_candidate_game_directories(directory)
+    .into_iter()
+    .find(|game_dir| {
+      get_subdirectories(game_dir.join("versions"))
+        .unwrap_or_default()
+        .iter()
+        .any(|version_path| {
+          version_path.file_name().is_some_and(|name| {
+            version_path
+              .join(format!("{}.json", name.to_string_lossy()))
+              .is_file()
+          })
+        })
+    })
+}
+
+/// Applies the settings left by other launchers onto the instances of a game directory.
+/// The directory is not added yet, so only the instance configs are written, and they are
+/// picked up once the directory is added.
+async fn import_instance_settings(
+  app: &AppHandle,
+  game_dir: &Path,
+  instances: &[Instance],
+  instance_names: &[String],
+) -> SJMCLResult<()> {
+  let global_config = get_global_game_config(app);
+
+  for name in instance_names {
+    let version_path = game_dir.join("versions").join(name);
+    let Some(settings) = load_external_instance_settings(&version_path, &global_config) else {
+      continue;
+    };
+    let Ok(version_path) = version_path.canonicalize() else {
+      continue;
+    };
+    let Some(mut instance) = instances
+      .iter()
+      .find(|instance| {
+        instance
+          .version_path
+          .canonicalize()
+          .is_ok_and(|path| path == version_path)
+      })
+      .cloned()
+    else {
+      continue;
+    };
+    settings.apply_to(&mut instance);
+    instance.save_json_cfg().await?;
+  }
+  Ok(())
+}
+
 #[tauri::command]
-pub async fn check_game_directory(app: AppHandle, dir: String) -> SJMCLResult<String> {
+pub async fn check_game_directory(
+  app: AppHandle,
+  dir: String,
+  import_instance_names: Option<Vec<String>>,
+) -> SJMCLResult<String> {
   let local_game_directories: Vec<_>;
   {
     let binding = app.state::<Mutex<LauncherConfig>>();
@@ -247,45 +476,37 @@ pub async fn check_game_directory(app: AppHandle, dir: String) -> SJMCLResult<St
     return Err(LauncherConfigError::GameDirNotExist.into());
   }
 
-  if !refresh_instances(
-    &app,
-    &GameDirectory {
-      dir: directory.clone(),
-      name: "".to_string(),
-    },
-    false,
-  )
-  .await
-  .unwrap_or_default()
-  .is_empty()
-  {
+  let Some((game_dir, instances)) = find_instances_in_game_directory(&app, &directory).await else {
     return Ok("".to_string());
+  };
+  // the settings of other launchers to be imported are chosen from the preview
+  if let Some(instance_names) = import_instance_names {
+    import_instance_settings(&app, &game_dir, &instances, &instance_names).await?;
   }
 
-  let sub_dirs = get_subdirectories(&directory).unwrap_or_default();
-  for sub_dir in sub_dirs.into_iter().filter(|d| {
-    matches!(
-      d.file_name().and_then(|n| n.to_str()),
-      Some(".minecraft") | Some("minecraft")
-    )
-  }) {
-    if !refresh_instances(
-      &app,
-      &GameDirectory {
-        dir: sub_dir.clone(),
-        name: "".to_string(),
-      },
-      false,
-    )
-    .await
-    .unwrap_or_default()
-    .is_empty()
-    {
-      return Ok(sub_dir.to_str().unwrap().to_string());
-    }
+  if game_dir == directory {
+    Ok("".to_string())
+  } else {
+    Ok(game_dir.to_str().unwrap().to_string())
   }
+}
 
-  Ok("".to_string())
+#[tauri::command]
+pub async fn retrieve_instance_settings_import_preview(
+  app: AppHandle,
+  dir: String,
+) -> SJMCLResult<Vec<ImportedInstanceSettings>> {
+  let directory = PathBuf::from(&dir);
+  let game_dir = find_game_directory_readonly(&directory).unwrap_or(directory);
+  let global_config = get_global_game_config(&app);
+
+  let mut previews: Vec<ImportedInstanceSettings> = get_subdirectories(game_dir.join("versions"))
+    .unwrap_or_default()
+    .iter()
+    .filter_map(|version_path| load_external_instance_settings(version_path, &global_config))
+    .collect();
+  previews.sort_by(|a, b| a.instance_name.cmp(&b.instance_name));
+  Ok(previews)
 }
 
 #[tauri::command]
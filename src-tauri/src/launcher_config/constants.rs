@@ -1,3 +1,8 @@
 pub const LAUNCHER_CFG_FILE_NAME: &str = "sjmcl.conf.json";
+pub const LAUNCHER_CFG_SCHEMA_VERSION: u32 = 1;
 
 pub const CONFIG_PARTIAL_UPDATE_EVENT: &str = "config:partial-update";
+
+pub const LAUNCHER_CONFIG_EXPORT_SCHEMA_VERSION: u32 = 1;
+pub const LAUNCHER_CONFIG_EXPORT_ENTRY_NAME: &str = "sjmcl-config.json"; // inside the zip export
+pub const LAUNCHER_CONFIG_EXPORT_BACKGROUNDS_DIR: &str = "backgrounds";
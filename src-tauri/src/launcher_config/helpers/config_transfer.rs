@@ -0,0 +1,496 @@
+use crate::{
+  account::models::AuthServerInfo,
+  error::SJMCLResult,
+  instance::models::misc::Instance,
+  launcher_config::{
+    constants::{
+      LAUNCHER_CONFIG_EXPORT_BACKGROUNDS_DIR, LAUNCHER_CONFIG_EXPORT_ENTRY_NAME,
+      LAUNCHER_CONFIG_EXPORT_SCHEMA_VERSION,
+    },
+    models::{
+      AuthServerExport, GameConfig, GameDirectory, InstanceConfigExport, LauncherConfig,
+      LauncherConfigChange, LauncherConfigError, LauncherConfigExportFile,
+      LauncherConfigExportPayload, LauncherConfigImportMode, LauncherConfigSection,
+    },
+  },
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+// created by `setup_with_app` on every device, their paths are not portable
+const BUILT_IN_GAME_DIR_NAMES: [&str; 3] = ["CURRENT_DIR", "APP_DATA_SUBDIR", "OFFICIAL_DIR"];
+
+/// The states touched by an import, cloned out of the app states so a preview can be computed
+/// by applying the import to a copy.
+#[derive(Debug, Clone)]
+pub struct LauncherConfigSnapshot {
+  pub config: LauncherConfig,
+  pub instances: Vec<Instance>,
+  pub auth_servers: Vec<AuthServerInfo>,
+}
+
+fn is_built_in_game_dir(dir: &GameDirectory) -> bool {
+  BUILT_IN_GAME_DIR_NAMES.contains(&dir.name.as_str())
+}
+
+/// The checksum only guards against corrupted or hand-edited files, it is not a proof of origin.
+fn compute_payload_checksum(payload: &Value) -> String {
+  // `Value` keeps the keys of objects sorted, so the serialized payload is canonical
+  hex::encode(Sha256::digest(payload.to_string().as_bytes()))
+}
+
+fn to_json<T: Serialize>(value: &T) -> SJMCLResult<Value> {
+  Ok(serde_json::to_value(value)?)
+}
+
+fn from_json<T: DeserializeOwned>(value: Value) -> SJMCLResult<T> {
+  serde_json::from_value(value).map_err(|_| LauncherConfigError::ConfigFileParseError.into())
+}
+
+/// Overlays `incoming` onto `base` recursively. Keys unknown to `base` (e.g. from a newer launcher)
+/// are dropped, so the result still deserializes into the current structs.
+fn overlay_json(base: &mut Value, incoming: &Value) {
+  match (base, incoming) {
+    (Value::Object(base_map), Value::Object(incoming_map)) => {
+      for (key, value) in incoming_map {
+        if let Some(base_value) = base_map.get_mut(key) {
+          overlay_json(base_value, value);
+        }
+      }
+    }
+    (base, incoming) => *base = incoming.clone(),
+  }
+}
+
+fn merge_object_section<T: Serialize + DeserializeOwned + Default>(
+  current: &T,
+  incoming: &Value,
+  mode: LauncherConfigImportMode,
+) -> SJMCLResult<T> {
+  let mut base = match mode {
+    LauncherConfigImportMode::Merge => to_json(current)?,
+    LauncherConfigImportMode::Replace => to_json(&T::default())?,
+  };
+  overlay_json(&mut base, incoming);
+  from_json(base)
+}
+
+pub fn build_export_payload(
+  snapshot: &LauncherConfigSnapshot,
+  sections: &[LauncherConfigSection],
+) -> SJMCLResult<LauncherConfigExportPayload> {
+  let config = &snapshot.config;
+  let mut payload = LauncherConfigExportPayload {
+    launcher_version: config.basic_info.launcher_version.clone(),
+    exported_at: chrono::Utc::now().timestamp(),
+    ..Default::default()
+  };
+  for section in sections {
+    match section {
+      LauncherConfigSection::Appearance => payload.appearance = Some(to_json(&config.appearance)?),
+      LauncherConfigSection::Download => payload.download = Some(to_json(&config.download)?),
+      LauncherConfigSection::GlobalGameConfig => {
+        payload.global_game_config = Some(to_json(&config.global_game_config)?)
+      }
+      LauncherConfigSection::GameDirectories => {
+        payload.game_directories = Some(
+          config
+            .local_game_directories
+            .iter()
+            .filter(|dir| !is_built_in_game_dir(dir))
+            .cloned()
+            .collect(),
+        )
+      }
+      LauncherConfigSection::InstanceConfigs => {
+        let mut instance_configs = Vec::new();
+        for instance in &snapshot.instances {
+          instance_configs.push(InstanceConfigExport {
+            instance_name: instance.name.clone(),
+            use_spec_game_config: instance.use_spec_game_config,
+            spec_game_config: match &instance.spec_game_config {
+              Some(game_config) => Some(to_json(game_config)?),
+              None => None,
+            },
+          });
+        }
+        payload.instance_configs = Some(instance_configs);
+      }
+      LauncherConfigSection::AuthServers => {
+        payload.auth_servers = Some(
+          snapshot
+            .auth_servers
+            .iter()
+            .map(|server| AuthServerExport {
+              auth_url: server.auth_url.clone(),
+              metadata: server.metadata.clone(),
+            })
+            .collect(),
+        )
+      }
+    }
+  }
+  Ok(payload)
+}
+
+/// Writes the export as a json file, or as a zip file which also contains the custom backgrounds
+/// if the appearance section is exported.
+pub fn write_export_file(
+  payload: &LauncherConfigExportPayload,
+  save_path: &Path,
+  as_zip: bool,
+  backgrounds_dir: &Path,
+) -> SJMCLResult<()> {
+  let payload = to_json(payload)?;
+  let file = LauncherConfigExportFile {
+    schema_version: LAUNCHER_CONFIG_EXPORT_SCHEMA_VERSION,
+    checksum: compute_payload_checksum(&payload),
+    payload,
+  };
+  let content = serde_json::to_string_pretty(&file)?;
+  if !as_zip {
+    fs::write(save_path, content)?;
+    return Ok(());
+  }
+
+  let mut zip = ZipWriter::new(fs::File::create(save_path)?);
+  let options = SimpleFileOptions::default();
+  zip.start_file(LAUNCHER_CONFIG_EXPORT_ENTRY_NAME, options)?;
+  zip.write_all(content.as_bytes())?;
+  if file
+    .payload
+    .get("appearance")
+    .map_or(false, |v| !v.is_null())
+    && backgrounds_dir.is_dir()
+  {
+    for entry in fs::read_dir(backgrounds_dir)?.flatten() {
+      if !entry.path().is_file() {
+        continue;
+      }
+      let name = format!(
+        "{}/{}",
+        LAUNCHER_CONFIG_EXPORT_BACKGROUNDS_DIR,
+        entry.file_name().to_string_lossy()
+      );
+      zip.start_file(name, options)?;
+      zip.write_all(&fs::read(entry.path())?)?;
+    }
+  }
+  zip.finish()?;
+  Ok(())
+}
+
+fn is_zip_file(path: &Path) -> SJMCLResult<bool> {
+  let mut magic = [0u8; 4];
+  let mut file = fs::File::open(path)?;
+  Ok(file.read_exact(&mut magic).is_ok() && magic == *b"PK\x03\x04")
+}
+
+/// Reads and verifies an export file (json or zip). Returns the schema version and the payload.
+pub fn read_export_file(path: &Path) -> SJMCLResult<(u32, LauncherConfigExportPayload)> {
+  let content = if is_zip_file(path)? {
+    let mut zip = ZipArchive::new(fs::File::open(path)?)?;
+    let mut entry = zip
+      .by_name(LAUNCHER_CONFIG_EXPORT_ENTRY_NAME)
+      .map_err(|_| LauncherConfigError::ConfigFileParseError)?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content)?;
+    content
+  } else {
+    fs::read_to_string(path)?
+  };
+
+  let file: LauncherConfigExportFile =
+    serde_json::from_str(&content).map_err(|_| LauncherConfigError::ConfigFileParseError)?;
+  if file.schema_version > LAUNCHER_CONFIG_EXPORT_SCHEMA_VERSION {
+    return Err(LauncherConfigError::UnsupportedSchemaVersion.into());
+  }
+  if compute_payload_checksum(&file.payload) != file.checksum {
+    return Err(LauncherConfigError::ConfigChecksumMismatch.into());
+  }
+  Ok((file.schema_version, from_json(file.payload)?))
+}
+
+/// Copies the custom backgrounds in a zip export into `backgrounds_dir`, skipping existing ones.
+pub fn extract_export_backgrounds(path: &Path, backgrounds_dir: &Path) -> SJMCLResult<()> {
+  if !is_zip_file(path)? {
+    return Ok(());
+  }
+  let mut zip = ZipArchive::new(fs::File::open(path)?)?;
+  for i in 0..zip.len() {
+    let mut entry = zip.by_index(i)?;
+    let Some(file_name) = entry
+      .enclosed_name()
+      .filter(|p| p.starts_with(LAUNCHER_CONFIG_EXPORT_BACKGROUNDS_DIR))
+      .and_then(|p| p.file_name().map(|n| n.to_os_string()))
+    else {
+      continue;
+    };
+    let dest_path = backgrounds_dir.join(file_name);
+    if entry.is_dir() || dest_path.exists() {
+      continue;
+    }
+    fs::create_dir_all(backgrounds_dir)?;
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes)?;
+    fs::write(dest_path, bytes)?;
+  }
+  Ok(())
+}
+
+/// Returns the sections contained in the payload.
+pub fn get_payload_sections(payload: &LauncherConfigExportPayload) -> Vec<LauncherConfigSection> {
+  [
+    (
+      payload.appearance.is_some(),
+      LauncherConfigSection::Appearance,
+    ),
+    (payload.download.is_some(), LauncherConfigSection::Download),
+    (
+      payload.global_game_config.is_some(),
+      LauncherConfigSection::GlobalGameConfig,
+    ),
+    (
+      payload.game_directories.is_some(),
+      LauncherConfigSection::GameDirectories,
+    ),
+    (
+      payload.instance_configs.is_some(),
+      LauncherConfigSection::InstanceConfigs,
+    ),
+    (
+      payload.auth_servers.is_some(),
+      LauncherConfigSection::AuthServers,
+    ),
+  ]
+  .into_iter()
+  .filter_map(|(contained, section)| contained.then_some(section))
+  .collect()
+}
+
+/// Applies the selected sections of the payload to the snapshot.
+/// Sections which are not contained in the payload are left untouched.
+pub fn apply_export_payload(
+  snapshot: &mut LauncherConfigSnapshot,
+  payload: &LauncherConfigExportPayload,
+  sections: &[LauncherConfigSection],
+  mode: LauncherConfigImportMode,
+) -> SJMCLResult<()> {
+  let config = &mut snapshot.config;
+  for section in sections {
+    match section {
+      LauncherConfigSection::Appearance => {
+        if let Some(incoming) = &payload.appearance {
+          config.appearance = merge_object_section(&config.appearance, incoming, mode)?;
+        }
+      }
+      LauncherConfigSection::Download => {
+        if let Some(incoming) = &payload.download {
+          let cache_dir = config.download.cache.directory.clone();
+          config.download = merge_object_section(&config.download, incoming, mode)?;
+          // the cache directory of another device is meaningless here
+          config.download.cache.directory = cache_dir;
+        }
+      }
+      LauncherConfigSection::GlobalGameConfig => {
+        if let Some(incoming) = &payload.global_game_config {
+          let game_java = config.global_game_config.game_java.clone();
+          config.global_game_config =
+            merge_object_section(&config.global_game_config, incoming, mode)?;
+          // the java of another device is meaningless here
+          config.global_game_config.game_java = game_java;
+        }
+      }
+      LauncherConfigSection::GameDirectories => {
+        let Some(incoming) = &payload.game_directories else {
+          continue;
+        };
+        let incoming = incoming.iter().filter(|dir| !is_built_in_game_dir(dir));
+        let dirs = &mut config.local_game_directories;
+        if mode == LauncherConfigImportMode::Replace {
+          dirs.retain(is_built_in_game_dir);
+        }
+        for dir in incoming {
+          if !dirs.iter().any(|d| d.name == dir.name || d.dir == dir.dir) {
+            dirs.push(dir.clone());
+          }
+        }
+      }
+      LauncherConfigSection::InstanceConfigs => {
+        let Some(incoming) = &payload.instance_configs else {
+          continue;
+        };
+        for instance in snapshot.instances.iter_mut() {
+          let Some(instance_config) = incoming.iter().find(|c| c.instance_name == instance.name)
+          else {
+            if mode == LauncherConfigImportMode::Replace {
+              // not in the file, fall back to the global game config
+              instance.use_spec_game_config = false;
+            }
+            continue;
+          };
+          instance.use_spec_game_config = instance_config.use_spec_game_config;
+          if let Some(spec_game_config) = &instance_config.spec_game_config {
+            let current = match mode {
+              LauncherConfigImportMode::Merge => instance
+                .spec_game_config
+                .clone()
+                .unwrap_or_else(|| config.global_game_config.clone()),
+              LauncherConfigImportMode::Replace => GameConfig::default(),
+            };
+            let game_java = instance
+              .spec_game_config
+              .as_ref()
+              .map_or_else(|| current.game_java.clone(), |c| c.game_java.clone());
+            let mut spec_game_config =
+              merge_object_section(&current, spec_game_config, LauncherConfigImportMode::Merge)?;
+            // keep the java of this device, like the global game config
+            spec_game_config.game_java = game_java;
+            instance.spec_game_config = Some(spec_game_config);
+          } else if mode == LauncherConfigImportMode::Replace {
+            instance.spec_game_config = None;
+          }
+        }
+      }
+      LauncherConfigSection::AuthServers => {
+        let Some(incoming) = &payload.auth_servers else {
+          continue;
+        };
+        let to_auth_server_info = |server: &AuthServerExport| {
+          snapshot
+            .auth_servers
+            .iter()
+            .find(|s| s.auth_url == server.auth_url)
+            .cloned()
+            .unwrap_or_else(|| AuthServerInfo {
+              auth_url: server.auth_url.clone(),
+              client_id: String::new(),
+              metadata: server.metadata.clone(),
+              timestamp: 0, // refreshed on the next start
+            })
+        };
+        let mut auth_servers = match mode {
+          LauncherConfigImportMode::Merge => snapshot.auth_servers.clone(),
+          LauncherConfigImportMode::Replace => Vec::new(),
+        };
+        for server in incoming {
+          if !auth_servers.iter().any(|s| s.auth_url == server.auth_url) {
+            auth_servers.push(to_auth_server_info(server));
+          }
+        }
+        snapshot.auth_servers = auth_servers;
+      }
+    }
+  }
+  Ok(())
+}
+
+/// The comparable value of a section, lists are keyed by the name (or url) of their items.
+fn get_section_value(
+  snapshot: &LauncherConfigSnapshot,
+  section: LauncherConfigSection,
+) -> SJMCLResult<Value> {
+  let config = &snapshot.config;
+  Ok(match section {
+    LauncherConfigSection::Appearance => to_json(&config.appearance)?,
+    LauncherConfigSection::Download => to_json(&config.download)?,
+    LauncherConfigSection::GlobalGameConfig => to_json(&config.global_game_config)?,
+    LauncherConfigSection::GameDirectories => Value::Object(
+      config
+        .local_game_directories
+        .iter()
+        .map(|dir| (dir.name.clone(), to_json(&dir.dir).unwrap_or_default()))
+        .collect(),
+    ),
+    LauncherConfigSection::InstanceConfigs => {
+      let mut instances = Map::new();
+      for instance in &snapshot.instances {
+        instances.insert(
+          instance.name.clone(),
+          serde_json::json!({
+            "useSpecGameConfig": instance.use_spec_game_config,
+            "specGameConfig": to_json(&instance.spec_game_config)?,
+          }),
+        );
+      }
+      Value::Object(instances)
+    }
+    LauncherConfigSection::AuthServers => Value::Object(
+      snapshot
+        .auth_servers
+        .iter()
+        .map(|server| {
+          let name = server.metadata["meta"]["serverName"].clone();
+          (server.auth_url.clone(), name)
+        })
+        .collect(),
+    ),
+  })
+}
+
+fn flatten_json(prefix: &str, value: &Value, entries: &mut BTreeMap<String, Value>) {
+  match value {
+    Value::Object(map) if !map.is_empty() => {
+      for (key, value) in map {
+        let key_path = if prefix.is_empty() {
+          key.clone()
+        } else {
+          format!("{}.{}", prefix, key)
+        };
+        flatten_json(&key_path, value, entries);
+      }
+    }
+    _ => {
+      entries.insert(prefix.to_string(), value.clone());
+    }
+  }
+}
+
+/// Lists the changed leaf values of the selected sections, with camelCase key paths.
+pub fn diff_snapshots(
+  current: &LauncherConfigSnapshot,
+  incoming: &LauncherConfigSnapshot,
+  sections: &[LauncherConfigSection],
+) -> SJMCLResult<Vec<LauncherConfigChange>> {
+  let mut changes = Vec::new();
+  for section in sections {
+    let mut current_entries = BTreeMap::new();
+    let mut incoming_entries = BTreeMap::new();
+    flatten_json(
+      "",
+      &get_section_value(current, *section)?,
+      &mut current_entries,
+    );
+    flatten_json(
+      "",
+      &get_section_value(incoming, *section)?,
+      &mut incoming_entries,
+    );
+
+    let mut key_paths: Vec<&String> = current_entries
+      .keys()
+      .chain(incoming_entries.keys())
+      .collect();
+    key_paths.sort();
+    key_paths.dedup();
+    for key_path in key_paths {
+      let current_value = current_entries.get(key_path).cloned().unwrap_or_default();
+      let incoming_value = incoming_entries.get(key_path).cloned().unwrap_or_default();
+      if current_value != incoming_value {
+        changes.push(LauncherConfigChange {
+          section: *section,
+          key_path: key_path.clone(),
+          current: current_value,
+          incoming: incoming_value,
+        });
+      }
+    }
+  }
+  Ok(changes)
+}
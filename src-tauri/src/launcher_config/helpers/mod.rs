@@ -1,3 +1,4 @@
+pub mod config_transfer;
 pub mod java;
 pub mod memory;
 pub mod misc;
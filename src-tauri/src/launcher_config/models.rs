@@ -1,12 +1,16 @@
 use crate::{
-  launcher_config::constants::{CONFIG_PARTIAL_UPDATE_EVENT, LAUNCHER_CFG_FILE_NAME},
+  instance::models::misc::Instance,
+  launcher_config::constants::{
+    CONFIG_PARTIAL_UPDATE_EVENT, LAUNCHER_CFG_FILE_NAME, LAUNCHER_CFG_SCHEMA_VERSION,
+  },
   partial::PartialUpdate,
-  storage::Storage,
+  storage::{introduce_schema_version, MigrationStep, SchemaMigration, Storage},
   utils::{string::snake_to_camel_case, sys_info},
   APP_DATA_DIR, EXE_DIR, IS_PORTABLE,
 };
 use partial_derive::Partial;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use smart_default::SmartDefault;
 use std::path::PathBuf;
 use strum_macros::Display;
@@ -108,6 +112,16 @@ structstruck::strike! {
     #[default(LauncherVisiablity::Always)]
     pub launcher_visibility: LauncherVisiablity,
     pub display_game_log: bool,
+    pub world_backup: struct {
+      pub auto_backup_before_launch: bool,
+      // retention policies, a snapshot is kept if any of them keeps it (all 0 means keep all).
+      pub retention: struct WorldBackupRetention {
+        #[default = 10]
+        pub keep_last: usize,
+        pub keep_daily: usize,
+        pub keep_weekly: usize,
+      },
+    },
     pub advanced_options: struct {
       pub enabled: bool,
     },
@@ -159,6 +173,8 @@ structstruck::strike! {
   #[strikethrough[derive(SmartDefault)]]
   #[strikethrough[serde(default)]]
   pub struct LauncherConfig {
+    #[default(LAUNCHER_CFG_SCHEMA_VERSION)]
+    pub schema_version: u32,
     pub basic_info: struct {
       #[default = "dev"]
       pub launcher_version: String,
@@ -205,6 +221,9 @@ structstruck::strike! {
       pub source: struct {
         #[default = "auto"]
         pub strategy: String,
+        // "modrinth" or "curseforge", which entry is shown first in the merged resource search
+        #[default = "modrinth"]
+        pub preferred_resource_source: String,
       },
       pub transmission: struct {
         #[default = true]
@@ -238,6 +257,10 @@ structstruck::strike! {
         pub instances_nav_type: String,
         #[default = true]
         pub launch_page_quick_switch: bool,
+        #[default = true]
+        pub auto_check_resource_updates: bool,
+        #[default = 24]
+        pub resource_update_check_interval: u32, // in hours
       }
     },
     pub global_game_config: GameConfig,
@@ -315,6 +338,132 @@ impl Storage for LauncherConfig {
   }
 }
 
+impl SchemaMigration for LauncherConfig {
+  const SCHEMA_VERSION: u32 = LAUNCHER_CFG_SCHEMA_VERSION;
+  const MIGRATIONS: &'static [MigrationStep] = &[introduce_schema_version];
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LauncherConfigSection {
+  Appearance,
+  Download,
+  GlobalGameConfig,
+  GameDirectories,
+  InstanceConfigs,
+  AuthServers, // without client ids
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LauncherConfigImportMode {
+  Merge,   // overlay the imported values onto the current ones, and append to the lists
+  Replace, // reset the selected sections to the imported values (defaults for the missing ones)
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct InstanceConfigExport {
+  pub instance_name: String,
+  pub use_spec_game_config: bool,
+  pub spec_game_config: Option<Value>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AuthServerExport {
+  pub auth_url: String,
+  pub metadata: Value,
+}
+
+// the sections are kept as raw json, so files from older or newer launchers can still be merged.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LauncherConfigExportPayload {
+  pub launcher_version: String,
+  pub exported_at: i64,
+  pub appearance: Option<Value>,
+  pub download: Option<Value>,
+  pub global_game_config: Option<Value>,
+  pub game_directories: Option<Vec<GameDirectory>>,
+  pub instance_configs: Option<Vec<InstanceConfigExport>>,
+  pub auth_servers: Option<Vec<AuthServerExport>>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LauncherConfigExportFile {
+  pub schema_version: u32,
+  pub checksum: String, // sha256 of the serialized payload
+  pub payload: Value,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LauncherConfigChange {
+  pub section: LauncherConfigSection,
+  pub key_path: String,
+  pub current: Value,  // null if added
+  pub incoming: Value, // null if removed
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LauncherConfigImportPreview {
+  pub schema_version: u32,
+  pub launcher_version: String,
+  pub exported_at: i64,
+  pub sections: Vec<LauncherConfigSection>, // sections contained in the file
+  pub changes: Vec<LauncherConfigChange>,
+}
+
+/// Per-version settings found in the files of other launchers, used both as the import
+/// preview and as the patch applied onto the instance config.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedInstanceSettings {
+  pub instance_name: String,
+  pub source_launchers: Vec<String>,
+  pub description: Option<String>,
+  pub icon_src: Option<String>,
+  pub starred: Option<bool>,
+  pub spec_game_config: Option<GameConfig>,
+}
+
+impl ImportedInstanceSettings {
+  pub fn is_empty(&self) -> bool {
+    self.description.is_none()
+      && self.icon_src.is_none()
+      && self.starred.is_none()
+      && self.spec_game_config.is_none()
+  }
+
+  // fields already set are kept, so the first launcher merged in takes precedence.
+  pub fn merge(&mut self, other: ImportedInstanceSettings) {
+    self.source_launchers.extend(other.source_launchers);
+    self.description = self.description.take().or(other.description);
+    self.icon_src = self.icon_src.take().or(other.icon_src);
+    self.starred = self.starred.take().or(other.starred);
+    self.spec_game_config = self.spec_game_config.take().or(other.spec_game_config);
+  }
+
+  pub fn apply_to(&self, instance: &mut Instance) {
+    if let Some(ref description) = self.description {
+      instance.description = description.clone();
+    }
+    if let Some(ref icon_src) = self.icon_src {
+      instance.icon_src = icon_src.clone();
+    }
+    if let Some(starred) = self.starred {
+      instance.starred = starred;
+    }
+    if let Some(ref config) = self.spec_game_config {
+      instance.use_spec_game_config = true;
+      instance.spec_game_config = Some(config.clone());
+    }
+  }
+}
+
 #[derive(Debug, Display)]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 pub enum LauncherConfigError {
@@ -327,6 +476,9 @@ pub enum LauncherConfigError {
   JavaExecInvalid,
   HasActiveDownloadTasks,
   FileDeletionFailed,
+  ConfigFileParseError,
+  ConfigChecksumMismatch,
+  UnsupportedSchemaVersion,
 }
 
 impl std::error::Error for LauncherConfigError {}
@@ -11,15 +11,17 @@ mod tasks;
 mod utils;
 
 use account::{
-  helpers::authlib_injector::info::refresh_and_update_auth_servers, models::AccountInfo,
+  helpers::authlib_injector::info::refresh_and_update_auth_servers,
+  models::{AccountInfo, PlayerType},
 };
 use instance::helpers::misc::refresh_and_update_instances;
 use instance::models::misc::Instance;
-use launch::models::LaunchingState;
+use launch::models::{LaunchingState, PlayHistory};
 use launcher_config::{
   helpers::java::refresh_and_update_javas,
   models::{JavaInfo, LauncherConfig},
 };
+use resource::models::ResourceUpdateRecords;
 use std::path::PathBuf;
 use std::sync::{LazyLock, Mutex};
 use std::{collections::HashMap, sync::OnceLock};
@@ -68,12 +70,16 @@ pub async fn run() {
       launcher_config::commands::restore_launcher_config,
       launcher_config::commands::export_launcher_config,
       launcher_config::commands::import_launcher_config,
+      launcher_config::commands::export_launcher_config_to_file,
+      launcher_config::commands::retrieve_launcher_config_import_preview,
+      launcher_config::commands::import_launcher_config_from_file,
       launcher_config::commands::retrieve_custom_background_list,
       launcher_config::commands::add_custom_background,
       launcher_config::commands::delete_custom_background,
       launcher_config::commands::retrieve_java_list,
       launcher_config::commands::validate_java,
       launcher_config::commands::check_game_directory,
+      launcher_config::commands::retrieve_instance_settings_import_preview,
       launcher_config::commands::clear_download_cache,
       account::commands::retrieve_player_list,
       account::commands::add_player_offline,
@@ -84,13 +90,19 @@ pub async fn run() {
       account::commands::add_player_3rdparty_password,
       account::commands::relogin_player_3rdparty_password,
       account::commands::add_player_from_selection,
+      account::commands::switch_player_profile,
       account::commands::update_player_skin_offline_preset,
+      account::commands::upload_player_skin,
+      account::commands::reset_player_skin,
+      account::commands::retrieve_player_capes,
+      account::commands::update_player_cape,
       account::commands::delete_player,
       account::commands::refresh_player,
       account::commands::retrieve_auth_server_list,
       account::commands::add_auth_server,
       account::commands::delete_auth_server,
       account::commands::fetch_auth_server,
+      account::commands::diagnose_auth_server,
       instance::commands::retrieve_instance_list,
       instance::commands::create_instance,
       instance::commands::update_instance_config,
@@ -99,18 +111,49 @@ pub async fn run() {
       instance::commands::retrieve_instance_subdir_path,
       instance::commands::delete_instance,
       instance::commands::rename_instance,
+      instance::commands::duplicate_instance,
       instance::commands::copy_resource_to_instances,
       instance::commands::move_resource_to_instance,
       instance::commands::retrieve_world_list,
       instance::commands::retrieve_world_details,
+      instance::commands::update_world_level_data,
+      instance::commands::create_world_backup,
+      instance::commands::retrieve_world_backup_list,
+      instance::commands::restore_world_backup,
+      instance::commands::delete_world_backup,
+      instance::commands::retrieve_world_datapack_list,
+      instance::commands::add_world_datapack,
+      instance::commands::download_world_datapack,
+      instance::commands::toggle_world_datapack,
       instance::commands::retrieve_game_server_list,
+      instance::commands::add_game_server,
+      instance::commands::update_game_server,
+      instance::commands::delete_game_server,
+      instance::commands::reorder_game_servers,
+      instance::commands::apply_game_server_list,
       instance::commands::retrieve_local_mod_list,
       instance::commands::retrieve_resource_pack_list,
+      instance::commands::retrieve_active_resource_packs,
+      instance::commands::update_active_resource_packs,
+      instance::commands::retrieve_game_options,
+      instance::commands::update_game_options,
+      instance::commands::retrieve_game_options_template,
+      instance::commands::save_game_options_template,
+      instance::commands::update_game_options_template_settings,
+      instance::commands::sync_game_options_template,
       instance::commands::retrieve_server_resource_pack_list,
       instance::commands::retrieve_schematic_list,
+      instance::commands::retrieve_schematic_materials,
       instance::commands::retrieve_shader_pack_list,
+      instance::commands::retrieve_shader_pack_options,
+      instance::commands::save_shader_pack_options,
       instance::commands::retrieve_screenshot_list,
+      instance::commands::delete_screenshots,
+      instance::commands::move_screenshots,
+      instance::commands::copy_screenshot_to_clipboard,
+      instance::commands::export_screenshots,
       instance::commands::toggle_mod_by_extension,
+      instance::commands::toggle_resource_pinned,
       instance::commands::create_launch_desktop_shortcut,
       instance::commands::finish_mod_loader_install,
       instance::commands::retrieve_modpack_meta_info,
@@ -122,14 +165,23 @@ pub async fn run() {
       launch::commands::open_game_log_window,
       launch::commands::retrieve_game_log,
       launch::commands::retrieve_game_launching_state,
+      launch::commands::retrieve_running_games,
       launch::commands::export_game_crash_info,
+      launch::commands::retrieve_play_sessions,
+      launch::commands::retrieve_play_statistics,
+      launch::commands::export_play_history,
       resource::commands::fetch_game_version_list,
       resource::commands::fetch_game_version_specific,
       resource::commands::fetch_mod_loader_version_list,
       resource::commands::fetch_resource_list_by_name,
+      resource::commands::fetch_merged_resource_list_by_name,
       resource::commands::fetch_resource_version_packs,
       resource::commands::download_game_server,
       resource::commands::fetch_remote_resource_by_local,
+      resource::commands::fetch_remote_resources_by_local_mods,
+      resource::commands::retrieve_resource_updates,
+      resource::commands::check_resource_updates,
+      resource::commands::update_all_resources_in_instance,
       resource::commands::update_mods,
       resource::commands::fetch_remote_resource_by_id,
       discover::commands::fetch_news_sources_info,
@@ -169,12 +221,18 @@ pub async fn run() {
 
       // Set the launcher config and other states
       // Also extract assets in `setup_with_app()` if the application is portable
-      let mut launcher_config: LauncherConfig = LauncherConfig::load().unwrap_or_default();
+      // a broken or newer config file is backed up and never saved over (see `storage`)
+      let mut launcher_config: LauncherConfig =
+        LauncherConfig::load_with_migration().unwrap_or_default();
       launcher_config.setup_with_app(app.handle()).unwrap();
       launcher_config.save().unwrap();
       app.manage(Mutex::new(launcher_config));
 
-      let account_info = AccountInfo::load().unwrap_or_default();
+      let account_info = AccountInfo::load_with_migration().unwrap_or_default();
+      let has_offline_players = account_info
+        .players
+        .iter()
+        .any(|player| player.player_type == PlayerType::Offline);
       app.manage(Mutex::new(account_info));
 
       let instances: HashMap<String, Instance> = HashMap::new();
@@ -188,9 +246,15 @@ pub async fn run() {
       let client = build_sjmcl_client(app.handle(), true, false);
       app.manage(client);
 
-      let launching_queue = Vec::<LaunchingState>::new();
+      let launching_queue = HashMap::<u64, LaunchingState>::new();
       app.manage(Mutex::new(launching_queue));
 
+      let play_history = PlayHistory::load().unwrap_or_default();
+      app.manage(Mutex::new(play_history));
+
+      let resource_updates = ResourceUpdateRecords::load().unwrap_or_default();
+      app.manage(Mutex::new(resource_updates));
+
       // check if full account feature (offline and 3rd-party login) is available
       let app_handle = app.handle().clone();
       tauri::async_runtime::spawn(async move {
@@ -199,6 +263,15 @@ pub async fn run() {
           .unwrap_or_default();
       });
 
+      // Prepare the signing key of the local yggdrasil server, which serves offline skins
+      if has_offline_players {
+        tauri::async_runtime::spawn(async move {
+          if let Err(e) = account::helpers::local_yggdrasil::get_or_load_key().await {
+            log::error!("failed to prepare the local yggdrasil key: {}", e.0);
+          }
+        });
+      }
+
       // Refresh all auth servers
       let app_handle = app.handle().clone();
       tauri::async_runtime::spawn(async move {
@@ -224,6 +297,18 @@ pub async fn run() {
         tasks::background::monitor_background_process(app_handle).await;
       });
 
+      // Check resource updates of all instances at startup and periodically
+      let app_handle = app.handle().clone();
+      tauri::async_runtime::spawn(async move {
+        tasks::background::check_resource_updates_periodically(app_handle).await;
+      });
+
+      // Refresh the tokens of online players before they expire
+      let app_handle = app.handle().clone();
+      tauri::async_runtime::spawn(async move {
+        tasks::background::maintain_accounts_periodically(app_handle).await;
+      });
+
       // On platforms other than macOS, set the menu to empty to hide the default menu.
       // On macOS, some shortcuts depend on default menu: https://github.com/tauri-apps/tauri/issues/12458
       if os.clone() != "macos" {
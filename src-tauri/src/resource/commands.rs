@@ -5,6 +5,7 @@ use super::{
       fabric::get_fabric_meta_by_game_version, forge::get_forge_meta_by_game_version,
       neoforge::get_neoforge_meta_by_game_version,
     },
+    merged_search::fetch_resource_list_by_name_merged,
     misc::get_source_priority_list,
     modrinth::{fetch_resource_list_by_name_modrinth, fetch_resource_version_packs_modrinth},
     version_manifest::get_game_version_manifest,
@@ -17,8 +18,11 @@ use super::{
 use crate::{
   error::SJMCLResult,
   instance::{
-    helpers::{client_json::McClientInfo, misc::get_instance_subdir_path_by_id},
-    models::misc::{InstanceSubdirType, ModLoaderType},
+    helpers::{
+      client_json::McClientInfo,
+      misc::{get_instance_subdir_path_by_id, get_instance_subdir_paths},
+    },
+    models::misc::{Instance, InstanceError, InstanceSubdirType, ModLoaderType},
   },
   launcher_config::models::LauncherConfig,
   resource::{
@@ -27,13 +31,25 @@ use crate::{
         fetch_remote_resource_by_id_curseforge, fetch_remote_resource_by_local_curseforge,
       },
       modrinth::{fetch_remote_resource_by_id_modrinth, fetch_remote_resource_by_local_modrinth},
+      update_check::{
+        check_and_store_resource_updates, fetch_remote_resources_by_local_files,
+        finish_resource_updates_after_download, get_mod_loader_name,
+      },
+    },
+    models::{
+      LocalModRemoteInfo, ModUpdateQuery, OtherResourceFileInfo, OtherResourceInfo,
+      OtherResourceSource, PendingResourceUpdate, ResourceUpdateInfo, ResourceUpdateRecords,
     },
-    models::{ModUpdateQuery, OtherResourceFileInfo, OtherResourceInfo, OtherResourceSource},
   },
+  storage::Storage,
   tasks::{commands::schedule_progressive_task_group, download::DownloadParam, PTaskParam},
+  utils::fs::get_files_with_regex,
 };
+use regex::RegexBuilder;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Mutex;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
 use tauri_plugin_http::reqwest;
 
 #[tauri::command]
@@ -103,6 +119,14 @@ pub async fn fetch_resource_list_by_name(
   }
 }
 
+#[tauri::command]
+pub async fn fetch_merged_resource_list_by_name(
+  app: AppHandle,
+  query: OtherResourceSearchQuery,
+) -> SJMCLResult<OtherResourceSearchRes> {
+  fetch_resource_list_by_name_merged(&app, &query).await
+}
+
 #[tauri::command]
 pub async fn fetch_resource_version_packs(
   app: AppHandle,
@@ -172,6 +196,48 @@ pub async fn fetch_remote_resource_by_local(
   }
 }
 
+#[tauri::command]
+pub async fn fetch_remote_resources_by_local_mods(
+  app: AppHandle,
+  instance_id: String,
+) -> SJMCLResult<HashMap<String, LocalModRemoteInfo>> {
+  let (mods_dir, instance) = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let state = binding.lock()?;
+    let instance = state
+      .get(&instance_id)
+      .ok_or(InstanceError::InstanceNotFoundByID)?;
+    let mods_dir = get_instance_subdir_paths(&app, instance, &[&InstanceSubdirType::Mods])
+      .and_then(|mut paths| paths.pop())
+      .ok_or(InstanceError::InstanceNotFoundByID)?;
+    (mods_dir, instance.clone())
+  };
+
+  let valid_extensions = RegexBuilder::new(r"\.jar(\.disabled)?$")
+    .case_insensitive(true)
+    .build()
+    .unwrap();
+  let paths = get_files_with_regex(&mods_dir, &valid_extensions).unwrap_or_default();
+  let remote_infos = fetch_remote_resources_by_local_files(
+    &app,
+    &paths,
+    get_mod_loader_name(&instance.mod_loader.loader_type),
+    &instance.version,
+    &instance.update_channel,
+  )
+  .await;
+
+  Ok(
+    remote_infos
+      .into_values()
+      .filter_map(|info| {
+        let file_name = info.file_path.file_name()?.to_string_lossy().to_string();
+        Some((file_name, info))
+      })
+      .collect(),
+  )
+}
+
 #[tauri::command]
 pub async fn update_mods(
   app: AppHandle,
@@ -200,21 +266,136 @@ pub async fn update_mods(
     download_tasks.push(PTaskParam::Download(download_param));
   }
 
-  schedule_progressive_task_group(app, "mod-update".to_string(), download_tasks, true).await?;
+  let group =
+    schedule_progressive_task_group(app.clone(), "mod-update".to_string(), download_tasks, true)
+      .await?;
 
-  for query in &queries {
-    let old_file_path = &query.old_file_path;
-    let new_file_path = mods_dir.join(&query.file_name);
-
-    if old_file_path != &new_file_path.to_string_lossy().to_string() {
-      let old_backup_path = format!("{}.old", old_file_path);
-      if let Err(e) = std::fs::rename(old_file_path, &old_backup_path) {
-        log::error!("Failed to rename old mod file: {}", e);
-        return Err(ResourceError::FileOperationError.into());
-      }
+  let pending_updates = queries
+    .iter()
+    .map(|query| PendingResourceUpdate {
+      instance_id: instance_id.clone(),
+      task_group: group.task_group.clone(),
+      old_path: PathBuf::from(&query.old_file_path),
+      new_path: mods_dir.join(&query.file_name),
+      sha1: Some(query.sha1.clone()).filter(|sha1| !sha1.is_empty()),
+    })
+    .collect();
+  add_pending_resource_updates(&app, pending_updates)?;
+  tauri::async_runtime::spawn(finish_resource_updates_after_download(
+    app,
+    group.task_group,
+  ));
+
+  Ok(())
+}
+
+// persisted, so the updates are still finished if the launcher exits before the downloads end.
+fn add_pending_resource_updates(
+  app: &AppHandle,
+  pending_updates: Vec<PendingResourceUpdate>,
+) -> SJMCLResult<()> {
+  let binding = app.state::<Mutex<ResourceUpdateRecords>>();
+  let mut records = binding.lock()?;
+  records.pending_updates.extend(pending_updates);
+  records.save()?;
+  Ok(())
+}
+
+#[tauri::command]
+pub fn retrieve_resource_updates(
+  app: AppHandle,
+  instance_id: String,
+) -> SJMCLResult<Vec<ResourceUpdateInfo>> {
+  let binding = app.state::<Mutex<ResourceUpdateRecords>>();
+  let records = binding.lock()?;
+  Ok(
+    records
+      .instances
+      .get(&instance_id)
+      .cloned()
+      .unwrap_or_default(),
+  )
+}
+
+#[tauri::command]
+pub async fn check_resource_updates(
+  app: AppHandle,
+  instance_id: Option<String>,
+) -> SJMCLResult<()> {
+  check_and_store_resource_updates(&app, instance_id.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn update_all_resources_in_instance(
+  app: AppHandle,
+  instance_id: String,
+) -> SJMCLResult<()> {
+  let instance_name = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let state = binding.lock()?;
+    state
+      .get(&instance_id)
+      .ok_or(InstanceError::InstanceNotFoundByID)?
+      .name
+      .clone()
+  };
+  let updates = {
+    let binding = app.state::<Mutex<ResourceUpdateRecords>>();
+    let records = binding.lock()?;
+    records
+      .instances
+      .get(&instance_id)
+      .cloned()
+      .unwrap_or_default()
+  };
+  if updates.is_empty() {
+    return Ok(());
+  }
+
+  let mut download_tasks = Vec::new();
+  let mut file_paths = Vec::new();
+  for update in &updates {
+    let Some(dir) = update.file_path.parent() else {
+      continue;
+    };
+    // keep disabled mods disabled after the update
+    let mut file_name = update.latest.file_name.clone();
+    if update.file_name.ends_with(".disabled") {
+      file_name.push_str(".disabled");
     }
+    let new_file_path = dir.join(file_name);
+    download_tasks.push(PTaskParam::Download(DownloadParam {
+      src: url::Url::parse(&update.latest.download_url).map_err(|_| ResourceError::ParseError)?,
+      dest: new_file_path.clone(),
+      filename: None,
+      sha1: Some(update.latest.sha1.clone()).filter(|sha1| !sha1.is_empty()),
+    }));
+    file_paths.push((update, new_file_path));
   }
 
+  let group = schedule_progressive_task_group(
+    app.clone(),
+    format!("resource-update?{}", instance_name),
+    download_tasks,
+    true,
+  )
+  .await?;
+  // the records are cleared one by one as their downloads complete, same as `update_mods`
+  let pending_updates = file_paths
+    .into_iter()
+    .map(|(update, new_file_path)| PendingResourceUpdate {
+      instance_id: instance_id.clone(),
+      task_group: group.task_group.clone(),
+      old_path: update.file_path.clone(),
+      new_path: new_file_path,
+      sha1: Some(update.latest.sha1.clone()).filter(|sha1| !sha1.is_empty()),
+    })
+    .collect();
+  add_pending_resource_updates(&app, pending_updates)?;
+  tauri::async_runtime::spawn(finish_resource_updates_after_download(
+    app.clone(),
+    group.task_group,
+  ));
   Ok(())
 }
 
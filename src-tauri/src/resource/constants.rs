@@ -0,0 +1,3 @@
+pub const RESOURCE_UPDATES_FILE_NAME: &str = "sjmcl.resource-updates.json";
+
+pub const RESOURCE_UPDATES_CHECKED_EVENT: &str = "resource:updates-checked";
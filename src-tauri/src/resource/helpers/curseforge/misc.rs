@@ -3,11 +3,14 @@ use std::collections::HashMap;
 use std::env;
 
 use crate::error::SJMCLResult;
+use crate::resource::helpers::http_cache::send_with_cache;
 use crate::resource::models::{
   OtherResourceDependency, OtherResourceFileInfo, OtherResourceInfo, OtherResourceSearchRes,
   OtherResourceSource, OtherResourceVersionPack, ResourceError,
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_http::reqwest;
 
 use super::super::misc::version_pack_sort;
@@ -22,35 +25,26 @@ pub enum CurseForgeRequestType<'a, P> {
 }
 
 pub async fn make_curseforge_request<T, P>(
-  client: &reqwest::Client,
+  app: &AppHandle,
   url: &str,
   request_type: CurseForgeRequestType<'_, P>,
+  cache_ttl: Duration,
 ) -> SJMCLResult<T>
 where
   T: serde::de::DeserializeOwned,
   P: serde::Serialize,
 {
+  let client = app.state::<reqwest::Client>();
   let request_builder = match request_type {
     CurseForgeRequestType::GetWithParams(params) => client.get(url).query(params),
     CurseForgeRequestType::Get => client.get(url),
     CurseForgeRequestType::Post(payload) => client.post(url).json(payload),
-  };
-
-  let response = request_builder
-    .header("x-api-key", CURSEFORGE_API_KEY)
-    .header("accept", "application/json")
-    .send()
-    .await
-    .map_err(|_| ResourceError::NetworkError)?;
-
-  if !response.status().is_success() {
-    return Err(ResourceError::NetworkError.into());
   }
+  .header("x-api-key", CURSEFORGE_API_KEY)
+  .header("accept", "application/json");
 
-  response
-    .json::<T>()
-    .await
-    .map_err(|_| ResourceError::ParseError.into())
+  let body = send_with_cache(app, request_builder, cache_ttl).await?;
+  serde_json::from_str::<T>(&body).map_err(|_| ResourceError::ParseError.into())
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -59,6 +53,22 @@ pub enum CurseForgeApiEndpoint {
   ModFiles,
   Fingerprints,
   Project,
+  Projects,
+  Files,
+}
+
+impl CurseForgeApiEndpoint {
+  // how long a cached response stays fresh without revalidation
+  pub fn cache_ttl(&self) -> Duration {
+    let minutes = match self {
+      CurseForgeApiEndpoint::Search => 10,
+      CurseForgeApiEndpoint::ModFiles | CurseForgeApiEndpoint::Projects => 30,
+      CurseForgeApiEndpoint::Project => 60,
+      // fingerprints and file ids always point to the same file
+      CurseForgeApiEndpoint::Fingerprints | CurseForgeApiEndpoint::Files => 6 * 60,
+    };
+    Duration::from_secs(minutes * 60)
+  }
 }
 
 pub fn get_curseforge_api(
@@ -78,6 +88,8 @@ pub fn get_curseforge_api(
       let mod_id = id.ok_or(ResourceError::ParseError)?;
       format!("{}/mods/{}", base_url, mod_id)
     }
+    CurseForgeApiEndpoint::Projects => format!("{}/mods", base_url),
+    CurseForgeApiEndpoint::Files => format!("{}/mods/files", base_url),
   };
 
   Ok(url_str)
@@ -89,8 +101,12 @@ structstruck::strike! {
   pub struct CurseForgeProject {
     pub id: u32,
     pub class_id: u32,
+    #[serde(default)]
+    pub slug: String,
     pub links: pub struct {
       pub website_url: String,
+      #[serde(default)]
+      pub source_url: Option<String>,
     },
     pub name: String,
     pub summary: String,
@@ -102,6 +118,22 @@ structstruck::strike! {
       pub url: String,
     }>,
     pub date_modified: String,
+    #[serde(default)]
+    pub latest_files_indexes: Vec<pub struct CurseForgeFileIndex {
+      pub game_version: String,
+      pub file_id: u32,
+      #[serde(default)]
+      pub release_type: u32,
+      pub mod_loader: Option<u32>,
+    }>,
+    #[serde(default)]
+    pub latest_files: Vec<pub struct CurseForgeLatestFile {
+      #[serde(default)]
+      pub hashes: Vec<pub struct CurseForgeLatestFileHash {
+        pub value: String,
+        pub algo: u32,
+      }>,
+    }>,
   }
 }
 
@@ -140,6 +172,8 @@ structstruck::strike! {
       pub mod_id: u32,
       pub relation_type: u32,
     }>,
+    #[serde(default)]
+    pub file_fingerprint: u64,
   }
 }
 
@@ -167,6 +201,16 @@ pub struct CurseForgeGetProjectRes {
   pub data: CurseForgeProject,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CurseForgeGetProjectsRes {
+  pub data: Vec<CurseForgeProject>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CurseForgeGetFilesRes {
+  pub data: Vec<CurseForgeFileInfo>,
+}
+
 fn extract_versions_and_loaders(game_versions: &[String]) -> (Vec<String>, Vec<String>) {
   let mut versions = Vec::new();
   let mut loaders = Vec::new();
@@ -249,6 +293,8 @@ impl From<CurseForgeProject> for OtherResourceInfo {
       last_updated: project.date_modified,
       downloads: project.download_count,
       source: OtherResourceSource::CurseForge,
+      slug: project.slug,
+      alt_sources: Vec::new(),
     }
   }
 }
@@ -6,18 +6,21 @@ use std::collections::HashMap;
 use std::path::Path;
 
 use crate::error::SJMCLResult;
+use crate::instance::models::misc::ReleaseChannel;
+use crate::resource::helpers::http_cache::REVALIDATE_CACHE_TTL;
 use crate::resource::models::{
   OtherResourceFileInfo, OtherResourceInfo, OtherResourceSearchQuery, OtherResourceSearchRes,
-  OtherResourceVersionPack, OtherResourceVersionPackQuery, ResourceError,
+  OtherResourceSource, OtherResourceVersionPack, OtherResourceVersionPackQuery,
+  RemoteResourceMatch, ResourceError,
 };
-use tauri::{AppHandle, Manager};
-use tauri_plugin_http::reqwest;
+use tauri::AppHandle;
 
 use misc::{
-  cvt_category_to_id, cvt_mod_loader_to_id, cvt_sort_by_to_id, cvt_type_to_class_id,
-  cvt_version_to_type_id, get_curseforge_api, make_curseforge_request,
+  cvt_category_to_id, cvt_id_to_release_type, cvt_mod_loader_to_id, cvt_sort_by_to_id,
+  cvt_type_to_class_id, cvt_version_to_type_id, get_curseforge_api, make_curseforge_request,
   map_curseforge_file_to_version_pack, CurseForgeApiEndpoint, CurseForgeFileInfo,
-  CurseForgeFingerprintRes, CurseForgeGetProjectRes, CurseForgeRequestType, CurseForgeSearchRes,
+  CurseForgeFingerprintRes, CurseForgeGetFilesRes, CurseForgeGetProjectRes,
+  CurseForgeGetProjectsRes, CurseForgeRequestType, CurseForgeSearchRes,
   CurseForgeVersionPackSearchRes,
 };
 
@@ -28,6 +31,14 @@ pub async fn fetch_resource_list_by_name_curseforge(
   app: &AppHandle,
   query: &OtherResourceSearchQuery,
 ) -> SJMCLResult<OtherResourceSearchRes> {
+  Ok(search_projects_curseforge(app, query).await?.into())
+}
+
+// raw search results, the merged search needs the source urls and file hashes of the projects
+pub async fn search_projects_curseforge(
+  app: &AppHandle,
+  query: &OtherResourceSearchQuery,
+) -> SJMCLResult<CurseForgeSearchRes> {
   let url = get_curseforge_api(CurseForgeApiEndpoint::Search, None)?;
 
   let OtherResourceSearchQuery {
@@ -64,15 +75,13 @@ pub async fn fetch_resource_list_by_name_curseforge(
   params.insert("sortOrder".to_string(), sort_order.to_string());
   params.insert("index".to_string(), (page * page_size).to_string());
   params.insert("pageSize".to_string(), page_size.to_string());
-
-  let client = app.state::<reqwest::Client>();
-  let results = make_curseforge_request::<CurseForgeSearchRes, ()>(
-    &client,
+  make_curseforge_request::<CurseForgeSearchRes, ()>(
+    app,
     &url,
     CurseForgeRequestType::GetWithParams(&params),
+    CurseForgeApiEndpoint::Search.cache_ttl(),
   )
-  .await?;
-  Ok(results.into())
+  .await
 }
 
 pub async fn fetch_resource_version_packs_curseforge(
@@ -110,12 +119,11 @@ pub async fn fetch_resource_version_packs_curseforge(
     params.insert("index".to_string(), (page * page_size).to_string());
     params.insert("pageSize".to_string(), page_size.to_string());
 
-    let client = app.state::<reqwest::Client>();
-
     let results = make_curseforge_request::<CurseForgeVersionPackSearchRes, ()>(
-      &client,
+      app,
       &url,
       CurseForgeRequestType::GetWithParams(&params),
+      CurseForgeApiEndpoint::ModFiles.cache_ttl(),
     )
     .await?;
 
@@ -154,12 +162,11 @@ pub async fn fetch_remote_resource_by_local_curseforge(
   let payload = json!({
     "fingerprints": [hash]
   });
-
-  let client = app.state::<reqwest::Client>();
   let fingerprint_response = make_curseforge_request::<CurseForgeFingerprintRes, _>(
-    &client,
+    app,
     &url,
     CurseForgeRequestType::Post(&payload),
+    CurseForgeApiEndpoint::Fingerprints.cache_ttl(),
   )
   .await?;
 
@@ -171,17 +178,114 @@ pub async fn fetch_remote_resource_by_local_curseforge(
   }
 }
 
+/// Looks up local files by their fingerprints, and the newest file of each matched project
+/// for the given loader and game version, with one request each. Keyed by fingerprint.
+pub async fn fetch_remote_resources_by_fingerprints_curseforge(
+  app: &AppHandle,
+  fingerprints: &[u32],
+  mod_loader: Option<&str>,
+  game_version: &str,
+  channel: &ReleaseChannel,
+) -> SJMCLResult<HashMap<u32, RemoteResourceMatch>> {
+  if fingerprints.is_empty() {
+    return Ok(HashMap::new());
+  }
+
+  // the update checker must see the newest files, so these lookups always revalidate the cache
+  let url = get_curseforge_api(CurseForgeApiEndpoint::Fingerprints, None)?;
+  let payload = json!({ "fingerprints": fingerprints });
+  let fingerprint_response = make_curseforge_request::<CurseForgeFingerprintRes, _>(
+    app,
+    &url,
+    CurseForgeRequestType::Post(&payload),
+    REVALIDATE_CACHE_TTL,
+  )
+  .await?;
+  let current_files: Vec<CurseForgeFileInfo> = fingerprint_response
+    .data
+    .exact_matches
+    .into_iter()
+    .map(|exact_match| exact_match.file)
+    .collect();
+
+  // the latest file indexes of the projects point to the newest file of each game version and loader
+  let loader_id = mod_loader.map(cvt_mod_loader_to_id).unwrap_or(0);
+  let url = get_curseforge_api(CurseForgeApiEndpoint::Projects, None)?;
+  let mod_ids: Vec<u32> = current_files.iter().map(|file| file.mod_id).collect();
+  let payload = json!({ "modIds": mod_ids });
+  let projects = make_curseforge_request::<CurseForgeGetProjectsRes, _>(
+    app,
+    &url,
+    CurseForgeRequestType::Post(&payload),
+    REVALIDATE_CACHE_TTL,
+  )
+  .await
+  .map(|res| res.data)
+  .unwrap_or_default();
+  let latest_file_ids: HashMap<u32, u32> = projects
+    .iter()
+    .filter_map(|project| {
+      project
+        .latest_files_indexes
+        .iter()
+        .filter(|index| index.game_version == game_version)
+        .filter(|index| loader_id == 0 || index.mod_loader == Some(loader_id))
+        .filter(|index| channel.accepts(&cvt_id_to_release_type(index.release_type)))
+        .map(|index| index.file_id)
+        .max() // newer files always have greater ids
+        .map(|file_id| (project.id, file_id))
+    })
+    .collect();
+
+  let latest_files: HashMap<u32, CurseForgeFileInfo> = if latest_file_ids.is_empty() {
+    HashMap::new()
+  } else {
+    let url = get_curseforge_api(CurseForgeApiEndpoint::Files, None)?;
+    let payload = json!({ "fileIds": latest_file_ids.values().collect::<Vec<_>>() });
+    make_curseforge_request::<CurseForgeGetFilesRes, _>(
+      app,
+      &url,
+      CurseForgeRequestType::Post(&payload),
+      REVALIDATE_CACHE_TTL,
+    )
+    .await
+    .map(|res| res.data)
+    .unwrap_or_default()
+    .into_iter()
+    .map(|file| (file.mod_id, file))
+    .collect()
+  };
+
+  let mut matches = HashMap::new();
+  for cf_file in current_files {
+    let current: OtherResourceFileInfo = (&cf_file, None).into();
+    let latest_file = latest_files.get(&cf_file.mod_id);
+    let has_update = latest_file.is_some_and(|file| file.id > cf_file.id);
+    let latest: Option<OtherResourceFileInfo> = latest_file.map(|file| (file, None).into());
+    matches.insert(
+      cf_file.file_fingerprint as u32,
+      RemoteResourceMatch {
+        source: OtherResourceSource::CurseForge,
+        current,
+        latest,
+        has_update,
+      },
+    );
+  }
+  Ok(matches)
+}
+
 pub async fn fetch_remote_resource_by_id_curseforge(
   app: &AppHandle,
   resource_id: &str,
 ) -> SJMCLResult<OtherResourceInfo> {
   let url = get_curseforge_api(CurseForgeApiEndpoint::Project, Some(resource_id))?;
-  let client = app.state::<reqwest::Client>();
 
   let results = make_curseforge_request::<CurseForgeGetProjectRes, ()>(
-    &client,
+    app,
     &url,
     CurseForgeRequestType::Get,
+    CurseForgeApiEndpoint::Project.cache_ttl(),
   )
   .await?;
 
@@ -0,0 +1,135 @@
+use crate::error::SJMCLResult;
+use crate::launcher_config::models::LauncherConfig;
+use crate::storage::{load_json_async, save_json_async};
+use futures::stream::{self, StreamExt};
+use lazy_static::lazy_static;
+use murmur2::murmur2;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use sha2::Sha512;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager};
+
+const FILE_HASH_CACHE_FILE_NAME: &str = "file-hashes.json";
+
+lazy_static! {
+  // serializes the load-modify-save of the cache file between concurrent checks.
+  static ref FILE_HASH_CACHE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+// hashes used to identify local files on Modrinth (sha1 / sha512) and CurseForge (fingerprint).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHashes {
+  pub sha1: String,
+  pub sha512: String,
+  pub fingerprint: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedFileHashes {
+  size: u64,
+  modified: u64,
+  #[serde(flatten)]
+  hashes: FileHashes,
+}
+
+pub fn compute_file_hashes(path: &Path) -> SJMCLResult<FileHashes> {
+  let content = std::fs::read(path)?;
+  let sha1 = hex::encode(Sha1::digest(&content));
+  let sha512 = hex::encode(Sha512::digest(&content));
+  // CurseForge fingerprint: murmur2 (seed 1) of the file without whitespace bytes
+  let filtered_bytes: Vec<u8> = content
+    .into_iter()
+    .filter(|&byte| !matches!(byte, 0x09 | 0x0a | 0x0d | 0x20))
+    .collect();
+  Ok(FileHashes {
+    sha1,
+    sha512,
+    fingerprint: murmur2(&filtered_bytes, 1),
+  })
+}
+
+fn get_size_and_modified(path: &Path) -> Option<(u64, u64)> {
+  let metadata = std::fs::metadata(path).ok()?;
+  let modified = metadata
+    .modified()
+    .ok()?
+    .duration_since(SystemTime::UNIX_EPOCH)
+    .ok()?
+    .as_secs();
+  Some((metadata.len(), modified))
+}
+
+/// Hashes the files in parallel. Results are cached in the download cache directory and
+/// reused as long as the size and modification time of a file are unchanged.
+/// Files that can not be read are left out of the result.
+pub async fn get_file_hashes_cached(
+  app: &AppHandle,
+  paths: &[PathBuf],
+) -> HashMap<PathBuf, FileHashes> {
+  let cache_path = {
+    let binding = app.state::<Mutex<LauncherConfig>>();
+    let config = binding.lock().unwrap();
+    config
+      .download
+      .cache
+      .directory
+      .join(FILE_HASH_CACHE_FILE_NAME)
+  };
+  let cache: HashMap<PathBuf, CachedFileHashes> =
+    load_json_async(&cache_path).await.unwrap_or_default();
+
+  let mut results = HashMap::new();
+  let mut to_compute = Vec::new();
+  for path in paths {
+    let Some((size, modified)) = get_size_and_modified(path) else {
+      continue;
+    };
+    match cache.get(path) {
+      Some(cached) if cached.size == size && cached.modified == modified => {
+        results.insert(path.clone(), cached.hashes.clone());
+      }
+      _ => to_compute.push((path.clone(), size, modified)),
+    }
+  }
+
+  let parallelism = std::thread::available_parallelism()
+    .map(|n| n.get())
+    .unwrap_or(4);
+  let computed: Vec<_> = stream::iter(to_compute)
+    .map(|(path, size, modified)| async move {
+      let file_path = path.clone();
+      let hashes = tokio::task::spawn_blocking(move || compute_file_hashes(&file_path)).await;
+      (path, size, modified, hashes)
+    })
+    .buffer_unordered(parallelism)
+    .collect()
+    .await;
+
+  // reload the cache under the lock, other checks may have saved it while hashing.
+  let _lock = FILE_HASH_CACHE_LOCK.lock().await;
+  let mut cache: HashMap<PathBuf, CachedFileHashes> =
+    load_json_async(&cache_path).await.unwrap_or_default();
+  for (path, size, modified, hashes) in computed {
+    if let Ok(Ok(hashes)) = hashes {
+      results.insert(path.clone(), hashes.clone());
+      cache.insert(
+        path,
+        CachedFileHashes {
+          size,
+          modified,
+          hashes,
+        },
+      );
+    }
+  }
+
+  cache.retain(|path, _| path.exists());
+  let _ = save_json_async(&cache, &cache_path).await;
+  results
+}
@@ -0,0 +1,271 @@
+use crate::{
+  error::SJMCLResult, launcher_config::models::LauncherConfig, resource::models::ResourceError,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_http::reqwest::{
+  self,
+  header::{self, HeaderMap, HeaderValue},
+  RequestBuilder, StatusCode,
+};
+
+const HTTP_CACHE_DIR_NAME: &str = "http-cache";
+// the least recently stored responses are evicted beyond this total size
+const HTTP_CACHE_MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+// freshness lifetimes of the APIs without their own endpoint enum
+pub const GAME_VERSION_MANIFEST_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+pub const LOADER_META_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+// always revalidate, for lookups which must see the latest data (e.g. the update checker)
+pub const REVALIDATE_CACHE_TTL: Duration = Duration::ZERO;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HttpCacheEntry {
+  url: String,
+  etag: Option<String>,
+  last_modified: Option<String>,
+  stored_at: u64,
+  max_age: Option<u64>, // from Cache-Control, takes precedence over the endpoint TTL
+  body: String,
+}
+
+impl HttpCacheEntry {
+  fn is_fresh(&self, ttl: Duration) -> bool {
+    let age = get_unix_time().saturating_sub(self.stored_at);
+    age < self.max_age.unwrap_or(ttl.as_secs())
+  }
+}
+
+#[derive(Debug, Default)]
+struct CacheControl {
+  no_store: bool,
+  no_cache: bool,
+  max_age: Option<u64>,
+}
+
+impl CacheControl {
+  fn from_headers(headers: &HeaderMap) -> Self {
+    let mut cache_control = CacheControl::default();
+    let Some(value) = headers
+      .get(header::CACHE_CONTROL)
+      .and_then(|v| v.to_str().ok())
+    else {
+      return cache_control;
+    };
+    for directive in value.split(',').map(|d| d.trim().to_lowercase()) {
+      if directive == "no-store" {
+        cache_control.no_store = true;
+      } else if directive == "no-cache" {
+        cache_control.no_cache = true;
+      } else if let Some(max_age) = directive.strip_prefix("max-age=") {
+        cache_control.max_age = max_age.trim_matches('"').parse().ok();
+      }
+    }
+    cache_control
+  }
+
+  // a response with `no-cache` is stored but always revalidated
+  fn get_max_age(&self) -> Option<u64> {
+    if self.no_cache {
+      Some(0)
+    } else {
+      self.max_age
+    }
+  }
+}
+
+fn get_unix_time() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default()
+}
+
+fn get_http_cache_dir(app: &AppHandle) -> PathBuf {
+  let binding = app.state::<Mutex<LauncherConfig>>();
+  let config = binding.lock().unwrap();
+  config.download.cache.directory.join(HTTP_CACHE_DIR_NAME)
+}
+
+fn get_cache_key(request: &reqwest::Request) -> String {
+  let mut hasher = Sha256::new();
+  hasher.update(request.method().as_str());
+  hasher.update(request.url().as_str());
+  if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+    hasher.update(body);
+  }
+  hex::encode(hasher.finalize())
+}
+
+async fn load_cache_entry(path: &Path) -> Option<HttpCacheEntry> {
+  let content = tokio::fs::read_to_string(path).await.ok()?;
+  serde_json::from_str(&content).ok()
+}
+
+async fn save_cache_entry(path: &Path, entry: &HttpCacheEntry) {
+  let result = async {
+    if let Some(parent) = path.parent() {
+      tokio::fs::create_dir_all(parent).await?;
+    }
+    // write to a temp file first, so a concurrent reader never sees half of an entry
+    let temp_path = path.with_extension("json.tmp");
+    tokio::fs::write(&temp_path, serde_json::to_vec(entry)?).await?;
+    tokio::fs::rename(&temp_path, path).await
+  }
+  .await;
+  if let Err(e) = result {
+    log::error!("Failed to save http cache entry {}: {}", entry.url, e);
+  }
+}
+
+/// Removes the least recently stored entries until the cache fits in `max_size` bytes.
+fn evict_http_cache(cache_dir: &Path, max_size: u64) -> std::io::Result<()> {
+  let mut entries = Vec::new();
+  let mut total_size = 0;
+  for entry in fs::read_dir(cache_dir)? {
+    let entry = entry?;
+    let metadata = entry.metadata()?;
+    if !metadata.is_file() {
+      continue;
+    }
+    total_size += metadata.len();
+    entries.push((
+      entry.path(),
+      metadata.len(),
+      metadata.modified().unwrap_or(UNIX_EPOCH),
+    ));
+  }
+  if total_size <= max_size {
+    return Ok(());
+  }
+
+  entries.sort_by_key(|(_, _, modified)| *modified);
+  for (path, size, _) in entries {
+    if total_size <= max_size {
+      break;
+    }
+    if fs::remove_file(&path).is_ok() {
+      total_size -= size;
+    }
+  }
+  Ok(())
+}
+
+/// Sends a request through the on-disk cache under the download cache dir and returns the body.
+///
+/// A cached response younger than its max-age (or `ttl` without Cache-Control) is returned
+/// directly; an older one is revalidated with ETag / Last-Modified, and served as is when the
+/// network or the server fails. A zero `ttl` ignores the max-age and always revalidates.
+///
+/// # Example
+///
+/// ```rust
+/// let client = app.state::<reqwest::Client>();
+/// let body = send_with_cache(&app, client.get(url), Duration::from_secs(600)).await?;
+/// ```
+pub async fn send_with_cache(
+  app: &AppHandle,
+  request_builder: RequestBuilder,
+  ttl: Duration,
+) -> SJMCLResult<String> {
+  let (client, request) = request_builder.build_split();
+  let mut request = request.map_err(|_| ResourceError::NetworkError)?;
+
+  let cache_dir = get_http_cache_dir(app);
+  let cache_path = cache_dir.join(format!("{}.json", get_cache_key(&request)));
+  let cached = load_cache_entry(&cache_path).await;
+  if let Some(entry) = cached
+    .as_ref()
+    .filter(|entry| !ttl.is_zero() && entry.is_fresh(ttl))
+  {
+    return Ok(entry.body.clone());
+  }
+
+  if let Some(entry) = &cached {
+    let headers = request.headers_mut();
+    if let Some(etag) = entry
+      .etag
+      .as_deref()
+      .and_then(|v| HeaderValue::from_str(v).ok())
+    {
+      headers.insert(header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = entry
+      .last_modified
+      .as_deref()
+      .and_then(|v| HeaderValue::from_str(v).ok())
+    {
+      headers.insert(header::IF_MODIFIED_SINCE, last_modified);
+    }
+  }
+
+  let url = request.url().to_string();
+  let response = match client.execute(request).await {
+    Ok(response)
+      if response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED =>
+    {
+      response
+    }
+    Ok(response)
+      if response.status().is_client_error()
+        && response.status() != StatusCode::TOO_MANY_REQUESTS =>
+    {
+      return Err(ResourceError::NetworkError.into());
+    }
+    _ => {
+      // offline, rate limited or server failure, fall back to the stale copy
+      return match cached {
+        Some(entry) => {
+          log::warn!("Serving stale cached response of {}", url);
+          Ok(entry.body)
+        }
+        None => Err(ResourceError::NetworkError.into()),
+      };
+    }
+  };
+
+  let cache_control = CacheControl::from_headers(response.headers());
+  if response.status() == StatusCode::NOT_MODIFIED {
+    let Some(mut entry) = cached else {
+      return Err(ResourceError::NetworkError.into());
+    };
+    entry.stored_at = get_unix_time();
+    entry.max_age = cache_control.get_max_age();
+    save_cache_entry(&cache_path, &entry).await;
+    return Ok(entry.body);
+  }
+
+  let get_header = |name: header::HeaderName| {
+    response
+      .headers()
+      .get(name)
+      .and_then(|v| v.to_str().ok())
+      .map(str::to_string)
+  };
+  let etag = get_header(header::ETAG);
+  let last_modified = get_header(header::LAST_MODIFIED);
+  let body = response
+    .text()
+    .await
+    .map_err(|_| ResourceError::NetworkError)?;
+
+  if !cache_control.no_store {
+    let entry = HttpCacheEntry {
+      url,
+      etag,
+      last_modified,
+      stored_at: get_unix_time(),
+      max_age: cache_control.get_max_age(),
+      body: body.clone(),
+    };
+    save_cache_entry(&cache_path, &entry).await;
+    tokio::task::spawn_blocking(move || evict_http_cache(&cache_dir, HTTP_CACHE_MAX_SIZE));
+  }
+  Ok(body)
+}
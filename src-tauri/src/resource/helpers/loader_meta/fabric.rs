@@ -1,3 +1,4 @@
+use super::super::http_cache::{send_with_cache, LOADER_META_CACHE_TTL};
 use super::super::misc::get_download_api;
 use crate::error::{SJMCLError, SJMCLResult};
 use crate::instance::models::misc::ModLoaderType;
@@ -34,30 +35,25 @@ pub async fn get_fabric_meta_by_game_version(
     let url = get_download_api(*source_type, ResourceType::FabricMeta)?
       .join("v2/versions/loader/")?
       .join(game_version)?;
-    match client.get(url).send().await {
-      Ok(response) => {
-        if response.status().is_success() {
-          if let Ok(manifest) = response.json::<Vec<FabricMetaItem>>().await {
-            return Ok(
-              manifest
-                .into_iter()
-                .map(|info| ModLoaderResourceInfo {
-                  loader_type: ModLoaderType::Fabric,
-                  version: info.loader.version,
-                  description: String::new(),
-                  stable: info.loader.stable,
-                  branch: None,
-                })
-                .collect(),
-            );
-          } else {
-            return Err(ResourceError::ParseError.into());
-          }
-        } else {
-          continue;
-        }
-      }
+    let body = match send_with_cache(app, client.get(url), LOADER_META_CACHE_TTL).await {
+      Ok(body) => body,
       Err(_) => continue,
+    };
+    if let Ok(manifest) = serde_json::from_str::<Vec<FabricMetaItem>>(&body) {
+      return Ok(
+        manifest
+          .into_iter()
+          .map(|info| ModLoaderResourceInfo {
+            loader_type: ModLoaderType::Fabric,
+            version: info.loader.version,
+            description: String::new(),
+            stable: info.loader.stable,
+            branch: None,
+          })
+          .collect(),
+      );
+    } else {
+      return Err(ResourceError::ParseError.into());
     }
   }
   Err(SJMCLError(String::new()))
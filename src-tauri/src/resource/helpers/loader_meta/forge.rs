@@ -1,3 +1,4 @@
+use super::super::http_cache::{send_with_cache, LOADER_META_CACHE_TTL};
 use super::super::misc::get_download_api;
 use crate::error::{SJMCLError, SJMCLResult};
 use crate::instance::models::misc::ModLoaderType;
@@ -25,31 +26,23 @@ async fn get_forge_meta_by_game_version_bmcl(
   let url = get_download_api(SourceType::BMCLAPIMirror, ResourceType::ForgeMeta)?
     .join("minecraft/")?
     .join(game_version)?;
-  match client.get(url).send().await {
-    Ok(response) => {
-      if response.status().is_success() {
-        if let Ok(mut manifest) = response.json::<Vec<ForgeMetaItem>>().await {
-          manifest.sort_by(|a, b| b.build.cmp(&a.build));
-          Ok(
-            manifest
-              .into_iter()
-              .map(|info| ModLoaderResourceInfo {
-                loader_type: ModLoaderType::Forge,
-                version: info.version,
-                description: info.modified,
-                stable: true,
-                branch: info.branch.and_then(|v| v.as_str().map(String::from)),
-              })
-              .collect(),
-          )
-        } else {
-          Err(ResourceError::ParseError.into())
-        }
-      } else {
-        Err(ResourceError::NetworkError.into())
-      }
-    }
-    Err(_) => Err(ResourceError::NetworkError.into()),
+  let body = send_with_cache(app, client.get(url), LOADER_META_CACHE_TTL).await?;
+  if let Ok(mut manifest) = serde_json::from_str::<Vec<ForgeMetaItem>>(&body) {
+    manifest.sort_by(|a, b| b.build.cmp(&a.build));
+    Ok(
+      manifest
+        .into_iter()
+        .map(|info| ModLoaderResourceInfo {
+          loader_type: ModLoaderType::Forge,
+          version: info.version,
+          description: info.modified,
+          stable: true,
+          branch: info.branch.and_then(|v| v.as_str().map(String::from)),
+        })
+        .collect(),
+    )
+  } else {
+    Err(ResourceError::ParseError.into())
   }
 }
 
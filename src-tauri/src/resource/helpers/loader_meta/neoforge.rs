@@ -1,3 +1,4 @@
+use super::super::http_cache::{send_with_cache, LOADER_META_CACHE_TTL};
 use super::super::misc::get_download_api;
 use crate::error::SJMCLResult;
 use crate::instance::models::misc::ModLoaderType;
@@ -40,76 +41,60 @@ async fn get_neoforge_meta_by_game_version_official(
   let client = app.state::<reqwest::Client>();
   if game_version == "1.20.1" {
     let old_url = get_download_api(SourceType::Official, ResourceType::NeoforgeMetaForge)?;
-    if let Ok(response) = client.get(old_url).send().await {
-      if response.status().is_success() {
-        match response.json::<NeoforgeVersions>().await {
-          Ok(versions) => {
-            let mut results = Vec::new();
-            for version in versions.versions.into_iter() {
-              if let Some(cap) = OLD_VERSION_REGEX.captures(&version) {
-                let sort_key = (
-                  cap[1].parse::<i32>()?,
-                  cap[2].parse::<i32>()?,
-                  cap[3].parse::<i32>()?,
-                );
-                results.push((
-                  sort_key,
-                  ModLoaderResourceInfo {
-                    loader_type: ModLoaderType::NeoForge,
-                    version,
-                    description: String::new(),
-                    stable: !versions.is_snapshot,
-                    branch: None,
-                  },
-                ));
-              }
-            }
-            results.sort_by(|a, b| b.0.cmp(&a.0));
-            Ok(results.into_iter().map(|r| r.1).collect())
+    let body = send_with_cache(app, client.get(old_url), LOADER_META_CACHE_TTL).await?;
+    match serde_json::from_str::<NeoforgeVersions>(&body) {
+      Ok(versions) => {
+        let mut results = Vec::new();
+        for version in versions.versions.into_iter() {
+          if let Some(cap) = OLD_VERSION_REGEX.captures(&version) {
+            let sort_key = (
+              cap[1].parse::<i32>()?,
+              cap[2].parse::<i32>()?,
+              cap[3].parse::<i32>()?,
+            );
+            results.push((
+              sort_key,
+              ModLoaderResourceInfo {
+                loader_type: ModLoaderType::NeoForge,
+                version,
+                description: String::new(),
+                stable: !versions.is_snapshot,
+                branch: None,
+              },
+            ));
           }
-          Err(_) => Err(ResourceError::ParseError.into()),
         }
-      } else {
-        Err(ResourceError::NetworkError.into())
+        results.sort_by(|a, b| b.0.cmp(&a.0));
+        Ok(results.into_iter().map(|r| r.1).collect())
       }
-    } else {
-      Err(ResourceError::NetworkError.into())
+      Err(_) => Err(ResourceError::ParseError.into()),
     }
   } else {
     let new_url = get_download_api(SourceType::Official, ResourceType::NeoforgeMetaNeoforge)?;
-    if let Ok(response) = client.get(new_url).send().await {
-      if response.status().is_success() {
-        match response.json::<NeoforgeVersions>().await {
-          Ok(versions) => {
-            let mut results: Vec<(i32, ModLoaderResourceInfo)> = Vec::new();
-            for version in versions.versions.into_iter() {
-              if let Some(cap) = NEW_VERSION_REGEX.captures(&version.clone()) {
-                if *game_version
-                  == format!("1.{}.{}", cap[1].parse::<i32>()?, cap[2].parse::<i32>()?)
-                {
-                  results.push((
-                    cap[3].parse::<i32>()?,
-                    ModLoaderResourceInfo {
-                      loader_type: ModLoaderType::NeoForge,
-                      version,
-                      description: String::new(),
-                      stable: cap.get(4).is_none(),
-                      branch: None,
-                    },
-                  ));
-                }
-              }
+    let body = send_with_cache(app, client.get(new_url), LOADER_META_CACHE_TTL).await?;
+    match serde_json::from_str::<NeoforgeVersions>(&body) {
+      Ok(versions) => {
+        let mut results: Vec<(i32, ModLoaderResourceInfo)> = Vec::new();
+        for version in versions.versions.into_iter() {
+          if let Some(cap) = NEW_VERSION_REGEX.captures(&version.clone()) {
+            if *game_version == format!("1.{}.{}", cap[1].parse::<i32>()?, cap[2].parse::<i32>()?) {
+              results.push((
+                cap[3].parse::<i32>()?,
+                ModLoaderResourceInfo {
+                  loader_type: ModLoaderType::NeoForge,
+                  version,
+                  description: String::new(),
+                  stable: cap.get(4).is_none(),
+                  branch: None,
+                },
+              ));
             }
-            results.sort_by(|a, b| b.0.cmp(&a.0));
-            Ok(results.into_iter().map(|r| r.1).collect())
           }
-          Err(_) => Err(ResourceError::ParseError.into()),
         }
-      } else {
-        Err(ResourceError::NetworkError.into())
+        results.sort_by(|a, b| b.0.cmp(&a.0));
+        Ok(results.into_iter().map(|r| r.1).collect())
       }
-    } else {
-      Err(ResourceError::NetworkError.into())
+      Err(_) => Err(ResourceError::ParseError.into()),
     }
   }
 }
@@ -125,48 +110,40 @@ async fn get_neoforge_meta_by_game_version_bmcl(
   )?
   .join("list/")?
   .join(game_version)?;
-  match client.get(url).send().await {
-    Ok(response) => {
-      if response.status().is_success() {
-        if let Ok(mut manifest) = response.json::<Vec<NeoforgeMetaItem>>().await {
-          manifest.sort_by(|a, b| {
-            let parse_version = |v: &str| {
-              let stripped = if game_version == "1.20.1" {
-                v.strip_prefix("1.20.1-").unwrap_or(v)
-              } else {
-                v
-              };
-              stripped
-                .split('.')
-                .flat_map(|part| part.split('-'))
-                .map(|s| s.parse::<i32>().unwrap_or(0))
-                .collect::<Vec<_>>()
-            };
-            parse_version(&b.version).cmp(&parse_version(&a.version))
-          });
-          Ok(
-            manifest
-              .into_iter()
-              .map(|info| {
-                let stable = !info.version.ends_with("beta");
-                ModLoaderResourceInfo {
-                  loader_type: ModLoaderType::NeoForge,
-                  version: info.version,
-                  description: String::new(),
-                  stable,
-                  branch: None,
-                }
-              })
-              .collect(),
-          )
+  let body = send_with_cache(app, client.get(url), LOADER_META_CACHE_TTL).await?;
+  if let Ok(mut manifest) = serde_json::from_str::<Vec<NeoforgeMetaItem>>(&body) {
+    manifest.sort_by(|a, b| {
+      let parse_version = |v: &str| {
+        let stripped = if game_version == "1.20.1" {
+          v.strip_prefix("1.20.1-").unwrap_or(v)
         } else {
-          Err(ResourceError::ParseError.into())
-        }
-      } else {
-        Err(ResourceError::NetworkError.into())
-      }
-    }
-    Err(_) => Err(ResourceError::NetworkError.into()),
+          v
+        };
+        stripped
+          .split('.')
+          .flat_map(|part| part.split('-'))
+          .map(|s| s.parse::<i32>().unwrap_or(0))
+          .collect::<Vec<_>>()
+      };
+      parse_version(&b.version).cmp(&parse_version(&a.version))
+    });
+    Ok(
+      manifest
+        .into_iter()
+        .map(|info| {
+          let stable = !info.version.ends_with("beta");
+          ModLoaderResourceInfo {
+            loader_type: ModLoaderType::NeoForge,
+            version: info.version,
+            description: String::new(),
+            stable,
+            branch: None,
+          }
+        })
+        .collect(),
+    )
+  } else {
+    Err(ResourceError::ParseError.into())
   }
 }
 
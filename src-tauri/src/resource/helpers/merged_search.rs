@@ -0,0 +1,391 @@
+use crate::{
+  error::SJMCLResult,
+  launcher_config::models::LauncherConfig,
+  resource::{
+    helpers::{
+      curseforge::{misc::cvt_type_to_class_id, search_projects_curseforge},
+      modrinth::{
+        fetch_project_ids_by_sha1_modrinth, fetch_resource_list_by_name_modrinth,
+        fetch_source_urls_modrinth,
+      },
+    },
+    models::{
+      OtherResourceAltSource, OtherResourceInfo, OtherResourceSearchQuery, OtherResourceSearchRes,
+      OtherResourceSource,
+    },
+  },
+};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const ALL_FILTER: &str = "All";
+
+// (CurseForge class id, Modrinth category, CurseForge category).
+// The merged search uses the Modrinth categories as tags of both platforms.
+const CATEGORY_PAIRS: &[(u32, &str, &str)] = &[
+  // mods
+  (6, "adventure", "Adventure and RPG"),
+  (6, "decoration", "Cosmetic"),
+  (6, "equipment", "Armor, Tools, and Weapons"),
+  (6, "food", "Food"),
+  (6, "library", "API and Library"),
+  (6, "magic", "Magic"),
+  (6, "mobs", "Mobs"),
+  (6, "optimization", "Performance"),
+  (6, "social", "Server Utility"),
+  (6, "storage", "Storage"),
+  (6, "technology", "Technology"),
+  (6, "transportation", "Player Transport"),
+  (6, "utility", "Utility & QoL"),
+  (6, "worldgen", "World Gen"),
+  // resource packs
+  (12, "16x", "16x"),
+  (12, "32x", "32x"),
+  (12, "64x", "64x"),
+  (12, "128x", "128x"),
+  (12, "256x", "256x"),
+  (12, "512x+", "512x and Higher"),
+  (12, "fonts", "Font Packs"),
+  (12, "medieval", "Medieval"),
+  (12, "modded", "Mod Support"),
+  (12, "realistic", "Photo Realistic"),
+  // mod packs
+  (4471, "adventure", "Adventure and RPG"),
+  (4471, "challenging", "Hardcore"),
+  (4471, "combat", "Combat / PvP"),
+  (4471, "kitchen-sink", "Extra Large"),
+  (4471, "lightweight", "Small / Light"),
+  (4471, "magic", "Magic"),
+  (4471, "multiplayer", "Multiplayer"),
+  (4471, "quests", "Quests"),
+  (4471, "technology", "Tech"),
+  // shader packs
+  (6552, "fantasy", "Fantasy"),
+  (6552, "realistic", "Realistic"),
+  (6552, "vanilla-like", "Vanilla"),
+  // data packs
+  (6945, "adventure", "Adventure"),
+  (6945, "library", "Library"),
+  (6945, "magic", "Magic"),
+  (6945, "technology", "Tech"),
+  (6945, "utility", "Utility"),
+];
+
+fn cvt_tag_to_curseforge(tag: &str, class_id: u32) -> Option<&'static str> {
+  CATEGORY_PAIRS
+    .iter()
+    .find(|(id, modrinth, _)| *id == class_id && *modrinth == tag)
+    .map(|(_, _, curseforge)| *curseforge)
+}
+
+fn cvt_tag_from_curseforge(tag: &str, class_id: u32) -> Option<&'static str> {
+  CATEGORY_PAIRS
+    .iter()
+    .find(|(id, _, curseforge)| *id == class_id && *curseforge == tag)
+    .map(|(_, modrinth, _)| *modrinth)
+}
+
+// the merged search uses the Modrinth search indexes as sort options
+fn cvt_sort_by_to_curseforge(sort_by: &str) -> &'static str {
+  match sort_by {
+    "downloads" => "Total downloads",
+    "newest" => "Creation date",
+    "updated" => "Latest update",
+    _ => "Popularity",
+  }
+}
+
+// e.g. `https://www.GitHub.com/foo/bar.git/` and `github.com/foo/bar` are the same repository
+fn normalize_source_url(url: &str) -> String {
+  let url = url.trim().to_lowercase();
+  let url = url
+    .trim_start_matches("https://")
+    .trim_start_matches("http://")
+    .trim_start_matches("www.");
+  url
+    .trim_end_matches('/')
+    .trim_end_matches(".git")
+    .to_string()
+}
+
+struct CurseForgeEntry {
+  info: OtherResourceInfo,
+  source_url: Option<String>,
+  sha1_hashes: Vec<String>, // of the latest files
+}
+
+/// Pairs the entries which are the same project, by slug, source url, then shared file hashes.
+/// Returns a map from CurseForge index to Modrinth index.
+async fn match_same_projects(
+  app: &AppHandle,
+  modrinth_list: &[OtherResourceInfo],
+  curseforge_list: &[CurseForgeEntry],
+) -> HashMap<usize, usize> {
+  let mut pairs = HashMap::new();
+  let mut matched = HashSet::new();
+
+  let slugs: HashMap<String, usize> = modrinth_list
+    .iter()
+    .enumerate()
+    .map(|(j, info)| (info.slug.to_lowercase(), j))
+    .collect();
+  for (i, entry) in curseforge_list.iter().enumerate() {
+    if entry.info.slug.is_empty() {
+      continue;
+    }
+    if let Some(&j) = slugs.get(&entry.info.slug.to_lowercase()) {
+      if matched.insert(j) {
+        pairs.insert(i, j);
+      }
+    }
+  }
+
+  // the search hits of Modrinth do not contain the source urls
+  let unmatched_ids: Vec<&str> = modrinth_list
+    .iter()
+    .enumerate()
+    .filter(|(j, _)| !matched.contains(j))
+    .map(|(_, info)| info.id.as_str())
+    .collect();
+  let has_source_urls = curseforge_list
+    .iter()
+    .enumerate()
+    .any(|(i, entry)| !pairs.contains_key(&i) && entry.source_url.is_some());
+  if !unmatched_ids.is_empty() && has_source_urls {
+    let source_urls = fetch_source_urls_modrinth(app, &unmatched_ids)
+      .await
+      .unwrap_or_else(|e| {
+        log::error!("Failed to fetch source urls from Modrinth: {}", e.0);
+        HashMap::new()
+      });
+    let urls: HashMap<String, usize> = modrinth_list
+      .iter()
+      .enumerate()
+      .filter_map(|(j, info)| Some((normalize_source_url(source_urls.get(&info.id)?), j)))
+      .collect();
+    for (i, entry) in curseforge_list.iter().enumerate() {
+      if pairs.contains_key(&i) {
+        continue;
+      }
+      let Some(url) = entry.source_url.as_deref().map(normalize_source_url) else {
+        continue;
+      };
+      if let Some(&j) = urls.get(&url) {
+        if matched.insert(j) {
+          pairs.insert(i, j);
+        }
+      }
+    }
+  }
+
+  let unmatched_hashes: Vec<&str> = curseforge_list
+    .iter()
+    .enumerate()
+    .filter(|(i, _)| !pairs.contains_key(i))
+    .flat_map(|(_, entry)| entry.sha1_hashes.iter().map(String::as_str))
+    .collect();
+  if !unmatched_hashes.is_empty() && matched.len() < modrinth_list.len() {
+    let project_ids = fetch_project_ids_by_sha1_modrinth(app, &unmatched_hashes)
+      .await
+      .unwrap_or_else(|e| {
+        log::error!("Failed to look up file hashes on Modrinth: {}", e.0);
+        HashMap::new()
+      });
+    let indexes: HashMap<&str, usize> = modrinth_list
+      .iter()
+      .enumerate()
+      .map(|(j, info)| (info.id.as_str(), j))
+      .collect();
+    for (i, entry) in curseforge_list.iter().enumerate() {
+      if pairs.contains_key(&i) {
+        continue;
+      }
+      let found = entry
+        .sha1_hashes
+        .iter()
+        .filter_map(|hash| project_ids.get(hash))
+        .filter_map(|id| indexes.get(id.as_str()).copied())
+        .find(|j| !matched.contains(j));
+      if let Some(j) = found {
+        matched.insert(j);
+        pairs.insert(i, j);
+      }
+    }
+  }
+
+  pairs
+}
+
+fn merge_resource_infos(
+  mut primary: OtherResourceInfo,
+  secondary: OtherResourceInfo,
+) -> OtherResourceInfo {
+  primary.downloads = primary.downloads.saturating_add(secondary.downloads);
+  if secondary.last_updated > primary.last_updated {
+    primary.last_updated = secondary.last_updated;
+  }
+  for tag in secondary.tags {
+    if !primary.tags.contains(&tag) {
+      primary.tags.push(tag);
+    }
+  }
+  if primary.icon_src.is_empty() {
+    primary.icon_src = secondary.icon_src;
+  }
+  primary.alt_sources.push(OtherResourceAltSource {
+    source: secondary.source,
+    resource_id: secondary.id,
+    website_url: secondary.website_url,
+  });
+  primary
+}
+
+/// Searches CurseForge and Modrinth at the same time and merges the same projects into one
+/// entry, whose main source follows the preference in the launcher config.
+/// The tags and sort options of the query are the Modrinth ones.
+pub async fn fetch_resource_list_by_name_merged(
+  app: &AppHandle,
+  query: &OtherResourceSearchQuery,
+) -> SJMCLResult<OtherResourceSearchRes> {
+  let preferred_source = {
+    let binding = app.state::<Mutex<LauncherConfig>>();
+    let config = binding.lock()?;
+    OtherResourceSource::from_str(&config.download.source.preferred_resource_source)
+      .unwrap_or(OtherResourceSource::Modrinth)
+  };
+
+  let class_id = cvt_type_to_class_id(&query.resource_type);
+  // a tag without CurseForge counterpart only searches Modrinth
+  let curseforge_tag = if query.selected_tag.is_empty() || query.selected_tag == ALL_FILTER {
+    Some(ALL_FILTER)
+  } else {
+    cvt_tag_to_curseforge(&query.selected_tag, class_id)
+  };
+  let curseforge_query = curseforge_tag.map(|tag| OtherResourceSearchQuery {
+    selected_tag: tag.to_string(),
+    sort_by: cvt_sort_by_to_curseforge(&query.sort_by).to_string(),
+    game_version: if query.game_version.is_empty() {
+      ALL_FILTER.to_string()
+    } else {
+      query.game_version.clone()
+    },
+    ..query.clone()
+  });
+
+  let (modrinth_res, curseforge_res) =
+    tokio::join!(fetch_resource_list_by_name_modrinth(app, query), async {
+      match &curseforge_query {
+        Some(curseforge_query) => search_projects_curseforge(app, curseforge_query)
+          .await
+          .map(Some),
+        None => Ok(None),
+      }
+    });
+  // one failed platform should not hide the results of the other
+  let (modrinth_res, curseforge_res) = match (modrinth_res, curseforge_res) {
+    (Err(e), Err(_)) => return Err(e),
+    (modrinth_res, curseforge_res) => (
+      modrinth_res.unwrap_or_else(|e| {
+        log::error!("Failed to search on Modrinth: {}", e.0);
+        OtherResourceSearchRes::default()
+      }),
+      curseforge_res.unwrap_or_else(|e| {
+        log::error!("Failed to search on CurseForge: {}", e.0);
+        None
+      }),
+    ),
+  };
+
+  let (curseforge_total, curseforge_list) = match curseforge_res {
+    Some(res) => (
+      res.pagination.total_count,
+      res
+        .data
+        .into_iter()
+        .map(|project| {
+          let source_url = project.links.source_url.clone().filter(|u| !u.is_empty());
+          let sha1_hashes = project
+            .latest_files
+            .iter()
+            .flat_map(|file| file.hashes.iter())
+            .filter(|hash| hash.algo == 1)
+            .map(|hash| hash.value.clone())
+            .collect();
+          let mut info = OtherResourceInfo::from(project);
+          info.tags = info
+            .tags
+            .iter()
+            .map(|tag| {
+              cvt_tag_from_curseforge(tag, class_id)
+                .map(str::to_string)
+                .unwrap_or_else(|| tag.clone())
+            })
+            .collect();
+          CurseForgeEntry {
+            info,
+            source_url,
+            sha1_hashes,
+          }
+        })
+        .collect::<Vec<_>>(),
+    ),
+    None => (0, Vec::new()),
+  };
+  let modrinth_total = modrinth_res.total;
+  let modrinth_list = modrinth_res.list;
+  let pairs = match_same_projects(app, &modrinth_list, &curseforge_list).await;
+
+  // index 0 is the preferred platform, keep the relevance order of both by interleaving
+  let curseforge_slots: Vec<Option<OtherResourceInfo>> = curseforge_list
+    .into_iter()
+    .map(|entry| Some(entry.info))
+    .collect();
+  let modrinth_slots: Vec<Option<OtherResourceInfo>> =
+    modrinth_list.into_iter().map(Some).collect();
+  let curseforge_partners = pairs;
+  let modrinth_partners: HashMap<usize, usize> =
+    curseforge_partners.iter().map(|(&i, &j)| (j, i)).collect();
+  let (mut slots, partners) = if preferred_source == OtherResourceSource::CurseForge {
+    (
+      [curseforge_slots, modrinth_slots],
+      [curseforge_partners, modrinth_partners],
+    )
+  } else {
+    (
+      [modrinth_slots, curseforge_slots],
+      [modrinth_partners, curseforge_partners],
+    )
+  };
+
+  let mut list = Vec::new();
+  for rank in 0..slots[0].len().max(slots[1].len()) {
+    for side in 0..2 {
+      let Some(info) = slots[side].get_mut(rank).and_then(Option::take) else {
+        continue;
+      };
+      let partner = partners[side]
+        .get(&rank)
+        .and_then(|&k| slots[1 - side][k].take());
+      list.push(match partner {
+        Some(partner) if side == 0 => merge_resource_infos(info, partner),
+        Some(partner) => merge_resource_infos(partner, info),
+        None => info,
+      });
+    }
+  }
+
+  match query.sort_by.as_str() {
+    "downloads" => list.sort_by(|a, b| b.downloads.cmp(&a.downloads)),
+    "updated" => list.sort_by(|a, b| b.last_updated.cmp(&a.last_updated)),
+    _ => {}
+  }
+
+  Ok(OtherResourceSearchRes {
+    list,
+    total: modrinth_total.max(curseforge_total),
+    page: query.page,
+    page_size: query.page_size,
+  })
+}
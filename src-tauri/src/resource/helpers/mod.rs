@@ -1,5 +1,9 @@
 pub mod curseforge;
+pub mod file_hash;
+pub mod http_cache;
 pub mod loader_meta;
+pub mod merged_search;
 pub mod misc;
 pub mod modrinth;
+pub mod update_check;
 pub mod version_manifest;
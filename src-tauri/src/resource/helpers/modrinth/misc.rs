@@ -1,15 +1,17 @@
 use crate::error::SJMCLResult;
+use crate::resource::helpers::http_cache::send_with_cache;
 use crate::resource::models::{
   OtherResourceDependency, OtherResourceFileInfo, OtherResourceInfo, OtherResourceSearchRes,
   OtherResourceSource, OtherResourceVersionPack, ResourceError,
 };
 use serde::Deserialize;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_http::reqwest;
 
 use super::super::misc::version_pack_sort;
 
 // Enum to represent different request types
-#[allow(dead_code)] // Post is not used now, but may be in the future
 pub enum ModrinthRequestType<'a, P> {
   GetWithParams(&'a std::collections::HashMap<String, String>),
   Get,
@@ -17,33 +19,24 @@ pub enum ModrinthRequestType<'a, P> {
 }
 
 pub async fn make_modrinth_request<T, P>(
-  client: &reqwest::Client,
+  app: &AppHandle,
   url: &str,
   request_type: ModrinthRequestType<'_, P>,
+  cache_ttl: Duration,
 ) -> SJMCLResult<T>
 where
   T: serde::de::DeserializeOwned,
   P: serde::Serialize,
 {
+  let client = app.state::<reqwest::Client>();
   let request_builder = match request_type {
     ModrinthRequestType::GetWithParams(params) => client.get(url).query(params),
     ModrinthRequestType::Get => client.get(url),
     ModrinthRequestType::Post(payload) => client.post(url).json(payload),
   };
 
-  let response = request_builder
-    .send()
-    .await
-    .map_err(|_| ResourceError::NetworkError)?;
-
-  if !response.status().is_success() {
-    return Err(ResourceError::NetworkError.into());
-  }
-
-  response
-    .json::<T>()
-    .await
-    .map_err(|_| ResourceError::ParseError.into())
+  let body = send_with_cache(app, request_builder, cache_ttl).await?;
+  serde_json::from_str::<T>(&body).map_err(|_| ResourceError::ParseError.into())
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -51,7 +44,24 @@ pub enum ModrinthApiEndpoint {
   Search,
   ProjectVersions,
   VersionFile,
+  VersionFiles,
+  VersionFilesUpdate,
   Project,
+  Projects,
+}
+
+impl ModrinthApiEndpoint {
+  // how long a cached response stays fresh without revalidation
+  pub fn cache_ttl(&self) -> Duration {
+    let minutes = match self {
+      ModrinthApiEndpoint::Search => 10,
+      ModrinthApiEndpoint::ProjectVersions | ModrinthApiEndpoint::VersionFilesUpdate => 30,
+      ModrinthApiEndpoint::Project | ModrinthApiEndpoint::Projects => 60,
+      // a file hash always points to the same version
+      ModrinthApiEndpoint::VersionFile | ModrinthApiEndpoint::VersionFiles => 6 * 60,
+    };
+    Duration::from_secs(minutes * 60)
+  }
 }
 
 pub fn get_modrinth_api(endpoint: ModrinthApiEndpoint, param: Option<&str>) -> SJMCLResult<String> {
@@ -67,10 +77,13 @@ pub fn get_modrinth_api(endpoint: ModrinthApiEndpoint, param: Option<&str>) -> S
       let hash = param.ok_or(ResourceError::ParseError)?;
       format!("{}/version_file/{}", base_url, hash)
     }
+    ModrinthApiEndpoint::VersionFiles => format!("{}/version_files", base_url),
+    ModrinthApiEndpoint::VersionFilesUpdate => format!("{}/version_files/update", base_url),
     ModrinthApiEndpoint::Project => {
       let project_id = param.ok_or(ResourceError::ParseError)?;
       format!("{}/project/{}", base_url, project_id)
     }
+    ModrinthApiEndpoint::Projects => format!("{}/projects", base_url),
   };
 
   Ok(url_str)
@@ -92,6 +105,13 @@ pub struct ModrinthProject {
   pub date_modified: String,
 }
 
+// only the fields of `/projects` which are missing in the search hits
+#[derive(Deserialize, Debug)]
+pub struct ModrinthProjectLinks {
+  pub id: String,
+  pub source_url: Option<String>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ModrinthSearchRes {
   pub hits: Vec<ModrinthProject>,
@@ -105,8 +125,12 @@ structstruck::strike! {
   pub struct ModrinthFileInfo {
     pub url: String,
     pub filename: String,
+    #[serde(default)]
+    pub primary: bool,
     pub hashes: pub struct {
       pub sha1: String,
+      #[serde(default)]
+      pub sha512: String,
     },
   }
 }
@@ -215,10 +239,12 @@ impl From<ModrinthProject> for OtherResourceInfo {
       description: project.description,
       icon_src: project.icon_url,
       website_url: format!("https://modrinth.com/mod/{}", project.slug),
+      slug: project.slug,
       tags: project.categories,
       last_updated: project.date_modified,
       downloads: project.downloads,
       source: OtherResourceSource::Modrinth,
+      alt_sources: Vec::new(),
     }
   }
 }
@@ -1,20 +1,24 @@
 pub mod misc;
 
 use crate::error::SJMCLResult;
+use crate::instance::models::misc::ReleaseChannel;
+use crate::resource::helpers::http_cache::REVALIDATE_CACHE_TTL;
 use crate::resource::models::{
   OtherResourceFileInfo, OtherResourceInfo, OtherResourceSearchQuery, OtherResourceSearchRes,
-  OtherResourceVersionPack, OtherResourceVersionPackQuery, ResourceError,
+  OtherResourceSource, OtherResourceVersionPack, OtherResourceVersionPackQuery,
+  RemoteResourceMatch, ResourceError,
 };
 use hex;
+use serde_json::json;
 use sha1::{Digest, Sha1};
 use std::collections::HashMap;
 use std::fs;
-use tauri::{AppHandle, Manager};
-use tauri_plugin_http::reqwest;
+use tauri::AppHandle;
 
 use misc::{
   get_modrinth_api, make_modrinth_request, map_modrinth_file_to_version_pack, ModrinthApiEndpoint,
-  ModrinthProject, ModrinthRequestType, ModrinthSearchRes, ModrinthVersionPack,
+  ModrinthFileInfo, ModrinthProject, ModrinthProjectLinks, ModrinthRequestType, ModrinthSearchRes,
+  ModrinthVersionPack,
 };
 
 const ALL_FILTER: &str = "All";
@@ -52,12 +56,11 @@ pub async fn fetch_resource_list_by_name_modrinth(
   params.insert("offset".to_string(), (page * page_size).to_string());
   params.insert("limit".to_string(), page_size.to_string());
   params.insert("index".to_string(), sort_by.to_string());
-
-  let client = app.state::<reqwest::Client>();
   let results = make_modrinth_request::<ModrinthSearchRes, ()>(
-    &client,
+    app,
     &url,
     ModrinthRequestType::GetWithParams(&params),
+    ModrinthApiEndpoint::Search.cache_ttl(),
   )
   .await?;
   Ok(results.into())
@@ -97,12 +100,11 @@ pub async fn fetch_resource_version_packs_modrinth(
     }
   }
 
-  let client = app.state::<reqwest::Client>();
-
   let results = make_modrinth_request::<Vec<ModrinthVersionPack>, ()>(
-    &client,
+    app,
     &url,
     ModrinthRequestType::GetWithParams(&params),
+    ModrinthApiEndpoint::ProjectVersions.cache_ttl(),
   )
   .await?;
 
@@ -124,12 +126,12 @@ pub async fn fetch_remote_resource_by_local_modrinth(
   params.insert("algorithm".to_string(), "sha1".to_string());
 
   let url = get_modrinth_api(ModrinthApiEndpoint::VersionFile, Some(&hash_string))?;
-  let client = app.state::<reqwest::Client>();
 
   let version_pack = make_modrinth_request::<ModrinthVersionPack, ()>(
-    &client,
+    app,
     &url,
     ModrinthRequestType::GetWithParams(&params),
+    ModrinthApiEndpoint::VersionFile.cache_ttl(),
   )
   .await?;
 
@@ -154,15 +156,175 @@ pub async fn fetch_remote_resource_by_local_modrinth(
   )
 }
 
+fn get_modrinth_file_by_sha512<'a>(
+  version_pack: &'a ModrinthVersionPack,
+  sha512: &str,
+) -> Option<&'a ModrinthFileInfo> {
+  version_pack
+    .files
+    .iter()
+    .find(|file| file.hashes.sha512 == sha512)
+    .or_else(|| get_modrinth_primary_file(version_pack))
+}
+
+/// The main jar of a version, other files can be sources or secondary jars.
+fn get_modrinth_primary_file(version_pack: &ModrinthVersionPack) -> Option<&ModrinthFileInfo> {
+  version_pack
+    .files
+    .iter()
+    .find(|file| file.primary)
+    .or_else(|| version_pack.files.first())
+}
+
+/// Looks up local files by their sha512 hashes, and the newest version of each matched project
+/// for the given loader and game version, with one request each. Keyed by sha512.
+pub async fn fetch_remote_resources_by_hashes_modrinth(
+  app: &AppHandle,
+  sha512_hashes: &[String],
+  mod_loader: Option<&str>,
+  game_version: &str,
+  channel: &ReleaseChannel,
+) -> SJMCLResult<HashMap<String, RemoteResourceMatch>> {
+  if sha512_hashes.is_empty() {
+    return Ok(HashMap::new());
+  }
+
+  let url = get_modrinth_api(ModrinthApiEndpoint::VersionFiles, None)?;
+  let payload = json!({
+    "hashes": sha512_hashes,
+    "algorithm": "sha512",
+  });
+  let current_versions = make_modrinth_request::<HashMap<String, ModrinthVersionPack>, _>(
+    app,
+    &url,
+    ModrinthRequestType::Post(&payload),
+    ModrinthApiEndpoint::VersionFiles.cache_ttl(),
+  )
+  .await?;
+
+  let url = get_modrinth_api(ModrinthApiEndpoint::VersionFilesUpdate, None)?;
+  let mut payload = json!({
+    "hashes": current_versions.keys().collect::<Vec<_>>(),
+    "algorithm": "sha512",
+    "game_versions": [game_version],
+  });
+  if let Some(loader) = mod_loader {
+    payload["loaders"] = json!([loader.to_lowercase()]);
+  }
+  // the update lookup is optional, keep the matched versions if it fails
+  let latest_versions = make_modrinth_request::<HashMap<String, ModrinthVersionPack>, _>(
+    app,
+    &url,
+    ModrinthRequestType::Post(&payload),
+    REVALIDATE_CACHE_TTL, // a cached answer would hide the newly released versions
+  )
+  .await
+  .unwrap_or_default();
+
+  let mut matches = HashMap::new();
+  for (hash, version_pack) in current_versions.iter() {
+    let Some(current_file) = get_modrinth_file_by_sha512(version_pack, hash) else {
+      continue;
+    };
+    let current: OtherResourceFileInfo = (
+      version_pack,
+      current_file,
+      version_pack.loaders.first().cloned(),
+    )
+      .into();
+    // the update endpoint can not filter by version type, drop the ones out of the channel
+    let latest: Option<OtherResourceFileInfo> = latest_versions
+      .get(hash)
+      .filter(|pack| channel.accepts(&pack.version_type))
+      .and_then(|pack| {
+        // the newer version never holds the current file, so take its primary file
+        get_modrinth_primary_file(pack)
+          .map(|file| (pack, file, pack.loaders.first().cloned()).into())
+      });
+    let has_update = latest
+      .as_ref()
+      .is_some_and(|latest| latest.sha1 != current.sha1);
+    matches.insert(
+      hash.clone(),
+      RemoteResourceMatch {
+        source: OtherResourceSource::Modrinth,
+        current,
+        latest,
+        has_update,
+      },
+    );
+  }
+  Ok(matches)
+}
+
+/// Looks up the source code urls of projects, which are not included in the search hits.
+pub async fn fetch_source_urls_modrinth(
+  app: &AppHandle,
+  project_ids: &[&str],
+) -> SJMCLResult<HashMap<String, String>> {
+  let url = get_modrinth_api(ModrinthApiEndpoint::Projects, None)?;
+
+  let mut params = HashMap::new();
+  params.insert(
+    "ids".to_string(),
+    serde_json::to_string(project_ids).unwrap_or_default(),
+  );
+  let projects = make_modrinth_request::<Vec<ModrinthProjectLinks>, ()>(
+    app,
+    &url,
+    ModrinthRequestType::GetWithParams(&params),
+    ModrinthApiEndpoint::Projects.cache_ttl(),
+  )
+  .await?;
+
+  Ok(
+    projects
+      .into_iter()
+      .filter_map(|project| Some((project.id, project.source_url.filter(|u| !u.is_empty())?)))
+      .collect(),
+  )
+}
+
+/// Looks up the projects which contain the given files, keyed by sha1.
+pub async fn fetch_project_ids_by_sha1_modrinth(
+  app: &AppHandle,
+  sha1_hashes: &[&str],
+) -> SJMCLResult<HashMap<String, String>> {
+  let url = get_modrinth_api(ModrinthApiEndpoint::VersionFiles, None)?;
+
+  let payload = json!({
+    "hashes": sha1_hashes,
+    "algorithm": "sha1",
+  });
+  let versions = make_modrinth_request::<HashMap<String, ModrinthVersionPack>, _>(
+    app,
+    &url,
+    ModrinthRequestType::Post(&payload),
+    ModrinthApiEndpoint::VersionFiles.cache_ttl(),
+  )
+  .await?;
+
+  Ok(
+    versions
+      .into_iter()
+      .map(|(hash, version_pack)| (hash, version_pack.project_id))
+      .collect(),
+  )
+}
+
 pub async fn fetch_remote_resource_by_id_modrinth(
   app: &AppHandle,
   resource_id: &str,
 ) -> SJMCLResult<OtherResourceInfo> {
   let url = get_modrinth_api(ModrinthApiEndpoint::Project, Some(resource_id))?;
-  let client = app.state::<reqwest::Client>();
 
-  let results =
-    make_modrinth_request::<ModrinthProject, ()>(&client, &url, ModrinthRequestType::Get).await?;
+  let results = make_modrinth_request::<ModrinthProject, ()>(
+    app,
+    &url,
+    ModrinthRequestType::Get,
+    ModrinthApiEndpoint::Project.cache_ttl(),
+  )
+  .await?;
 
   Ok(results.into())
 }
@@ -0,0 +1,339 @@
+use crate::{
+  error::SJMCLResult,
+  instance::{
+    helpers::misc::get_instance_subdir_paths,
+    models::misc::{Instance, InstanceError, InstanceSubdirType, ModLoaderType, ReleaseChannel},
+  },
+  resource::{
+    constants::RESOURCE_UPDATES_CHECKED_EVENT,
+    helpers::{
+      curseforge::fetch_remote_resources_by_fingerprints_curseforge,
+      file_hash::get_file_hashes_cached, modrinth::fetch_remote_resources_by_hashes_modrinth,
+    },
+    models::{
+      LocalModRemoteInfo, PendingResourceUpdate, ResourceUpdateInfo, ResourceUpdateRecords,
+    },
+  },
+  storage::Storage,
+  tasks::{events::GEventStatus, monitor::TaskMonitor},
+  utils::fs::{get_files_with_regex, validate_sha1},
+};
+use regex::RegexBuilder;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::Duration;
+
+// subdirs scanned by the update checker
+const UPDATE_CHECKED_SUBDIRS: [InstanceSubdirType; 3] = [
+  InstanceSubdirType::Mods,
+  InstanceSubdirType::ResourcePacks,
+  InstanceSubdirType::ShaderPacks,
+];
+
+pub fn get_mod_loader_name(loader_type: &ModLoaderType) -> Option<&'static str> {
+  match loader_type {
+    ModLoaderType::Unknown => None,
+    ModLoaderType::Forge | ModLoaderType::LegacyForge => Some("Forge"),
+    ModLoaderType::Fabric => Some("Fabric"),
+    ModLoaderType::NeoForge => Some("NeoForge"),
+    ModLoaderType::Quilt => Some("Quilt"),
+    ModLoaderType::LiteLoader => Some("LiteLoader"),
+  }
+}
+
+/// Returns the key of a local resource in `Instance::pinned_resources`, e.g. `mods/foo.jar`.
+/// The `.disabled` suffix is ignored, so toggling a mod keeps it pinned.
+pub fn get_pinned_resource_key(file_path: &Path) -> Option<String> {
+  let file_name = file_path.file_name()?.to_string_lossy().to_string();
+  let subdir = file_path
+    .parent()?
+    .file_name()?
+    .to_string_lossy()
+    .to_string();
+  let file_name = file_name.strip_suffix(".disabled").unwrap_or(&file_name);
+  Some(format!("{}/{}", subdir, file_name))
+}
+
+/// Looks up local files on Modrinth and CurseForge by their hashes, keyed by file path.
+/// One failed source does not hide the results of the other.
+pub async fn fetch_remote_resources_by_local_files(
+  app: &AppHandle,
+  paths: &[PathBuf],
+  mod_loader: Option<&str>,
+  game_version: &str,
+  channel: &ReleaseChannel,
+) -> HashMap<PathBuf, LocalModRemoteInfo> {
+  let file_hashes = get_file_hashes_cached(app, paths).await;
+
+  let sha512_hashes: Vec<String> = file_hashes.values().map(|h| h.sha512.clone()).collect();
+  let fingerprints: Vec<u32> = file_hashes.values().map(|h| h.fingerprint).collect();
+  let (modrinth_matches, curseforge_matches) = tokio::join!(
+    fetch_remote_resources_by_hashes_modrinth(
+      app,
+      &sha512_hashes,
+      mod_loader,
+      game_version,
+      channel
+    ),
+    fetch_remote_resources_by_fingerprints_curseforge(
+      app,
+      &fingerprints,
+      mod_loader,
+      game_version,
+      channel
+    ),
+  );
+  let mut modrinth_matches = modrinth_matches.unwrap_or_else(|e| {
+    log::error!("Failed to look up local resources on Modrinth: {}", e.0);
+    HashMap::new()
+  });
+  let mut curseforge_matches = curseforge_matches.unwrap_or_else(|e| {
+    log::error!("Failed to look up local resources on CurseForge: {}", e.0);
+    HashMap::new()
+  });
+
+  file_hashes
+    .into_iter()
+    .map(|(path, hashes)| {
+      let info = LocalModRemoteInfo {
+        file_path: path.clone(),
+        sha1: hashes.sha1,
+        modrinth: modrinth_matches.remove(&hashes.sha512),
+        curseforge: curseforge_matches.remove(&hashes.fingerprint),
+      };
+      (path, info)
+    })
+    .collect()
+}
+
+/// Finds the outdated mods, resource packs and shader packs of an instance,
+/// skipping the pinned ones and the versions out of its release channel.
+pub async fn check_instance_resource_updates(
+  app: &AppHandle,
+  instance: &Instance,
+) -> SJMCLResult<Vec<ResourceUpdateInfo>> {
+  let dirs = get_instance_subdir_paths(
+    app,
+    instance,
+    &UPDATE_CHECKED_SUBDIRS.iter().collect::<Vec<_>>(),
+  )
+  .ok_or(InstanceError::InstanceNotFoundByID)?;
+
+  let mut updates = Vec::new();
+  for (subdir_type, dir) in UPDATE_CHECKED_SUBDIRS.into_iter().zip(dirs) {
+    // resource packs and shaders do not depend on the mod loader
+    let (pattern, mod_loader) = match subdir_type {
+      InstanceSubdirType::Mods => (
+        r"\.jar(\.disabled)?$",
+        get_mod_loader_name(&instance.mod_loader.loader_type),
+      ),
+      _ => (r"\.zip$", None),
+    };
+    let valid_extensions = RegexBuilder::new(pattern)
+      .case_insensitive(true)
+      .build()
+      .unwrap();
+    let paths: Vec<PathBuf> = get_files_with_regex(&dir, &valid_extensions)
+      .unwrap_or_default()
+      .into_iter()
+      .filter(|path| {
+        get_pinned_resource_key(path).map_or(true, |key| !instance.pinned_resources.contains(&key))
+      })
+      .collect();
+    if paths.is_empty() {
+      continue;
+    }
+
+    let remote_infos = fetch_remote_resources_by_local_files(
+      app,
+      &paths,
+      mod_loader,
+      &instance.version,
+      &instance.update_channel,
+    )
+    .await;
+    for (file_path, info) in remote_infos {
+      // prefer Modrinth if both sources have an update
+      let Some(remote) = [info.modrinth, info.curseforge]
+        .into_iter()
+        .flatten()
+        .find(|remote| remote.has_update)
+      else {
+        continue;
+      };
+      let (Some(latest), Some(file_name)) = (
+        remote.latest,
+        file_path
+          .file_name()
+          .map(|n| n.to_string_lossy().to_string()),
+      ) else {
+        continue;
+      };
+      updates.push(ResourceUpdateInfo {
+        subdir_type: subdir_type.clone(),
+        file_name,
+        file_path,
+        source: remote.source,
+        current: remote.current,
+        latest,
+      });
+    }
+  }
+
+  updates.sort_by(|a, b| a.file_name.to_lowercase().cmp(&b.file_name.to_lowercase()));
+  Ok(updates)
+}
+
+/// Checks the given instance (or all instances if `None`), stores the results and emits
+/// the number of available updates of each checked instance to the frontend.
+pub async fn check_and_store_resource_updates(
+  app: &AppHandle,
+  instance_id: Option<&str>,
+) -> SJMCLResult<()> {
+  let instances: Vec<Instance> = {
+    let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+    let state = binding.lock()?;
+    state
+      .values()
+      .filter(|instance| instance_id.map_or(true, |id| id == instance.id))
+      .cloned()
+      .collect()
+  };
+
+  let mut results = HashMap::new();
+  for instance in instances {
+    match check_instance_resource_updates(app, &instance).await {
+      Ok(updates) => {
+        results.insert(instance.id.clone(), updates);
+      }
+      Err(e) => log::error!(
+        "Failed to check resource updates of {}: {}",
+        instance.id,
+        e.0
+      ),
+    }
+  }
+
+  let counts: HashMap<String, usize> = results
+    .iter()
+    .map(|(id, updates)| (id.clone(), updates.len()))
+    .collect();
+  {
+    let instance_ids: Vec<String> = {
+      let binding = app.state::<Mutex<HashMap<String, Instance>>>();
+      let state = binding.lock()?;
+      state.keys().cloned().collect()
+    };
+    let binding = app.state::<Mutex<ResourceUpdateRecords>>();
+    let mut records = binding.lock()?;
+    // drop the records of removed instances, keep the previous ones of failed checks
+    records.instances.retain(|id, _| instance_ids.contains(id));
+    records.instances.extend(results);
+    records.checked_at = chrono::Utc::now().timestamp();
+    records.save()?;
+  }
+
+  app.emit(RESOURCE_UPDATES_CHECKED_EVENT, counts)?;
+  Ok(())
+}
+
+/// Waits for the update downloads of the task group to end, then applies each update whose new
+/// file was downloaded completely: the replaced file is kept as `<file name>.old` and the update
+/// record is cleared. A failed download keeps both the old file and its record.
+pub async fn finish_resource_updates_after_download(app: AppHandle, task_group: String) {
+  loop {
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let is_running = app
+      .state::<Pin<Box<TaskMonitor>>>()
+      .state_list()
+      .iter()
+      .any(|group| {
+        group.task_group == task_group
+          && matches!(group.status, GEventStatus::Started | GEventStatus::Stopped)
+      });
+    if !is_running {
+      break;
+    }
+  }
+
+  let binding = app.state::<Mutex<ResourceUpdateRecords>>();
+  let pending: Vec<PendingResourceUpdate> = {
+    let records = binding.lock().unwrap();
+    records
+      .pending_updates
+      .iter()
+      .filter(|update| update.task_group == task_group)
+      .cloned()
+      .collect()
+  };
+
+  let mut finished = Vec::new();
+  for update in &pending {
+    let is_downloaded = update.new_path.is_file()
+      && update.sha1.clone().map_or(true, |sha1| {
+        validate_sha1(update.new_path.clone(), sha1).is_ok()
+      });
+    if !is_downloaded {
+      log::error!(
+        "Update of {} was not downloaded, keep the old file",
+        update.old_path.display()
+      );
+      continue;
+    }
+    if update.old_path != update.new_path {
+      let backup_path = format!("{}.old", update.old_path.to_string_lossy());
+      if let Err(e) = std::fs::rename(&update.old_path, &backup_path) {
+        log::error!("Failed to rename old resource file: {}", e);
+      }
+    }
+    finished.push(update);
+  }
+
+  let counts: HashMap<String, usize> = {
+    let mut records = binding.lock().unwrap();
+    records
+      .pending_updates
+      .retain(|update| update.task_group != task_group);
+    for update in &finished {
+      if let Some(updates) = records.instances.get_mut(&update.instance_id) {
+        updates.retain(|info| info.file_path != update.old_path);
+      }
+    }
+    if let Err(e) = records.save() {
+      log::error!("Failed to save resource update records: {}", e);
+    }
+    pending
+      .iter()
+      .map(|update| {
+        let count = records
+          .instances
+          .get(&update.instance_id)
+          .map_or(0, |updates| updates.len());
+        (update.instance_id.clone(), count)
+      })
+      .collect()
+  };
+  let _ = app.emit(RESOURCE_UPDATES_CHECKED_EVENT, counts);
+}
+
+/// Finishes the updates left by the last run, once their download tasks have been restored.
+pub fn resume_pending_resource_updates(app: &AppHandle) {
+  let task_groups: HashSet<String> = {
+    let binding = app.state::<Mutex<ResourceUpdateRecords>>();
+    let records = binding.lock().unwrap();
+    records
+      .pending_updates
+      .iter()
+      .map(|update| update.task_group.clone())
+      .collect()
+  };
+  for task_group in task_groups {
+    tauri::async_runtime::spawn(finish_resource_updates_after_download(
+      app.clone(),
+      task_group,
+    ));
+  }
+}
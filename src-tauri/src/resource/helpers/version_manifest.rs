@@ -5,6 +5,7 @@ use std::fs;
 use tauri::{AppHandle, Manager};
 use tauri_plugin_http::reqwest;
 
+use super::http_cache::{send_with_cache, GAME_VERSION_MANIFEST_CACHE_TTL};
 use super::misc::get_download_api;
 
 #[derive(Serialize, Deserialize, Default)]
@@ -38,12 +39,12 @@ pub async fn get_game_version_manifest(
 
   for source_type in priority_list.iter() {
     let url = get_download_api(*source_type, ResourceType::VersionManifest)?;
-    let response = match client.get(url).send().await {
-      Ok(resp) if resp.status().is_success() => resp,
-      _ => continue,
+    let body = match send_with_cache(app, client.get(url), GAME_VERSION_MANIFEST_CACHE_TTL).await {
+      Ok(body) => body,
+      Err(_) => continue,
     };
 
-    let manifest = match response.json::<VersionManifest>().await {
+    let manifest = match serde_json::from_str::<VersionManifest>(&body) {
       Ok(m) => m,
       Err(_) => return Err(ResourceError::ParseError.into()),
     };
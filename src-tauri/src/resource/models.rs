@@ -1,6 +1,11 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::str::FromStr;
 
-use crate::instance::models::misc::ModLoaderType;
+use crate::instance::models::misc::{InstanceSubdirType, ModLoaderType};
+use crate::resource::constants::RESOURCE_UPDATES_FILE_NAME;
+use crate::storage::Storage;
+use crate::APP_DATA_DIR;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter};
 
@@ -70,6 +75,17 @@ pub struct OtherResourceInfo {
   pub downloads: u32,
   pub source: OtherResourceSource,
   pub website_url: String,
+  pub slug: String,
+  // the same project on other platforms, only filled by the merged search
+  pub alt_sources: Vec<OtherResourceAltSource>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OtherResourceAltSource {
+  pub source: OtherResourceSource,
+  pub resource_id: String,
+  pub website_url: String,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
@@ -139,6 +155,63 @@ pub struct ModUpdateQuery {
   pub old_file_path: String,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RemoteResourceMatch {
+  pub source: OtherResourceSource,
+  pub current: OtherResourceFileInfo,
+  // newest version compatible with the mod loader and game version of the instance
+  pub latest: Option<OtherResourceFileInfo>,
+  pub has_update: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LocalModRemoteInfo {
+  pub file_path: PathBuf,
+  pub sha1: String,
+  pub modrinth: Option<RemoteResourceMatch>,
+  pub curseforge: Option<RemoteResourceMatch>,
+}
+
+// an outdated local resource found by the update checker
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ResourceUpdateInfo {
+  pub subdir_type: InstanceSubdirType,
+  pub file_name: String,
+  pub file_path: PathBuf,
+  pub source: OtherResourceSource,
+  pub current: OtherResourceFileInfo,
+  pub latest: OtherResourceFileInfo,
+}
+
+// a local file being updated, kept until the download of its new file ends
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PendingResourceUpdate {
+  pub instance_id: String,
+  pub task_group: String,
+  pub old_path: PathBuf,
+  pub new_path: PathBuf,
+  pub sha1: Option<String>,
+}
+
+// results of the last update check, keyed by instance id
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct ResourceUpdateRecords {
+  pub checked_at: i64,
+  pub instances: HashMap<String, Vec<ResourceUpdateInfo>>,
+  pub pending_updates: Vec<PendingResourceUpdate>,
+}
+
+impl Storage for ResourceUpdateRecords {
+  fn file_path() -> PathBuf {
+    APP_DATA_DIR.get().unwrap().join(RESOURCE_UPDATES_FILE_NAME)
+  }
+}
+
 // game client itself
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
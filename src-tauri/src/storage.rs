@@ -1,5 +1,24 @@
+use chrono::Utc;
+use lazy_static::lazy_static;
 use serde::{de::DeserializeOwned, Serialize};
-use std::{fs, path::Path};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+lazy_static! {
+  // files which could not be loaded, the defaults used in their place must never be saved over them
+  static ref READ_ONLY_FILES: Mutex<HashSet<PathBuf>> = Mutex::new(HashSet::new());
+}
+
+fn is_read_only(path: &Path) -> bool {
+  let read_only = READ_ONLY_FILES.lock().unwrap().contains(path);
+  if read_only {
+    log::warn!("{} could not be loaded, not overwriting it", path.display());
+  }
+  read_only
+}
 
 pub trait Storage {
   fn file_path() -> std::path::PathBuf;
@@ -17,6 +36,9 @@ pub trait Storage {
   where
     Self: Serialize,
   {
+    if is_read_only(&Self::file_path()) {
+      return Ok(());
+    }
     if let Some(parent) = Self::file_path().parent() {
       fs::create_dir_all(parent)?;
     }
@@ -24,6 +46,13 @@ pub trait Storage {
     fs::write(Self::file_path(), json_string)?;
     Ok(())
   }
+
+  fn load_with_migration() -> Result<Self, std::io::Error>
+  where
+    Self: Sized + SchemaMigration + Serialize + DeserializeOwned + Default,
+  {
+    load_json_with_migration(&Self::file_path())
+  }
 }
 
 pub async fn load_json_async<T>(file_path: &Path) -> Result<T, std::io::Error>
@@ -39,6 +68,9 @@ pub async fn save_json_async<T>(value: &T, file_path: &Path) -> Result<(), std::
 where
   T: Serialize + Send,
 {
+  if is_read_only(file_path) {
+    return Ok(());
+  }
   if let Some(parent) = file_path.parent() {
     fs::create_dir_all(parent)?;
   }
@@ -46,3 +78,161 @@ where
   tokio::fs::write(file_path, json_string).await?;
   Ok(())
 }
+
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+pub type MigrationStep = fn(&mut Value);
+
+/// A persisted file with a schema version. The json of an older file is upgraded step by step
+/// before deserialization, files without a version are of version 0.
+pub trait SchemaMigration {
+  const SCHEMA_VERSION: u32;
+  /// `MIGRATIONS[i]` upgrades the json of version `i` to `i + 1`.
+  const MIGRATIONS: &'static [MigrationStep];
+}
+
+/// The first migration of every file, which only introduces the schema version.
+pub fn introduce_schema_version(_: &mut Value) {}
+
+fn backup_file(path: &Path, suffix: &str) -> Result<PathBuf, std::io::Error> {
+  let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+  file_name.push(format!(".{}.bak", suffix));
+  let backup_path = path.with_file_name(file_name);
+  fs::copy(path, &backup_path)?;
+  Ok(backup_path)
+}
+
+/// Backs up a file which can not be loaded and marks it read-only, so it is left as is for the
+/// user (or a newer launcher) even after the default value is saved in its place.
+fn keep_unloadable_file(path: &Path, suffix: &str) {
+  if let Err(e) = backup_file(path, suffix) {
+    log::error!("Failed to back up {}: {}", path.display(), e);
+  }
+  READ_ONLY_FILES.lock().unwrap().insert(path.to_path_buf());
+}
+
+/// Copies the valid fields of `incoming` onto `recovered` (the default value), field by field,
+/// so a single invalid field only resets itself instead of the whole file.
+fn recover_fields<F>(recovered: &mut Value, pointer: &str, incoming: &Value, is_valid: &F)
+where
+  F: Fn(&Value) -> bool,
+{
+  let Value::Object(map) = incoming else {
+    return;
+  };
+  for (key, field) in map {
+    let field_pointer = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+    // unknown to this version, e.g. written by a newer launcher
+    let Some(target) = recovered.pointer_mut(&field_pointer) else {
+      continue;
+    };
+    let default = std::mem::replace(target, field.clone());
+    if is_valid(recovered) {
+      continue;
+    }
+    if let Some(target) = recovered.pointer_mut(&field_pointer) {
+      *target = default;
+      if target.is_object() {
+        recover_fields(recovered, &field_pointer, field, is_valid);
+      }
+    }
+  }
+}
+
+/// Upgrades and deserializes the content of a versioned file. A backup of the original file is kept
+/// before it is migrated or recovered. Returns whether the file should be written back.
+///
+/// Files which are not valid json, or written by a newer launcher, are backed up, reported as
+/// `InvalidData` and never overwritten by `save`, so the user (or the newer launcher) can still use them.
+fn parse_with_migration<T>(path: &Path, json_string: &str) -> Result<(T, bool), std::io::Error>
+where
+  T: SchemaMigration + Serialize + DeserializeOwned + Default,
+{
+  debug_assert_eq!(T::MIGRATIONS.len(), T::SCHEMA_VERSION as usize);
+
+  let mut value: Value = match serde_json::from_str(json_string) {
+    Ok(value) => value,
+    Err(e) => {
+      log::error!("Failed to parse {}, left as is: {}", path.display(), e);
+      keep_unloadable_file(path, "invalid");
+      // also for a truncated file, which serde_json reports as `UnexpectedEof`
+      return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e));
+    }
+  };
+
+  let version = value
+    .get(SCHEMA_VERSION_KEY)
+    .and_then(Value::as_u64)
+    .unwrap_or(0) as u32;
+  if version > T::SCHEMA_VERSION {
+    log::error!(
+      "{} is of schema version {}, newer than {}, left as is",
+      path.display(),
+      version,
+      T::SCHEMA_VERSION
+    );
+    keep_unloadable_file(path, &format!("v{}", version));
+    return Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidData,
+      "written by a newer launcher",
+    ));
+  }
+
+  // loaded again after the user fixed it
+  READ_ONLY_FILES.lock().unwrap().remove(path);
+
+  let mut changed = false;
+  if version < T::SCHEMA_VERSION {
+    backup_file(path, &format!("v{}", version))?;
+    for migrate in T::MIGRATIONS.iter().skip(version as usize) {
+      migrate(&mut value);
+    }
+    if let Value::Object(map) = &mut value {
+      map.insert(SCHEMA_VERSION_KEY.to_string(), T::SCHEMA_VERSION.into());
+    }
+    changed = true;
+  }
+
+  match serde_json::from_value::<T>(value.clone()) {
+    Ok(parsed) => Ok((parsed, changed)),
+    Err(e) => {
+      let backup_path = backup_file(path, &format!("corrupted-{}", Utc::now().timestamp()))?;
+      log::error!(
+        "Failed to deserialize {}, recovering the valid fields (backed up to {}): {}",
+        path.display(),
+        backup_path.display(),
+        e
+      );
+      let mut recovered = serde_json::to_value(T::default())?;
+      recover_fields(&mut recovered, "", &value, &|v: &Value| {
+        serde_json::from_value::<T>(v.clone()).is_ok()
+      });
+      Ok((serde_json::from_value(recovered)?, true))
+    }
+  }
+}
+
+/// Loads a versioned json file, see `parse_with_migration`.
+pub fn load_json_with_migration<T>(path: &Path) -> Result<T, std::io::Error>
+where
+  T: SchemaMigration + Serialize + DeserializeOwned + Default,
+{
+  let json_string = fs::read_to_string(path)?;
+  let (value, changed) = parse_with_migration::<T>(path, &json_string)?;
+  if changed {
+    fs::write(path, serde_json::to_string_pretty(&value)?)?;
+  }
+  Ok(value)
+}
+
+pub async fn load_json_with_migration_async<T>(path: &Path) -> Result<T, std::io::Error>
+where
+  T: SchemaMigration + Serialize + DeserializeOwned + Default + Send,
+{
+  let json_string = tokio::fs::read_to_string(path).await?;
+  let (value, changed) = parse_with_migration::<T>(path, &json_string)?;
+  if changed {
+    save_json_async(&value, path).await?;
+  }
+  Ok(value)
+}
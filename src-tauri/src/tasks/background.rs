@@ -1,9 +1,160 @@
 use super::monitor::TaskMonitor;
+use crate::account::helpers::{authlib_injector, maintenance, microsoft};
+use crate::account::models::{AccountError, AccountInfo, PlayerAuthState, PlayerInfo, PlayerType};
+use crate::launcher_config::models::LauncherConfig;
+use crate::resource::helpers::update_check::{
+  check_and_store_resource_updates, resume_pending_resource_updates,
+};
+use crate::resource::models::ResourceUpdateRecords;
+use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
+use tokio::time::{Duration, Instant};
+
+// give the instance list a moment to be refreshed before the first check
+const RESOURCE_UPDATE_CHECK_DELAY: Duration = Duration::from_secs(60);
+const RESOURCE_UPDATE_POLL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+const ACCOUNT_CHECK_DELAY: Duration = Duration::from_secs(30);
+const ACCOUNT_POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+// refresh the tokens this long before they expire, Minecraft access tokens last for 24 hours
+const TOKEN_REFRESH_AHEAD_SECS: i64 = 2 * 3600;
+// tokens which are not JWTs have no known expiry, validate them online instead
+const OPAQUE_TOKEN_VALIDATE_INTERVAL: Duration = Duration::from_secs(3600);
+const ACCOUNT_RETRY_MIN_DELAY: Duration = Duration::from_secs(60);
+const ACCOUNT_RETRY_MAX_DELAY: Duration = Duration::from_secs(3600);
 
 pub async fn monitor_background_process(app: AppHandle) {
   let monitor = app.state::<Pin<Box<TaskMonitor>>>();
   monitor.load_saved_tasks().await;
+  // the downloads of unfinished resource updates are restored now, wait for them again
+  resume_pending_resource_updates(&app);
   monitor.background_process().await;
 }
+
+/// Checks resource updates of all instances once at startup, then whenever the
+/// configured interval has passed since the last check.
+pub async fn check_resource_updates_periodically(app: AppHandle) {
+  tokio::time::sleep(RESOURCE_UPDATE_CHECK_DELAY).await;
+  let mut is_startup = true;
+  loop {
+    let (enabled, interval_secs) = {
+      let binding = app.state::<Mutex<LauncherConfig>>();
+      let config = binding.lock().unwrap();
+      let functionality = &config.general.functionality;
+      (
+        functionality.auto_check_resource_updates,
+        functionality.resource_update_check_interval.max(1) as i64 * 3600,
+      )
+    };
+    let checked_at = {
+      let binding = app.state::<Mutex<ResourceUpdateRecords>>();
+      let records = binding.lock().unwrap();
+      records.checked_at
+    };
+    let is_due = chrono::Utc::now().timestamp() - checked_at >= interval_secs;
+
+    if enabled && (is_startup || is_due) {
+      if let Err(e) = check_and_store_resource_updates(&app, None).await {
+        log::error!("Failed to check resource updates: {}", e.0);
+      }
+    }
+    is_startup = false;
+    tokio::time::sleep(RESOURCE_UPDATE_POLL_INTERVAL).await;
+  }
+}
+
+/// Whether the player's token should be refreshed now, `None` if it could not be checked.
+async fn is_player_token_due(app: &AppHandle, player: &PlayerInfo) -> Option<bool> {
+  let access_token = player.access_token.clone().unwrap_or_default();
+  if let Some(expiration) = maintenance::get_token_expiration(&access_token) {
+    return Some(expiration - chrono::Utc::now().timestamp() < TOKEN_REFRESH_AHEAD_SECS);
+  }
+  let is_valid = match player.player_type {
+    PlayerType::Microsoft => microsoft::oauth::validate(app, player).await,
+    PlayerType::ThirdParty => authlib_injector::common::validate(app, player).await,
+    PlayerType::Offline => return Some(false),
+  };
+  is_valid.ok().map(|is_valid| !is_valid)
+}
+
+/// Refreshes the tokens of online players before they expire, so that launching does not
+/// stop on an expired token. Failed refreshes are retried with an exponential backoff.
+pub async fn maintain_accounts_periodically(app: AppHandle) {
+  tokio::time::sleep(ACCOUNT_CHECK_DELAY).await;
+  let mut next_checks = HashMap::<String, Instant>::new();
+  let mut retry_delays = HashMap::<String, Duration>::new();
+  loop {
+    let players: Vec<PlayerInfo> = {
+      let account_binding = app.state::<Mutex<AccountInfo>>();
+      let account_state = account_binding.lock().unwrap();
+      account_state
+        .players
+        .iter()
+        .filter(|player| player.player_type != PlayerType::Offline)
+        .filter(|player| player.auth_state != PlayerAuthState::Revoked)
+        .cloned()
+        .collect()
+    };
+
+    for player in players {
+      if next_checks
+        .get(&player.id)
+        .is_some_and(|next_check| Instant::now() < *next_check)
+      {
+        continue;
+      }
+
+      let result = match is_player_token_due(&app, &player).await {
+        Some(false) => {
+          // opaque tokens are validated online, so they are not checked at every poll
+          let access_token = player.access_token.clone().unwrap_or_default();
+          if maintenance::get_token_expiration(&access_token).is_none() {
+            next_checks.insert(
+              player.id.clone(),
+              Instant::now() + OPAQUE_TOKEN_VALIDATE_INTERVAL,
+            );
+          }
+          retry_delays.remove(&player.id);
+          continue;
+        }
+        Some(true) => maintenance::refresh_and_save_player(&app, &player.id)
+          .await
+          .map(|_| ()),
+        None => Err(AccountError::NetworkError.into()),
+      };
+
+      match result {
+        Ok(()) => {
+          next_checks.remove(&player.id);
+          retry_delays.remove(&player.id);
+        }
+        // marked as revoked by the refresh, skipped until the user logs in again
+        Err(e) if maintenance::is_revoked_error(&e) => {}
+        Err(e) => {
+          log::error!("failed to refresh player {}: {}", player.id, e.0);
+          let retry_delay = retry_delays
+            .get(&player.id)
+            .map_or(ACCOUNT_RETRY_MIN_DELAY, |delay| {
+              (*delay * 2).min(ACCOUNT_RETRY_MAX_DELAY)
+            });
+          retry_delays.insert(player.id.clone(), retry_delay);
+          next_checks.insert(player.id.clone(), Instant::now() + retry_delay);
+        }
+      }
+    }
+
+    // wake up earlier if a retry is due before the next poll
+    let now = Instant::now();
+    let sleep_duration = next_checks
+      .values()
+      .filter(|next_check| **next_check > now)
+      .map(|next_check| *next_check - now)
+      .min()
+      .map_or(ACCOUNT_POLL_INTERVAL, |duration| {
+        duration.min(ACCOUNT_POLL_INTERVAL)
+      });
+    tokio::time::sleep(sleep_duration).await;
+  }
+}
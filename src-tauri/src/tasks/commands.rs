@@ -3,7 +3,10 @@ use tauri::{AppHandle, Manager};
 
 use crate::{
   error::SJMCLResult,
-  tasks::{download::DownloadTask, events::GEventStatus, monitor::TaskMonitor},
+  tasks::{
+    download::DownloadTask, events::GEventStatus, instance_duplicate::InstanceDuplicateTask,
+    monitor::TaskMonitor, world_backup::WorldBackupTask,
+  },
   utils::fs::extract_filename,
 };
 
@@ -57,6 +60,43 @@ pub async fn schedule_progressive_task_group(
         task_descs.push(task_desc);
         future_descs.push(future_desc);
       }
+      PTaskParam::WorldBackup(mut param) => {
+        if param.filename.is_none() {
+          param.filename = Some(extract_filename(
+            param.dest.to_str().unwrap_or_default(),
+            true,
+          ));
+        }
+        let task = WorldBackupTask::new(app.clone(), task_id, Some(task_group.clone()), param);
+        let (f, h) = task.future(app.clone()).await?;
+        let task_desc = h.read().unwrap().desc.clone();
+        let future_desc = SJMCLFutureDesc {
+          task_id,
+          f: Box::pin(f),
+          h: h.clone(),
+        };
+        task_descs.push(task_desc);
+        future_descs.push(future_desc);
+      }
+      PTaskParam::InstanceDuplicate(mut param) => {
+        if param.filename.is_none() {
+          param.filename = Some(extract_filename(
+            param.dest.to_str().unwrap_or_default(),
+            true,
+          ));
+        }
+        let task =
+          InstanceDuplicateTask::new(app.clone(), task_id, Some(task_group.clone()), param);
+        let (f, h) = task.future(app.clone()).await?;
+        let task_desc = h.read().unwrap().desc.clone();
+        let future_desc = SJMCLFutureDesc {
+          task_id,
+          f: Box::pin(f),
+          h: h.clone(),
+        };
+        task_descs.push(task_desc);
+        future_descs.push(future_desc);
+      }
     }
   }
   monitor
@@ -81,8 +81,8 @@ impl DownloadTask {
     report_interval: Duration,
     reset: bool,
   ) -> Self {
-    let param = match &desc.payload {
-      PTaskParam::Download(param) => param.clone(),
+    let PTaskParam::Download(param) = desc.payload.clone() else {
+      unreachable!("descriptor is not a download task");
     };
 
     let cache_dir = retrieve_launcher_config(app_handle.clone())
@@ -0,0 +1,131 @@
+use crate::error::{SJMCLError, SJMCLResult};
+use crate::instance::helpers::duplicate::{
+  duplicate_version_dir, get_duplicate_size, write_duplicated_instance_files,
+};
+use crate::instance::helpers::misc::refresh_and_update_instances;
+use crate::launcher_config::commands::retrieve_launcher_config;
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::streams::desc::{PDesc, PStatus};
+use super::streams::reporter::Reporter;
+use super::streams::ProgressStream;
+use super::*;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceDuplicateParam {
+  pub src: PathBuf,  // version directory of the source instance
+  pub dest: PathBuf, // version directory of the new instance
+  pub excluded: Vec<String>,
+  pub filename: Option<String>,
+}
+
+pub struct InstanceDuplicateTask {
+  p_handle: PTaskHandle,
+  param: InstanceDuplicateParam,
+}
+
+impl InstanceDuplicateTask {
+  pub fn new(
+    app_handle: AppHandle,
+    task_id: u32,
+    task_group: Option<String>,
+    param: InstanceDuplicateParam,
+  ) -> Self {
+    let cache_dir = retrieve_launcher_config(app_handle.clone())
+      .unwrap()
+      .download
+      .cache
+      .directory;
+    InstanceDuplicateTask {
+      p_handle: PTaskHandle::new(
+        PDesc::<PTaskParam>::new(
+          task_id,
+          task_group.clone(),
+          0,
+          PTaskParam::InstanceDuplicate(param.clone()),
+          PStatus::InProgress,
+        ),
+        Duration::from_secs(1),
+        cache_dir.join(format!("task-{task_id}.json")),
+        Reporter::new(
+          0,
+          Duration::from_secs(1),
+          TauriEventSink::new(app_handle.clone()),
+        ),
+      ),
+      param,
+    }
+  }
+
+  // copied files are simply overwritten, so restart the task from the beginning.
+  pub fn from_descriptor(app_handle: AppHandle, desc: PTaskDesc) -> Self {
+    let PTaskParam::InstanceDuplicate(param) = desc.payload.clone() else {
+      unreachable!("descriptor is not an instance duplicate task");
+    };
+    Self::new(app_handle, desc.task_id, desc.task_group, param)
+  }
+
+  pub async fn future(
+    self,
+    app_handle: AppHandle,
+  ) -> SJMCLResult<(
+    impl Future<Output = SJMCLResult<()>> + Send,
+    Arc<RwLock<PTaskHandle>>,
+  )> {
+    let handle = Arc::new(RwLock::new(self.p_handle));
+    let task_handle = handle.clone();
+    let param = self.param;
+    Ok((
+      async move {
+        let total = get_duplicate_size(&param.src, &param.excluded)? as i64;
+        // bounded channel, so the copy blocks while the task is stopped.
+        let (tx, rx) = flume::bounded::<usize>(16);
+        let (src, dest, excluded) = (
+          param.src.clone(),
+          param.dest.clone(),
+          param.excluded.clone(),
+        );
+        let worker = tokio::task::spawn_blocking(move || {
+          duplicate_version_dir(&src, &dest, &excluded, |n| tx.send(n).is_ok())
+        });
+
+        {
+          let mut task_handle = task_handle.write().unwrap();
+          task_handle.set_total(total);
+          task_handle.mark_started();
+        }
+        ProgressStream::new(rx.into_stream(), task_handle.clone())
+          .for_each(|_| async {})
+          .await;
+        let result = worker
+          .await
+          .map_err(|e| SJMCLError(format!("Instance duplicate worker failed: {}", e)))?;
+
+        if task_handle.read().unwrap().status().is_cancelled() {
+          let _ = tokio::fs::remove_dir_all(&param.dest).await;
+          return Ok(());
+        }
+        let result = match result {
+          Ok(()) => write_duplicated_instance_files(&param.src, &param.dest).await,
+          Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+          // remove the partial copy, otherwise retrying with the same name always conflicts
+          let _ = tokio::fs::remove_dir_all(&param.dest).await;
+          return Err(e);
+        }
+        refresh_and_update_instances(&app_handle, false).await;
+        Ok(())
+      },
+      handle,
+    ))
+  }
+}
@@ -2,19 +2,23 @@ pub mod background;
 pub mod commands;
 pub mod download;
 pub mod events;
+pub mod instance_duplicate;
 pub mod monitor;
 pub mod streams;
+pub mod world_backup;
 
 use crate::error::SJMCLResult;
 use download::DownloadParam;
 use events::TauriEventSink;
 use futures::stream::Stream;
+use instance_duplicate::InstanceDuplicateParam;
 use serde::{Deserialize, Serialize};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::{Arc, RwLock};
 use streams::{GDesc, PDesc, PHandle};
 use tokio::time::Duration;
+use world_backup::WorldBackupParam;
 
 pub type SJMCLBoxedFuture = Pin<Box<dyn Future<Output = SJMCLResult<()>> + Send>>;
 
@@ -47,4 +51,6 @@ pub struct THandle {
 #[serde(tag = "taskType", rename_all = "camelCase")]
 pub enum PTaskParam {
   Download(DownloadParam),
+  WorldBackup(WorldBackupParam),
+  InstanceDuplicate(InstanceDuplicateParam),
 }
@@ -7,6 +7,7 @@ use async_speed_limit::Limiter;
 use download::DownloadTask;
 use flume::{Receiver as FlumeReceiver, Sender as FlumeSender};
 use glob::glob;
+use instance_duplicate::InstanceDuplicateTask;
 use log::info;
 use std::collections::HashMap;
 use std::future::Future;
@@ -16,6 +17,7 @@ use std::vec::Vec;
 use tauri::async_runtime::JoinHandle;
 use tauri::AppHandle;
 use tokio::sync::Semaphore;
+use world_backup::WorldBackupTask;
 
 use super::events::{GEvent, TEvent};
 use super::SJMCLFuture;
@@ -104,6 +106,16 @@ impl TaskMonitor {
                   .unwrap();
                 self.enqueue_task(task_id, task_group, f, p_handle).await;
               }
+              PTaskParam::WorldBackup(_) => {
+                let task = WorldBackupTask::from_descriptor(self.app_handle.clone(), desc);
+                let (f, p_handle) = task.future(self.app_handle.clone()).await.unwrap();
+                self.enqueue_task(task_id, task_group, f, p_handle).await;
+              }
+              PTaskParam::InstanceDuplicate(_) => {
+                let task = InstanceDuplicateTask::from_descriptor(self.app_handle.clone(), desc);
+                let (f, p_handle) = task.future(self.app_handle.clone()).await.unwrap();
+                self.enqueue_task(task_id, task_group, f, p_handle).await;
+              }
             }
           }
           Err(_) => {
@@ -380,6 +392,16 @@ impl TaskMonitor {
             .unwrap();
           self.enqueue_task(id, task_group, f, new_h).await;
         }
+        PTaskParam::WorldBackup(_) => {
+          let task = WorldBackupTask::from_descriptor(self.app_handle.clone(), desc);
+          let (f, new_h) = task.future(self.app_handle.clone()).await.unwrap();
+          self.enqueue_task(id, task_group, f, new_h).await;
+        }
+        PTaskParam::InstanceDuplicate(_) => {
+          let task = InstanceDuplicateTask::from_descriptor(self.app_handle.clone(), desc);
+          let (f, new_h) = task.future(self.app_handle.clone()).await.unwrap();
+          self.enqueue_task(id, task_group, f, new_h).await;
+        }
       }
     }
   }
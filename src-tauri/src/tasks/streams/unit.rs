@@ -10,6 +10,12 @@ impl<T> Unit for &T {
   }
 }
 
+impl Unit for usize {
+  fn unit_size(&self) -> i64 {
+    *self as i64
+  }
+}
+
 impl Unit for Bytes {
   fn unit_size(&self) -> i64 {
     self.len() as i64
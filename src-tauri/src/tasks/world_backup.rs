@@ -0,0 +1,139 @@
+use crate::error::{SJMCLError, SJMCLResult};
+use crate::instance::helpers::misc::get_instance_game_config;
+use crate::instance::helpers::world_backup::{apply_backup_retention, create_world_snapshot};
+use crate::instance::models::misc::Instance;
+use crate::launcher_config::commands::retrieve_launcher_config;
+use crate::utils::fs::get_dir_size;
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::streams::desc::{PDesc, PStatus};
+use super::streams::reporter::Reporter;
+use super::streams::ProgressStream;
+use super::*;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldBackupParam {
+  pub instance_id: String,
+  pub src: PathBuf,  // world directory
+  pub dest: PathBuf, // snapshot zip file
+  pub filename: Option<String>,
+}
+
+pub struct WorldBackupTask {
+  p_handle: PTaskHandle,
+  param: WorldBackupParam,
+}
+
+impl WorldBackupTask {
+  pub fn new(
+    app_handle: AppHandle,
+    task_id: u32,
+    task_group: Option<String>,
+    param: WorldBackupParam,
+  ) -> Self {
+    let cache_dir = retrieve_launcher_config(app_handle.clone())
+      .unwrap()
+      .download
+      .cache
+      .directory;
+    WorldBackupTask {
+      p_handle: PTaskHandle::new(
+        PDesc::<PTaskParam>::new(
+          task_id,
+          task_group.clone(),
+          0,
+          PTaskParam::WorldBackup(param.clone()),
+          PStatus::InProgress,
+        ),
+        Duration::from_secs(1),
+        cache_dir.join(format!("task-{task_id}.json")),
+        Reporter::new(
+          0,
+          Duration::from_secs(1),
+          TauriEventSink::new(app_handle.clone()),
+        ),
+      ),
+      param,
+    }
+  }
+
+  // A snapshot can not be resumed halfway, always restart it from the beginning.
+  pub fn from_descriptor(app_handle: AppHandle, desc: PTaskDesc) -> Self {
+    let PTaskParam::WorldBackup(param) = desc.payload.clone() else {
+      unreachable!("descriptor is not a world backup task");
+    };
+    Self::new(app_handle, desc.task_id, desc.task_group, param)
+  }
+
+  pub async fn future(
+    self,
+    app_handle: AppHandle,
+  ) -> SJMCLResult<(
+    impl Future<Output = SJMCLResult<()>> + Send,
+    Arc<RwLock<PTaskHandle>>,
+  )> {
+    let handle = Arc::new(RwLock::new(self.p_handle));
+    let task_handle = handle.clone();
+    let param = self.param;
+    Ok((
+      async move {
+        let total = get_dir_size(&param.src)? as i64;
+        // bounded channel, so the compression blocks while the task is stopped.
+        let (tx, rx) = flume::bounded::<usize>(16);
+        let (src, dest) = (param.src.clone(), param.dest.clone());
+        let worker = tokio::task::spawn_blocking(move || {
+          create_world_snapshot(&src, &dest, |n| tx.send(n).is_ok())
+        });
+
+        {
+          let mut task_handle = task_handle.write().unwrap();
+          task_handle.set_total(total);
+          task_handle.mark_started();
+        }
+        ProgressStream::new(rx.into_stream(), task_handle.clone())
+          .for_each(|_| async {})
+          .await;
+        let result = worker
+          .await
+          .map_err(|e| SJMCLError(format!("World backup worker failed: {}", e)))?;
+
+        if task_handle.read().unwrap().status().is_cancelled() {
+          let _ = tokio::fs::remove_file(&param.dest).await;
+          return Ok(());
+        }
+        result?;
+
+        // apply the retention policies of the instance after each new snapshot
+        let instance = {
+          let binding = app_handle.state::<Mutex<HashMap<String, Instance>>>();
+          let state = binding.lock()?;
+          state.get(&param.instance_id).cloned()
+        };
+        if let (Some(instance), Some(backups_dir), Some(world_name)) = (
+          instance,
+          param.dest.parent(),
+          param
+            .src
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string()),
+        ) {
+          let retention = get_instance_game_config(&app_handle, &instance)
+            .world_backup
+            .retention;
+          apply_backup_retention(backups_dir, &world_name, &retention)?;
+        }
+        Ok(())
+      },
+      handle,
+    ))
+  }
+}
@@ -426,3 +426,169 @@ pub fn create_zip_from_dirs(paths: Vec<PathBuf>, zip_file_path: PathBuf) -> SJMC
 
   Ok(zip_file_path.to_string_lossy().to_string())
 }
+
+/// Recursively calculates the total size (in bytes) of all files under a directory.
+///
+/// # Examples
+///
+/// ```rust
+/// let total = get_dir_size(&world_dir)?;
+/// ```
+pub fn get_dir_size(path: &Path) -> std::io::Result<u64> {
+  let mut size = 0;
+  for entry in fs::read_dir(path)? {
+    let entry = entry?;
+    let file_type = entry.file_type()?;
+    if file_type.is_dir() {
+      size += get_dir_size(&entry.path())?;
+    } else if file_type.is_file() {
+      size += entry.metadata()?.len();
+    }
+  }
+  Ok(size)
+}
+
+/// Compresses a whole directory into a zip file, placing its contents under `root_name/`.
+///
+/// `on_progress` is called with the number of bytes written after each chunk, and the
+/// compression is aborted (and the unfinished zip file removed) once it returns `false`.
+/// Files whose names are listed in `excluded` are skipped at any depth.
+///
+/// # Examples
+///
+/// ```rust
+/// create_zip_from_dir(&world_dir, &zip_path, "New World", &["session.lock"], |_| true)?;
+/// ```
+pub fn create_zip_from_dir<F>(
+  src: &Path,
+  zip_file_path: &Path,
+  root_name: &str,
+  excluded: &[&str],
+  mut on_progress: F,
+) -> SJMCLResult<()>
+where
+  F: FnMut(usize) -> bool,
+{
+  fn add_dir_to_zip<F: FnMut(usize) -> bool>(
+    zip: &mut ZipWriter<fs::File>,
+    dir: &Path,
+    prefix: &str,
+    excluded: &[&str],
+    on_progress: &mut F,
+  ) -> SJMCLResult<()> {
+    let options = FileOptions::<ExtendedFileOptions>::default()
+      .compression_method(CompressionMethod::Deflated)
+      .large_file(true);
+    zip.add_directory(format!("{}/", prefix), options.clone())?;
+
+    for entry in fs::read_dir(dir)? {
+      let entry = entry?;
+      let name = entry.file_name().to_string_lossy().to_string();
+      if excluded.contains(&name.as_str()) {
+        continue;
+      }
+      let entry_path = entry.path();
+      let entry_name = format!("{}/{}", prefix, name);
+      if entry.file_type()?.is_dir() {
+        add_dir_to_zip(zip, &entry_path, &entry_name, excluded, on_progress)?;
+        continue;
+      }
+
+      zip.start_file(entry_name, options.clone())?;
+      let mut file = fs::File::open(&entry_path).map_err(|e| {
+        SJMCLError(format!(
+          "Failed to open file {}: {}",
+          entry_path.display(),
+          e
+        ))
+      })?;
+      let mut buf = vec![0u8; 64 * 1024];
+      loop {
+        let n = io::Read::read(&mut file, &mut buf)?;
+        if n == 0 {
+          break;
+        }
+        io::Write::write_all(zip, &buf[..n])?;
+        if !on_progress(n) {
+          return Err(SJMCLError("Zip compression aborted".to_string()));
+        }
+      }
+    }
+    Ok(())
+  }
+
+  if let Some(parent) = zip_file_path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  let zip_file = fs::File::create(zip_file_path)
+    .map_err(|e| SJMCLError(format!("Failed to create zip file: {}", e)))?;
+  let mut zip = ZipWriter::new(zip_file);
+
+  let result =
+    add_dir_to_zip(&mut zip, src, root_name, excluded, &mut on_progress).and_then(|_| {
+      zip
+        .finish()
+        .map(|_| ())
+        .map_err(|e| SJMCLError(format!("Failed to finalize zip file: {}", e)))
+    });
+  if result.is_err() {
+    let _ = fs::remove_file(zip_file_path);
+  }
+  result
+}
+
+/// Copies a single file in chunks, reporting the number of bytes written after each chunk.
+/// The copy is aborted (and the unfinished file removed) once `on_progress` returns `false`.
+///
+/// # Examples
+///
+/// ```rust
+/// copy_file_with_progress(&src_path, &dest_path, &mut |n| tx.send(n).is_ok())?;
+/// ```
+pub fn copy_file_with_progress<F>(src: &Path, dst: &Path, on_progress: &mut F) -> SJMCLResult<()>
+where
+  F: FnMut(usize) -> bool,
+{
+  let mut src_file = fs::File::open(src)
+    .map_err(|e| SJMCLError(format!("Failed to open file {}: {}", src.display(), e)))?;
+  let mut dst_file = fs::File::create(dst)
+    .map_err(|e| SJMCLError(format!("Failed to create file {}: {}", dst.display(), e)))?;
+
+  let result = (|| -> SJMCLResult<()> {
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+      let n = io::Read::read(&mut src_file, &mut buf)?;
+      if n == 0 {
+        break;
+      }
+      io::Write::write_all(&mut dst_file, &buf[..n])?;
+      if !on_progress(n) {
+        return Err(SJMCLError("File copy aborted".to_string()));
+      }
+    }
+    Ok(())
+  })();
+  if result.is_err() {
+    drop(dst_file);
+    let _ = fs::remove_file(dst);
+  }
+  result
+}
+
+/// Creates a hard link of `src` at `dst`, falling back to a plain copy when linking is not
+/// possible (e.g. across devices or on file systems without hard link support).
+///
+/// # Examples
+///
+/// ```rust
+/// hard_link_or_copy(&src_jar, &dest_jar)?;
+/// ```
+pub fn hard_link_or_copy(src: &Path, dst: &Path) -> std::io::Result<()> {
+  if dst.exists() {
+    fs::remove_file(dst)?;
+  }
+  if fs::hard_link(src, dst).is_err() {
+    fs::copy(src, dst)?;
+  }
+  Ok(())
+}
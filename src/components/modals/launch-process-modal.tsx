@@ -62,6 +62,7 @@ const LaunchProcessModal: React.FC<LaunchProcessModal> = ({
   const [errorDesc, setErrorDesc] = useState<string>("");
   const [activeStep, setActiveStep] = useState<number>(0);
   const previousStep = useRef<number>(-1);
+  const launchingId = useRef<number>();
 
   useEffect(() => {
     setLaunchingInstance(
@@ -70,7 +71,9 @@ const LaunchProcessModal: React.FC<LaunchProcessModal> = ({
   }, [getInstanceList, instanceId]);
 
   const handleCloseModalWithCancel = useCallback(() => {
-    LaunchService.cancelLaunchProcess();
+    if (launchingId.current !== undefined) {
+      LaunchService.cancelLaunchProcess(launchingId.current);
+    }
     setErrorPaused(false);
     props.onClose();
   }, [props]);
@@ -86,13 +89,16 @@ const LaunchProcessModal: React.FC<LaunchProcessModal> = ({
       {
         label: "selectSuitableJRE",
         function: () => LaunchService.selectSuitableJRE(instanceId),
-        isOK: (data: any) => true,
+        isOK: (data: number) => {
+          launchingId.current = data;
+          return true;
+        },
         onResCallback: (data: any) => {},
         onErrCallback: (error: ResponseError) => {}, // TODO
       },
       {
         label: "validateGameFiles",
-        function: () => LaunchService.validateGameFiles(),
+        function: () => LaunchService.validateGameFiles(launchingId.current!),
         isOK: (data: any) => true,
         onResCallback: (data: any) => {}, // TODO
         onErrCallback: (error: ResponseError) => {
@@ -107,7 +113,8 @@ const LaunchProcessModal: React.FC<LaunchProcessModal> = ({
       },
       {
         label: "validateSelectedPlayer",
-        function: () => LaunchService.validateSelectedPlayer(),
+        function: () =>
+          LaunchService.validateSelectedPlayer(launchingId.current!),
         isOK: (data: boolean) => data,
         onResCallback: (data: boolean) => {
           AccountService.refreshPlayer(selectedPlayer?.id || "").then(
@@ -135,7 +142,11 @@ const LaunchProcessModal: React.FC<LaunchProcessModal> = ({
       {
         label: "launchGame",
         function: () =>
-          LaunchService.launchGame(quickPlaySingleplayer, quickPlayMultiplayer),
+          LaunchService.launchGame(
+            launchingId.current!,
+            quickPlaySingleplayer,
+            quickPlayMultiplayer
+          ),
         isOK: (data: any) => true,
         onResCallback: (data: any) => {},
         onErrCallback: (error: ResponseError) => {},
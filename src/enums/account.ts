@@ -3,3 +3,9 @@ export enum PlayerType {
   ThirdParty = "3rdparty",
   Microsoft = "microsoft",
 }
+
+export enum PlayerAuthState {
+  Unknown = "unknown",
+  Valid = "valid",
+  Revoked = "revoked",
+}
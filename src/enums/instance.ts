@@ -1,5 +1,6 @@
 export enum InstanceSubdirType {
   Assets = "Assets",
+  Backups = "Backups",
   Libraries = "Libraries",
   Mods = "Mods",
   ResourcePacks = "ResourcePacks",
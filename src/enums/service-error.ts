@@ -12,6 +12,9 @@ export enum InstanceError {
   InstanceNotFoundById = "INSTANCE_NOT_FOUND_BY_ID",
   ConflictNameError = "CONFLICT_NAME_ERROR",
   ServerNbtReadError = "SERVER_NBT_READ_ERROR",
+  ServerNbtSaveError = "SERVER_NBT_SAVE_ERROR",
+  ServerNotExistError = "SERVER_NOT_EXIST_ERROR",
+  ServerAlreadyExistError = "SERVER_ALREADY_EXIST_ERROR",
   FileNotFoundError = "FILE_NOT_FOUND_ERROR",
   InvalidSourcePath = "INVALID_SOURCE_PATH",
   FileCopyFailed = "FILE_COPY_FAILED",
@@ -1,4 +1,4 @@
-import { PlayerType } from "@/enums/account";
+import { PlayerAuthState, PlayerType } from "@/enums/account";
 
 export enum PresetSkinType {
   Steve = "steve",
@@ -12,6 +12,19 @@ export interface Texture {
   preset?: PresetSkinType;
 }
 
+// a game profile (character) of a third-party account
+export interface GameProfile {
+  uuid: string;
+  name: string;
+}
+
+export interface CapeInfo {
+  id: string;
+  name: string;
+  url: string;
+  active: boolean;
+}
+
 // player (frontend display format)
 export interface Player {
   id: string;
@@ -25,6 +38,8 @@ export interface Player {
   accessToken?: string; // only from authlib-injector or microsoft
   refreshToken?: string; // only from oauth login methods
   textures: Array<Texture>;
+  authState: PlayerAuthState; // revoked players need to log in again
+  availableProfiles: GameProfile[]; // only from authlib-injector password login
 }
 
 // player (backend storage format)
@@ -39,6 +54,8 @@ export interface PlayerInfo {
   accessToken: string;
   refreshToken: string;
   textures: Array<Texture>;
+  authState: PlayerAuthState;
+  availableProfiles: GameProfile[];
 }
 
 // authlib-injector source
@@ -58,6 +75,28 @@ export interface AuthServerFeatures {
   openidConfigurationUrl: string;
 }
 
+export type AuthServerCheck =
+  | "aliRedirection"
+  | "apiRoot"
+  | "signaturePublicKey"
+  | "skinDomains"
+  | "authenticate"
+  | "validate"
+  | "refresh"
+  | "hasJoined"
+  | "openidFeature";
+
+export interface AuthServerCheckResult {
+  check: AuthServerCheck;
+  status: "passed" | "failed" | "skipped";
+  message: string;
+}
+
+export interface AuthServerDiagnosis {
+  authUrl: string;
+  checks: AuthServerCheckResult[];
+}
+
 export interface OAuthCodeResponse {
   deviceCode: string;
   userCode: string;
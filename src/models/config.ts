@@ -24,6 +24,14 @@ export interface GameConfig {
   versionIsolation: boolean;
   launcherVisibility: string;
   displayGameLog: boolean;
+  worldBackup: {
+    autoBackupBeforeLaunch: boolean;
+    retention: {
+      keepLast: number;
+      keepDaily: number;
+      keepWeekly: number;
+    };
+  };
   advancedOptions: {
     enabled: boolean;
   };
@@ -56,6 +64,7 @@ export interface GameDirectory {
 }
 
 export interface LauncherConfig {
+  schemaVersion: number;
   basicInfo: {
     launcherVersion: string;
     platform: string;
@@ -91,6 +100,7 @@ export interface LauncherConfig {
   download: {
     source: {
       strategy: string;
+      preferredResourceSource: string;
     };
     transmission: {
       autoConcurrent: boolean;
@@ -116,6 +126,8 @@ export interface LauncherConfig {
       discoverPage: boolean;
       instancesNavType: string;
       launchPageQuickSwitch: boolean;
+      autoCheckResourceUpdates: boolean;
+      resourceUpdateCheckInterval: number;
     };
   };
   localGameDirectories: GameDirectory[];
@@ -149,6 +161,31 @@ export interface LauncherConfig {
   };
 }
 
+export type LauncherConfigSection =
+  | "appearance"
+  | "download"
+  | "globalGameConfig"
+  | "gameDirectories"
+  | "instanceConfigs"
+  | "authServers";
+
+export type LauncherConfigImportMode = "merge" | "replace";
+
+export interface LauncherConfigChange {
+  section: LauncherConfigSection;
+  keyPath: string;
+  current: any; // null if added
+  incoming: any; // null if removed
+}
+
+export interface LauncherConfigImportPreview {
+  schemaVersion: number;
+  launcherVersion: string;
+  exportedAt: number; // UNIX timestamp
+  sections: LauncherConfigSection[];
+  changes: LauncherConfigChange[];
+}
+
 export const defaultGameConfig: GameConfig = {
   gameJava: {
     auto: true,
@@ -175,6 +212,14 @@ export const defaultGameConfig: GameConfig = {
   versionIsolation: true,
   launcherVisibility: "startHidden",
   displayGameLog: false,
+  worldBackup: {
+    autoBackupBeforeLaunch: false,
+    retention: {
+      keepLast: 10,
+      keepDaily: 0,
+      keepWeekly: 0,
+    },
+  },
   advancedOptions: {
     enabled: false,
   },
@@ -202,6 +247,7 @@ export const defaultGameConfig: GameConfig = {
 };
 
 export const defaultConfig: LauncherConfig = {
+  schemaVersion: 1,
   basicInfo: {
     launcherVersion: "dev",
     platform: "",
@@ -237,6 +283,7 @@ export const defaultConfig: LauncherConfig = {
   download: {
     source: {
       strategy: "auto",
+      preferredResourceSource: "modrinth",
     },
     transmission: {
       autoConcurrent: true,
@@ -262,6 +309,8 @@ export const defaultConfig: LauncherConfig = {
       discoverPage: false,
       instancesNavType: "instance",
       launchPageQuickSwitch: false,
+      autoCheckResourceUpdates: true,
+      resourceUpdateCheckInterval: 24,
     },
   },
   localGameDirectories: [{ name: "Current", dir: ".minecraft/" }],
@@ -297,3 +346,13 @@ export const defaultConfig: LauncherConfig = {
     },
   },
 };
+
+// per-version settings left by other launchers (HMCL, PCL), imported on adding a game directory
+export interface ImportedInstanceSettings {
+  instanceName: string;
+  sourceLaunchers: string[];
+  description?: string;
+  iconSrc?: string;
+  starred?: boolean;
+  specGameConfig?: GameConfig;
+}
@@ -26,6 +26,8 @@ export interface InstanceSummary {
   supportQuickPlay: boolean;
   useSpecGameConfig: boolean;
   isVersionIsolated: boolean;
+  updateChannel: "release" | "beta" | "alpha"; // the least stable release type for updates
+  pinnedResources: string[]; // skipped by the update checker, as `<subdir>/<file name>`
 }
 
 export interface ModpackMetaInfo {
@@ -50,6 +52,16 @@ export interface GameServerInfo {
   playersOnline?: number;
   playersMax?: number;
   online: boolean;
+  acceptTextures?: boolean; // server resource packs, prompt on joining if not set
+  hidden: boolean;
+}
+
+export interface GameServerEntry {
+  name: string;
+  ip: string;
+  icon?: string; // base64 png, keep the current one if not given
+  acceptTextures?: boolean;
+  hidden?: boolean;
 }
 
 export interface LocalModInfo {
@@ -65,25 +77,116 @@ export interface LocalModInfo {
   potentialIncompatibility: boolean;
 }
 
+export interface PackOverlay {
+  directory: string;
+  formats?: [number, number]; // inclusive range
+}
+
 export interface ResourcePackInfo {
   name: string;
   description?: string;
   iconSrc?: string;
   filePath: string;
+  packFormat?: number;
+  supportedFormats?: [number, number]; // inclusive range
+  overlays: PackOverlay[];
+  enabled: boolean; // listed in options.txt
+  potentialIncompatibility: boolean;
+}
+
+export interface DataPackInfo {
+  name: string;
+  description: string;
+  iconSrc?: string;
+  filePath: string;
+  packFormat?: number;
+  enabled: boolean;
+  potentialIncompatibility: boolean; // pack format does not match the world's data version
+}
+
+// isolated subdirs carried over to the duplicated instance
+export interface DuplicateInstanceOptions {
+  saves: boolean;
+  mods: boolean;
+  config: boolean;
+  screenshots: boolean;
+}
+
+export interface GameOptionsInfo {
+  dataVersion?: number;
+  lang?: string;
+  fov?: number; // in degrees
+  guiScale?: number;
+  renderDistance?: number;
+  maxFps?: number;
+  soundLevels: Record<string, number>; // category -> [0, 1]
+  keyBindings: Record<string, string>; // e.g. `key.jump` -> `key.keyboard.space`
+  entries: [string, string][]; // all the keys in the order of options.txt
+}
+
+export interface GameOptionsPatch {
+  lang?: string;
+  fov?: number;
+  guiScale?: number;
+  renderDistance?: number;
+  maxFps?: number;
+  soundLevels?: Record<string, number>;
+  keyBindings?: Record<string, string>;
+  others?: Record<string, string>; // raw options.txt keys
+}
+
+export interface GameOptionsTemplate {
+  entries: [string, string][];
+  dataVersion?: number;
+  sourceInstanceName?: string;
+  applyToNewInstances: boolean;
+  syncKeys: string[]; // keys or prefixes ending with `*`
 }
 
 export interface SchematicInfo {
   name: string;
   filePath: string;
+  format: "litematica" | "sponge" | "mcedit" | "structure";
+  version?: number;
+  width: number;
+  height: number;
+  length: number;
+  regionCount: number;
+  displayName?: string;
+  author?: string;
+  description?: string;
+  createdAt?: number; // UNIX timestamp
+  parseFailed: boolean; // the metadata above is left empty
 }
 
+export interface SchematicMaterial {
+  name: string;
+  count: number;
+}
+
+export type ShaderPackIssue =
+  | "invalidPack"
+  | "noShaderLoader"
+  | "irisFeaturesRequired";
+
 export interface ShaderPackInfo {
   fileName: string;
   filePath: string;
+  isDir: boolean;
+  version?: string;
+  dimensions: string[]; // e.g. world0, world-1, world1
+  profiles: string[];
+  requiredIrisFeatures: string[];
+  hasOptionsFile: boolean;
+  issue?: ShaderPackIssue;
 }
 
 export interface ScreenshotInfo {
   fileName: string;
   filePath: string;
   time: number; // UNIX timestamp
+  size: number;
+  width: number;
+  height: number;
+  thumbnailPath: string; // may not be generated yet
 }
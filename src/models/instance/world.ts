@@ -7,6 +7,14 @@ export interface WorldInfo {
   dirPath: string;
 }
 
+export interface WorldBackupInfo {
+  worldName: string;
+  fileName: string;
+  filePath: string;
+  size: number;
+  createdAt: number;
+}
+
 // level and player data
 export interface LevelData {
   allowCommands?: number;
@@ -48,6 +56,26 @@ export interface LevelData {
   wasModded: number;
 }
 
+// fields of level.dat which can be edited offline, omitted fields are left untouched
+export interface LevelDataPatch {
+  levelName?: string;
+  difficulty?: number; // 0: peaceful, 1: easy, 2: normal, 3: hard
+  difficultyLocked?: boolean;
+  hardcore?: boolean;
+  allowCommands?: boolean;
+  spawnX?: number;
+  spawnY?: number;
+  spawnZ?: number;
+  time?: number;
+  daytime?: number;
+  clearWeatherTime?: number;
+  raining?: boolean;
+  rainTime?: number;
+  thundering?: boolean;
+  thunderTime?: number;
+  gameRules?: Record<string, string>;
+}
+
 export interface Version {
   id: number;
   name: string;
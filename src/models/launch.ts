@@ -14,4 +14,67 @@ export interface LaunchingState {
   selectedPlayer?: PlayerInfo;
   authServerMeta: string;
   pid: number;
+  quickPlayServer?: string;
+  resourceHistory: ProcessResourceSample[];
+  gcHistory: GcEvent[];
+}
+
+export interface ProcessResourceSample {
+  timestamp: number;
+  cpuUsage: number;
+  memory: number;
+  threadCount: number;
+  openFileCount: number;
+  processCount: number;
+}
+
+export interface GcEvent {
+  timestamp: number;
+  uptime?: number;
+  gcId?: number;
+  kind: string;
+  heapBefore: number;
+  heapAfter: number;
+  heapCapacity: number;
+  pauseMs: number;
+}
+
+export interface PlaySession {
+  id: number; // the launching id
+  instanceId: string;
+  instanceName: string;
+  playerId: string;
+  playerName: string;
+  startTime: number; // UNIX timestamp in seconds
+  endTime: number;
+  playTime: number; // seconds since the game window showed up
+  exitCode?: number;
+  crashed: boolean;
+  server?: string;
+  javaName: string;
+  javaPath: string;
+}
+
+export interface PlayTimePeriodStats {
+  period: string; // `2025-03-01` for a day, `2025-W09` for an ISO week
+  playTime: number;
+  sessionCount: number;
+}
+
+export interface InstancePlayStats {
+  instanceId: string;
+  instanceName: string;
+  playTime: number;
+  sessionCount: number;
+  crashCount: number;
+  crashRate: number;
+  lastPlayedAt: number;
+}
+
+export interface PlayStatistics {
+  totalPlayTime: number;
+  sessionCount: number;
+  daily: PlayTimePeriodStats[];
+  weekly: PlayTimePeriodStats[];
+  instances: InstancePlayStats[]; // most played first
 }
@@ -7,12 +7,21 @@ export const mockResourcePacks: ResourcePackInfo[] = [
     iconSrc:
       "https://media.forgecdn.net/avatars/546/645/637882030837320187.png",
     filePath: "/.minecraft/resourcepacks",
+    packFormat: 34,
+    supportedFormats: [22, 34],
+    overlays: [],
+    enabled: true,
+    potentialIncompatibility: false,
   },
   {
     name: "空白资源包",
     description: "测试空白资源包",
     iconSrc: "",
     filePath: "/.minecraft/resourcepacks",
+    packFormat: 15,
+    overlays: [],
+    enabled: false,
+    potentialIncompatibility: true,
   },
 ];
 
@@ -20,9 +29,20 @@ export const mockSchematics: SchematicInfo[] = [
   {
     name: "TestFile.schematic",
     filePath: "/.minecraft/schematics",
+    format: "mcedit",
+    width: 16,
+    height: 8,
+    length: 16,
+    regionCount: 1,
   },
   {
     name: "McDonalds-Minhang-Campus.litematic",
     filePath: "/.minecraft/schematics",
+    format: "litematica",
+    version: 6,
+    width: 64,
+    height: 24,
+    length: 48,
+    regionCount: 2,
   },
 ];
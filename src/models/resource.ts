@@ -24,6 +24,14 @@ export interface OtherResourceInfo {
   lastUpdated: string;
   downloads: number;
   source?: OtherResourceSource;
+  slug?: string;
+  altSources?: OtherResourceAltSource[];
+}
+
+export interface OtherResourceAltSource {
+  source: OtherResourceSource;
+  resourceId: string;
+  websiteUrl: string;
 }
 
 export interface OtherResourceSearchRes {
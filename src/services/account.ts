@@ -1,5 +1,11 @@
 import { invoke } from "@tauri-apps/api/core";
-import { AuthServer, OAuthCodeResponse, Player } from "@/models/account";
+import {
+  AuthServer,
+  AuthServerDiagnosis,
+  CapeInfo,
+  OAuthCodeResponse,
+  Player,
+} from "@/models/account";
 import { InvokeResponse } from "@/models/response";
 import { responseHandler } from "@/utils/response";
 
@@ -142,6 +148,20 @@ export class AccountService {
     return await invoke("add_player_from_selection", { player });
   }
 
+  /**
+   * SWITCH the third-party player to another game profile of the same account.
+   * @param {string} playerId - The ID of the player to be switched.
+   * @param {string} profileUuid - The UUID of the profile to switch to, one of its available profiles.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("account")
+  static async switchPlayerProfile(
+    playerId: string,
+    profileUuid: string
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("switch_player_profile", { playerId, profileUuid });
+  }
+
   /**
    * UPDATE the skin of an offline player within preset roles (Steve, Alex).
    * @param {string} playerId - The player ID of the player to be updated.
@@ -159,6 +179,60 @@ export class AccountService {
     });
   }
 
+  /**
+   * UPLOAD a png skin for a player, offline players save it locally.
+   * @param {string} playerId - The player ID of the player to be updated.
+   * @param {string} filePath - The path of the skin file, 64x64 or 64x32.
+   * @param {string} model - The skin model, "default" (classic) or "slim".
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("account")
+  static async uploadPlayerSkin(
+    playerId: string,
+    filePath: string,
+    model: "default" | "slim"
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("upload_player_skin", { playerId, filePath, model });
+  }
+
+  /**
+   * RESET the skin of a player to the default one.
+   * @param {string} playerId - The player ID of the player to be updated.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("account")
+  static async resetPlayerSkin(
+    playerId: string
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("reset_player_skin", { playerId });
+  }
+
+  /**
+   * RETRIEVE the capes owned by a Microsoft player.
+   * @param {string} playerId - The player ID of the player.
+   * @returns {Promise<InvokeResponse<CapeInfo[]>>}
+   */
+  @responseHandler("account")
+  static async retrievePlayerCapes(
+    playerId: string
+  ): Promise<InvokeResponse<CapeInfo[]>> {
+    return await invoke("retrieve_player_capes", { playerId });
+  }
+
+  /**
+   * UPDATE the active cape of a Microsoft player.
+   * @param {string} playerId - The player ID of the player to be updated.
+   * @param {string} [capeId] - The cape ID to show, hide the cape if not given.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("account")
+  static async updatePlayerCape(
+    playerId: string,
+    capeId?: string
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("update_player_cape", { playerId, capeId });
+  }
+
   /**
    * DELETE a player by player ID.
    * @param {string} playerId - The player ID of the player to be deleted.
@@ -200,6 +274,22 @@ export class AccountService {
     return await invoke("fetch_auth_server", { url });
   }
 
+  /**
+   * DIAGNOSE the compatibility of an authentication server, returning a per-check report.
+   * @param {string} url - The URL of the authentication server.
+   * @param {string} [username] - The username of a test account, the account checks are skipped without it.
+   * @param {string} [password] - The password of the test account.
+   * @returns {Promise<InvokeResponse<AuthServerDiagnosis>>}
+   */
+  @responseHandler("account")
+  static async diagnoseAuthServer(
+    url: string,
+    username?: string,
+    password?: string
+  ): Promise<InvokeResponse<AuthServerDiagnosis>> {
+    return await invoke("diagnose_auth_server", { url, username, password });
+  }
+
   /**
    * ADD the new authentication server to the storage.
    * @param {string} authUrl - The authentication server URL (already formatted by backend).
@@ -1,6 +1,12 @@
 import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWebview } from "@tauri-apps/api/webview";
-import { LauncherConfig } from "@/models/config";
+import {
+  ImportedInstanceSettings,
+  LauncherConfig,
+  LauncherConfigImportMode,
+  LauncherConfigImportPreview,
+  LauncherConfigSection,
+} from "@/models/config";
 import { InvokeResponse } from "@/models/response";
 import { JavaInfo } from "@/models/system-info";
 import { responseHandler } from "@/utils/response";
@@ -68,6 +74,66 @@ export class ConfigService {
     return await invoke("import_launcher_config", { code });
   }
 
+  /**
+   * EXPORT the selected sections of the launcher configs to a local file.
+   * @param {LauncherConfigSection[]} sections - The sections to export.
+   * @param {string} savePath - The path of the exported file.
+   * @param {boolean} asZip - Whether to export a zip file, which also contains the custom backgrounds.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("config")
+  static async exportLauncherConfigToFile(
+    sections: LauncherConfigSection[],
+    savePath: string,
+    asZip: boolean
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("export_launcher_config_to_file", {
+      sections,
+      savePath,
+      asZip,
+    });
+  }
+
+  /**
+   * RETRIEVE the changes that importing a local config file would make.
+   * @param {string} filePath - The path of the exported file.
+   * @param {LauncherConfigSection[] | null} sections - The sections to import, all sections in the file if null.
+   * @param {LauncherConfigImportMode} mode - Merge into or replace the current configs.
+   * @returns {Promise<InvokeResponse<LauncherConfigImportPreview>>}
+   */
+  @responseHandler("config")
+  static async retrieveLauncherConfigImportPreview(
+    filePath: string,
+    sections: LauncherConfigSection[] | null,
+    mode: LauncherConfigImportMode
+  ): Promise<InvokeResponse<LauncherConfigImportPreview>> {
+    return await invoke("retrieve_launcher_config_import_preview", {
+      filePath,
+      sections,
+      mode,
+    });
+  }
+
+  /**
+   * IMPORT the selected sections of the launcher configs from a local file.
+   * @param {string} filePath - The path of the exported file.
+   * @param {LauncherConfigSection[]} sections - The sections to import.
+   * @param {LauncherConfigImportMode} mode - Merge into or replace the current configs.
+   * @returns {Promise<InvokeResponse<LauncherConfig>>} the launcher configs after importing, which have been saved in backend.
+   */
+  @responseHandler("config")
+  static async importLauncherConfigFromFile(
+    filePath: string,
+    sections: LauncherConfigSection[],
+    mode: LauncherConfigImportMode
+  ): Promise<InvokeResponse<LauncherConfig>> {
+    return await invoke("import_launcher_config_from_file", {
+      filePath,
+      sections,
+      mode,
+    });
+  }
+
   /**
    * RETRIEVE the list of custom background files.
    * @returns {Promise<InvokeResponse<string[]>>} A list of background file names.
@@ -125,13 +191,27 @@ export class ConfigService {
   /**
    * CHECK whether the game directory is valid.
    * @param {string} dir The game directory to check.
+   * @param {string[]} [importInstanceNames] The instances to import the settings of other launchers for, chosen from the preview.
    * @returns {Promise<InvokeResponse<string>>} The sub directory if a sub game directory is valid.
    */
   @responseHandler("config")
   static async checkGameDirectory(
-    dir: string
+    dir: string,
+    importInstanceNames?: string[]
   ): Promise<InvokeResponse<string>> {
-    return await invoke("check_game_directory", { dir });
+    return await invoke("check_game_directory", { dir, importInstanceNames });
+  }
+
+  /**
+   * RETRIEVE the settings left by other launchers (HMCL, PCL) in a game directory, to be imported on adding it.
+   * @param {string} dir The game directory to be added.
+   * @returns {Promise<InvokeResponse<ImportedInstanceSettings[]>>}
+   */
+  @responseHandler("config")
+  static async retrieveInstanceSettingsImportPreview(
+    dir: string
+  ): Promise<InvokeResponse<ImportedInstanceSettings[]>> {
+    return await invoke("retrieve_instance_settings_import_preview", { dir });
   }
 
   /**
@@ -2,19 +2,32 @@ import { invoke } from "@tauri-apps/api/core";
 import { InstanceSubdirType } from "@/enums/instance";
 import { GameConfig, GameDirectory } from "@/models/config";
 import {
+  DataPackInfo,
+  DuplicateInstanceOptions,
+  GameOptionsInfo,
+  GameOptionsPatch,
+  GameOptionsTemplate,
+  GameServerEntry,
   GameServerInfo,
   InstanceSummary,
   LocalModInfo,
   ModpackMetaInfo,
   ResourcePackInfo,
   SchematicInfo,
+  SchematicMaterial,
   ScreenshotInfo,
   ShaderPackInfo,
 } from "@/models/instance/misc";
-import { LevelData, WorldInfo } from "@/models/instance/world";
+import {
+  LevelData,
+  LevelDataPatch,
+  WorldBackupInfo,
+  WorldInfo,
+} from "@/models/instance/world";
 import {
   GameClientResourceInfo,
   ModLoaderResourceInfo,
+  OtherResourceFileInfo,
 } from "@/models/resource";
 import { InvokeResponse } from "@/models/response";
 import { responseHandler } from "@/utils/response";
@@ -163,6 +176,22 @@ export class InstanceService {
     });
   }
 
+  /**
+   * DUPLICATE an instance under a new name, scheduled as a background task.
+   * @param {string} instanceId - The ID of the instance to be duplicated.
+   * @param {string} newName - The name of the new instance.
+   * @param {DuplicateInstanceOptions} options - The isolated subdirs to be carried over.
+   * @returns {Promise<InvokeResponse<string>>} The version path of the new instance.
+   */
+  @responseHandler("instance")
+  static async duplicateInstance(
+    instanceId: string,
+    newName: string,
+    options: DuplicateInstanceOptions
+  ): Promise<InvokeResponse<string>> {
+    return await invoke("duplicate_instance", { instanceId, newName, options });
+  }
+
   /**
    * COPY the specified resource to the target instance(s).
    * @param {string} srcFilePath - The path of the file (or the directory) to copy.
@@ -237,6 +266,80 @@ export class InstanceService {
     });
   }
 
+  /**
+   * ADD a server to servers.dat of the instance.
+   * @param {string} instanceId - The instance ID to add the server to.
+   * @param {GameServerEntry} server - The server to add.
+   * @param {number} [index] - The position to insert at, appended if not given.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async addGameServer(
+    instanceId: string,
+    server: GameServerEntry,
+    index?: number
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("add_game_server", { instanceId, server, index });
+  }
+
+  /**
+   * UPDATE a server in servers.dat of the instance, unknown tags are kept.
+   * @param {string} instanceId - The instance ID to update the server for.
+   * @param {number} index - The index of the server in the list.
+   * @param {GameServerEntry} server - The new values of the server.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async updateGameServer(
+    instanceId: string,
+    index: number,
+    server: GameServerEntry
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("update_game_server", { instanceId, index, server });
+  }
+
+  /**
+   * DELETE a server from servers.dat of the instance.
+   * @param {string} instanceId - The instance ID to delete the server from.
+   * @param {number} index - The index of the server in the list.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async deleteGameServer(
+    instanceId: string,
+    index: number
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("delete_game_server", { instanceId, index });
+  }
+
+  /**
+   * REORDER the servers in servers.dat of the instance.
+   * @param {string} instanceId - The instance ID to reorder the servers for.
+   * @param {number[]} order - The current indices of the servers, in the new order.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async reorderGameServers(
+    instanceId: string,
+    order: number[]
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("reorder_game_servers", { instanceId, order });
+  }
+
+  /**
+   * APPLY a server list to several instances, skipping the addresses already in their lists.
+   * @param {string[]} instanceIds - The instance IDs to apply the list to.
+   * @param {GameServerEntry[]} servers - The servers to append.
+   * @returns {Promise<InvokeResponse<Record<string, number>>>} the number of servers added to each instance.
+   */
+  @responseHandler("instance")
+  static async applyGameServerList(
+    instanceIds: string[],
+    servers: GameServerEntry[]
+  ): Promise<InvokeResponse<Record<string, number>>> {
+    return await invoke("apply_game_server_list", { instanceIds, servers });
+  }
+
   /**
    * RETRIEVE the list of local mods.
    * @param {string} instanceId - The instance ID to retrieve the local mods for.
@@ -279,6 +382,117 @@ export class InstanceService {
     });
   }
 
+  /**
+   * RETRIEVE the active resource packs in options.txt.
+   * @param {string} instanceId - The instance ID to retrieve the active resource packs for.
+   * @returns {Promise<InvokeResponse<string[]>>} file names, from the highest priority to the lowest.
+   */
+  @responseHandler("instance")
+  static async retrieveActiveResourcePacks(
+    instanceId: string
+  ): Promise<InvokeResponse<string[]>> {
+    return await invoke("retrieve_active_resource_packs", {
+      instanceId,
+    });
+  }
+
+  /**
+   * UPDATE the active resource packs in options.txt.
+   * @param {string} instanceId - The instance ID to update the active resource packs for.
+   * @param {string[]} fileNames - The file names, from the highest priority to the lowest.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async updateActiveResourcePacks(
+    instanceId: string,
+    fileNames: string[]
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("update_active_resource_packs", {
+      instanceId,
+      fileNames,
+    });
+  }
+
+  /**
+   * RETRIEVE the options.txt of the instance.
+   * @param {string} instanceId - The instance ID to retrieve the game options for.
+   * @returns {Promise<InvokeResponse<GameOptionsInfo>>}
+   */
+  @responseHandler("instance")
+  static async retrieveGameOptions(
+    instanceId: string
+  ): Promise<InvokeResponse<GameOptionsInfo>> {
+    return await invoke("retrieve_game_options", { instanceId });
+  }
+
+  /**
+   * UPDATE the options.txt of the instance, unknown keys are kept as they are.
+   * @param {string} instanceId - The instance ID to update the game options for.
+   * @param {GameOptionsPatch} patch - The values to update.
+   * @returns {Promise<InvokeResponse<GameOptionsInfo>>} the updated options.
+   */
+  @responseHandler("instance")
+  static async updateGameOptions(
+    instanceId: string,
+    patch: GameOptionsPatch
+  ): Promise<InvokeResponse<GameOptionsInfo>> {
+    return await invoke("update_game_options", { instanceId, patch });
+  }
+
+  /**
+   * RETRIEVE the game options template.
+   * @returns {Promise<InvokeResponse<GameOptionsTemplate>>}
+   */
+  @responseHandler("instance")
+  static async retrieveGameOptionsTemplate(): Promise<
+    InvokeResponse<GameOptionsTemplate>
+  > {
+    return await invoke("retrieve_game_options_template");
+  }
+
+  /**
+   * SAVE the game options template from the options.txt of an instance.
+   * @param {string} instanceId - The instance ID to save the template from.
+   * @param {string[]} keys - The keys or prefixes ending with `*` to save, all the keys if empty.
+   * @returns {Promise<InvokeResponse<GameOptionsTemplate>>}
+   */
+  @responseHandler("instance")
+  static async saveGameOptionsTemplate(
+    instanceId: string,
+    keys: string[]
+  ): Promise<InvokeResponse<GameOptionsTemplate>> {
+    return await invoke("save_game_options_template", { instanceId, keys });
+  }
+
+  /**
+   * UPDATE the settings of the game options template.
+   * @param {boolean} applyToNewInstances - Whether to apply the template to new instances.
+   * @param {string[]} syncKeys - The keys or prefixes ending with `*` to sync to all instances.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async updateGameOptionsTemplateSettings(
+    applyToNewInstances: boolean,
+    syncKeys: string[]
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("update_game_options_template_settings", {
+      applyToNewInstances,
+      syncKeys,
+    });
+  }
+
+  /**
+   * SYNC the chosen keys of the game options template to the instances.
+   * @param {string[]} [instanceIds] - The instance IDs to sync to, all instances if not given.
+   * @returns {Promise<InvokeResponse<number>>} the number of options.txt files updated.
+   */
+  @responseHandler("instance")
+  static async syncGameOptionsTemplate(
+    instanceIds?: string[]
+  ): Promise<InvokeResponse<number>> {
+    return await invoke("sync_game_options_template", { instanceIds });
+  }
+
   /**
    * RETRIEVE the list of schematics.
    * @param {string} instanceId - The instance ID to retrieve the schematics for.
@@ -293,6 +507,18 @@ export class InstanceService {
     });
   }
 
+  /**
+   * RETRIEVE the block counts of a schematic, most used first.
+   * @param {string} filePath - The path of the schematic file.
+   * @returns {Promise<InvokeResponse<SchematicMaterial[]>>}
+   */
+  @responseHandler("instance")
+  static async retrieveSchematicMaterials(
+    filePath: string
+  ): Promise<InvokeResponse<SchematicMaterial[]>> {
+    return await invoke("retrieve_schematic_materials", { filePath });
+  }
+
   /**
    * RETRIEVE the list of shaderpacks.
    * @param {string} instanceId - The instance ID to retrieve the shaderpacks for.
@@ -307,6 +533,43 @@ export class InstanceService {
     });
   }
 
+  /**
+   * RETRIEVE the options of a shaderpack, saved as `<pack>.txt` next to it.
+   * @param {string} instanceId - The instance ID of the shaderpack.
+   * @param {string} fileName - The file name of the shaderpack in the shaderpacks folder.
+   * @returns {Promise<InvokeResponse<Record<string, string>>>}
+   */
+  @responseHandler("instance")
+  static async retrieveShaderPackOptions(
+    instanceId: string,
+    fileName: string
+  ): Promise<InvokeResponse<Record<string, string>>> {
+    return await invoke("retrieve_shader_pack_options", {
+      instanceId,
+      fileName,
+    });
+  }
+
+  /**
+   * SAVE the options of a shaderpack, overwriting `<pack>.txt` next to it.
+   * @param {string} instanceId - The instance ID of the shaderpack.
+   * @param {string} fileName - The file name of the shaderpack in the shaderpacks folder.
+   * @param {Record<string, string>} options - The options to save.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async saveShaderPackOptions(
+    instanceId: string,
+    fileName: string,
+    options: Record<string, string>
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("save_shader_pack_options", {
+      instanceId,
+      fileName,
+      options,
+    });
+  }
+
   /**
    * RETRIEVE the list of screenshots.
    * @param {string} instanceId - The instance ID to retrieve the screenshots for.
@@ -321,6 +584,81 @@ export class InstanceService {
     });
   }
 
+  /**
+   * DELETE the screenshots and their thumbnails.
+   * @param {string} instanceId - The instance ID the screenshots belong to.
+   * @param {string[]} filePaths - The paths of the screenshots, all in the screenshots folder of the instance.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async deleteScreenshots(
+    instanceId: string,
+    filePaths: string[]
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("delete_screenshots", {
+      instanceId,
+      filePaths,
+    });
+  }
+
+  /**
+   * MOVE the screenshots to the screenshots folder of another instance.
+   * @param {string} instanceId - The instance ID the screenshots belong to.
+   * @param {string[]} filePaths - The paths of the screenshots, all in the screenshots folder of the instance.
+   * @param {string} targetInstanceId - The instance ID to move the screenshots to.
+   * @returns {Promise<InvokeResponse<string[]>>} The new paths of the moved screenshots.
+   */
+  @responseHandler("instance")
+  static async moveScreenshots(
+    instanceId: string,
+    filePaths: string[],
+    targetInstanceId: string
+  ): Promise<InvokeResponse<string[]>> {
+    return await invoke("move_screenshots", {
+      instanceId,
+      filePaths,
+      targetInstanceId,
+    });
+  }
+
+  /**
+   * COPY the screenshot image to the clipboard.
+   * @param {string} filePath - The path of the screenshot.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async copyScreenshotToClipboard(
+    filePath: string
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("copy_screenshot_to_clipboard", { filePath });
+  }
+
+  /**
+   * EXPORT the screenshots taken in the time range to a folder or a zip file.
+   * @param {string} savePath - The folder or the zip file path to export to.
+   * @param {boolean} asZip - Whether to export as a zip file.
+   * @param {string[]} [instanceIds] - The instance IDs to export from, all instances if not given.
+   * @param {number} [startTime] - The UNIX timestamp (in seconds) of the range start.
+   * @param {number} [endTime] - The UNIX timestamp (in seconds) of the range end.
+   * @returns {Promise<InvokeResponse<number>>} The number of exported screenshots.
+   */
+  @responseHandler("instance")
+  static async exportScreenshots(
+    savePath: string,
+    asZip: boolean,
+    instanceIds?: string[],
+    startTime?: number,
+    endTime?: number
+  ): Promise<InvokeResponse<number>> {
+    return await invoke("export_screenshots", {
+      instanceIds,
+      startTime,
+      endTime,
+      savePath,
+      asZip,
+    });
+  }
+
   /**
    * TOGGLE the mod status by changing the file extension.
    * @param {string} filePath - The path of the file to toggle mod for.
@@ -355,6 +693,171 @@ export class InstanceService {
     });
   }
 
+  /**
+   * UPDATE the editable fields of a world's level.dat, the world must not be opened in game.
+   * @param {string} instanceId - The instance ID of the world.
+   * @param {string} worldName - The directory name of the world.
+   * @param {LevelDataPatch} patch - The fields to be updated.
+   * @returns {Promise<InvokeResponse<LevelData>>} The updated level data.
+   */
+  @responseHandler("instance")
+  static async updateWorldLevelData(
+    instanceId: string,
+    worldName: string,
+    patch: LevelDataPatch
+  ): Promise<InvokeResponse<LevelData>> {
+    return await invoke("update_world_level_data", {
+      instanceId,
+      worldName,
+      patch,
+    });
+  }
+
+  /**
+   * CREATE a zip backup of a world, scheduled as a background task.
+   * @param {string} instanceId - The instance ID of the world.
+   * @param {string} worldName - The directory name of the world to back up.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async createWorldBackup(
+    instanceId: string,
+    worldName: string
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("create_world_backup", { instanceId, worldName });
+  }
+
+  /**
+   * RETRIEVE the world backups of an instance.
+   * @param {string} instanceId - The instance ID to retrieve the backups for.
+   * @param {string} [worldName] - Only list the backups of this world if given.
+   * @returns {Promise<InvokeResponse<WorldBackupInfo[]>>}
+   */
+  @responseHandler("instance")
+  static async retrieveWorldBackupList(
+    instanceId: string,
+    worldName?: string
+  ): Promise<InvokeResponse<WorldBackupInfo[]>> {
+    return await invoke("retrieve_world_backup_list", {
+      instanceId,
+      worldName,
+    });
+  }
+
+  /**
+   * RESTORE a world backup, to the original world or as a new world.
+   * @param {string} instanceId - The instance ID of the backup.
+   * @param {string} backupPath - The path of the backup file.
+   * @param {string} [newWorldName] - Restore as a new world with this name if given.
+   * @returns {Promise<InvokeResponse<string>>} The directory of the restored world.
+   */
+  @responseHandler("instance")
+  static async restoreWorldBackup(
+    instanceId: string,
+    backupPath: string,
+    newWorldName?: string
+  ): Promise<InvokeResponse<string>> {
+    return await invoke("restore_world_backup", {
+      instanceId,
+      backupPath,
+      newWorldName,
+    });
+  }
+
+  /**
+   * DELETE a world backup.
+   * @param {string} instanceId - The instance ID of the backup.
+   * @param {string} backupPath - The path of the backup file.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async deleteWorldBackup(
+    instanceId: string,
+    backupPath: string
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("delete_world_backup", { instanceId, backupPath });
+  }
+
+  /**
+   * RETRIEVE the data packs of a world, including the disabled ones.
+   * @param {string} instanceId - The instance ID of the world.
+   * @param {string} worldName - The directory name of the world.
+   * @returns {Promise<InvokeResponse<DataPackInfo[]>>}
+   */
+  @responseHandler("instance")
+  static async retrieveWorldDatapackList(
+    instanceId: string,
+    worldName: string
+  ): Promise<InvokeResponse<DataPackInfo[]>> {
+    return await invoke("retrieve_world_datapack_list", {
+      instanceId,
+      worldName,
+    });
+  }
+
+  /**
+   * ADD a local data pack (zip or folder) to a world.
+   * @param {string} instanceId - The instance ID of the world.
+   * @param {string} worldName - The directory name of the world.
+   * @param {string} srcPath - The path of the data pack to be added.
+   * @returns {Promise<InvokeResponse<string>>} The path of the added data pack.
+   */
+  @responseHandler("instance")
+  static async addWorldDatapack(
+    instanceId: string,
+    worldName: string,
+    srcPath: string
+  ): Promise<InvokeResponse<string>> {
+    return await invoke("add_world_datapack", {
+      instanceId,
+      worldName,
+      srcPath,
+    });
+  }
+
+  /**
+   * DOWNLOAD a data pack into a world, scheduled as a background task.
+   * @param {string} instanceId - The instance ID of the world.
+   * @param {string} worldName - The directory name of the world.
+   * @param {OtherResourceFileInfo} fileInfo - The data pack file to be downloaded.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async downloadWorldDatapack(
+    instanceId: string,
+    worldName: string,
+    fileInfo: OtherResourceFileInfo
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("download_world_datapack", {
+      instanceId,
+      worldName,
+      fileInfo,
+    });
+  }
+
+  /**
+   * TOGGLE a data pack of a world, the world must not be opened in game.
+   * @param {string} instanceId - The instance ID of the world.
+   * @param {string} worldName - The directory name of the world.
+   * @param {string} fileName - The file name of the data pack.
+   * @param {boolean} enabled - Whether to enable the data pack.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("instance")
+  static async toggleWorldDatapack(
+    instanceId: string,
+    worldName: string,
+    fileName: string,
+    enabled: boolean
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("toggle_world_datapack", {
+      instanceId,
+      worldName,
+      fileName,
+      enabled,
+    });
+  }
+
   /**
    * CREATE a desktop shortcut for launching a specific instance.
    * @param {string} instanceId - The instance ID for which to create the shortcut.
@@ -1,6 +1,12 @@
 import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWebview } from "@tauri-apps/api/webview";
-import { LaunchingState } from "@/models/launch";
+import {
+  GcEvent,
+  LaunchingState,
+  PlaySession,
+  PlayStatistics,
+  ProcessResourceSample,
+} from "@/models/launch";
 import { InvokeResponse } from "@/models/response";
 import { responseHandler } from "@/utils/response";
 
@@ -12,58 +18,70 @@ export class LaunchService {
    * Launching Step 1: select suitable Java runtime environment for the specified instance.
    * At this step, pass the ID of the instance to be launched (which may not be the same as the selected instance ID), and no further input is required afterwards.
    * @param {string} instanceId - The ID of the instance.
-   * @returns {Promise<InvokeResponse<void>>}
+   * @returns {Promise<InvokeResponse<number>>} The launching id, passed to all the following steps.
    */
   @responseHandler("launch")
   static async selectSuitableJRE(
     instanceId: string
-  ): Promise<InvokeResponse<void>> {
+  ): Promise<InvokeResponse<number>> {
     return await invoke("select_suitable_jre", { instanceId });
   }
 
   /**
    * Launching Step 2: extract native libraries, validate the specified instance's game files.
+   * @param {number} launchingId - The id returned by step 1.
    * @returns {Promise<InvokeResponse<void>>}
    */
   @responseHandler("launch")
-  static async validateGameFiles(): Promise<InvokeResponse<void>> {
-    return await invoke("validate_game_files");
+  static async validateGameFiles(
+    launchingId: number
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("validate_game_files", { launchingId });
   }
 
   /**
    * Launching Step 3: validate the selected player, prepare prefetched server meta for authlib-injector.
    * The selected player ID is retrieved by the backend itself from the config state.
+   * @param {number} launchingId - The id returned by step 1.
    * @returns {Promise<InvokeResponse<boolean>>} false if the access token is expired.
    */
   @responseHandler("launch")
-  static async validateSelectedPlayer(): Promise<InvokeResponse<boolean>> {
-    return await invoke("validate_selected_player");
+  static async validateSelectedPlayer(
+    launchingId: number
+  ): Promise<InvokeResponse<boolean>> {
+    return await invoke("validate_selected_player", { launchingId });
   }
 
   /**
    * Launching Step 4: generate command args, launch the game instance.
+   * @param {number} launchingId - The id returned by step 1.
    * @param {string} [quickPlaySingleplayer] - Optional name of the singleplayer world to auto-enter.
    * @param {string} [quickPlayMultiplayer] - Optional address of multiplayer server to auto-join.
    * @returns {Promise<InvokeResponse<void>>}
    */
   @responseHandler("launch")
   static async launchGame(
+    launchingId: number,
     quickPlaySingleplayer?: string,
     quickPlayMultiplayer?: string
   ): Promise<InvokeResponse<void>> {
     return await invoke("launch_game", {
+      launchingId,
       quickPlaySingleplayer,
       quickPlayMultiplayer,
     });
   }
 
   /**
-   * CANCEL the launching process.
+   * CANCEL the launching process, or kill the game process if it is already running.
+   * @param {number} launchingId - The id of the launching state.
    * @returns {Promise<InvokeResponse<void>>}
    */
   @responseHandler("launch")
-  static async cancelLaunchProcess(): Promise<InvokeResponse<void>> {
-    return await invoke("cancel_launch_process");
+  static async cancelLaunchProcess(
+    launchingId: number
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("cancel_launch_process", { launchingId });
   }
 
   /**
@@ -104,6 +122,17 @@ export class LaunchService {
     return await invoke("retrieve_game_launching_state", { launchingId });
   }
 
+  /**
+   * RETRIEVE the launching states of all the running game processes.
+   * @returns {Promise<InvokeResponse<LaunchingState[]>>} Sorted by launching time.
+   */
+  @responseHandler("launch")
+  static async retrieveRunningGames(): Promise<
+    InvokeResponse<LaunchingState[]>
+  > {
+    return await invoke("retrieve_running_games");
+  }
+
   /**
    * EXPORT the game crash info to a zip file and reveal it in the file explorer.
    * This command is usually called by the game error window when game process crashed.
@@ -119,6 +148,72 @@ export class LaunchService {
     return await invoke("export_game_crash_info", { launchingId, savePath });
   }
 
+  /**
+   * RETRIEVE the play sessions, latest first.
+   * @param {string} [instanceId] - Only the sessions of this instance if given.
+   * @param {number} [startTime] - The UNIX timestamp (in seconds) of the range start.
+   * @param {number} [endTime] - The UNIX timestamp (in seconds) of the range end.
+   * @returns {Promise<InvokeResponse<PlaySession[]>>}
+   */
+  @responseHandler("launch")
+  static async retrievePlaySessions(
+    instanceId?: string,
+    startTime?: number,
+    endTime?: number
+  ): Promise<InvokeResponse<PlaySession[]>> {
+    return await invoke("retrieve_play_sessions", {
+      instanceId,
+      startTime,
+      endTime,
+    });
+  }
+
+  /**
+   * RETRIEVE the daily, weekly and per-instance play statistics.
+   * @param {string} [instanceId] - Only the sessions of this instance if given.
+   * @param {number} [startTime] - The UNIX timestamp (in seconds) of the range start.
+   * @param {number} [endTime] - The UNIX timestamp (in seconds) of the range end.
+   * @returns {Promise<InvokeResponse<PlayStatistics>>}
+   */
+  @responseHandler("launch")
+  static async retrievePlayStatistics(
+    instanceId?: string,
+    startTime?: number,
+    endTime?: number
+  ): Promise<InvokeResponse<PlayStatistics>> {
+    return await invoke("retrieve_play_statistics", {
+      instanceId,
+      startTime,
+      endTime,
+    });
+  }
+
+  /**
+   * EXPORT the play sessions to a CSV or JSON file.
+   * @param {"csv" | "json"} format - The format of the exported file.
+   * @param {string} savePath - The path to save the exported file.
+   * @param {string} [instanceId] - Only the sessions of this instance if given.
+   * @param {number} [startTime] - The UNIX timestamp (in seconds) of the range start.
+   * @param {number} [endTime] - The UNIX timestamp (in seconds) of the range end.
+   * @returns {Promise<InvokeResponse<void>>}
+   */
+  @responseHandler("launch")
+  static async exportPlayHistory(
+    format: "csv" | "json",
+    savePath: string,
+    instanceId?: string,
+    startTime?: number,
+    endTime?: number
+  ): Promise<InvokeResponse<void>> {
+    return await invoke("export_play_history", {
+      instanceId,
+      startTime,
+      endTime,
+      format,
+      savePath,
+    });
+  }
+
   /**
    * LISTEN to the game log output line by line.
    * @param callback The callback function to be called when the game log is output.
@@ -135,4 +230,40 @@ export class LaunchService {
       unlisten.then((f) => f());
     };
   }
+
+  /**
+   * LISTEN to the resource usage samples of the game process, emitted every 2 seconds.
+   * @param callback The callback function to be called with each sample.
+   */
+  static onGameProcessResource(
+    callback: (payload: ProcessResourceSample) => void
+  ) {
+    const unlisten = getCurrentWebview().listen<ProcessResourceSample>(
+      "launch:game-process-resource",
+      (event) => {
+        callback(event.payload);
+      }
+    );
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }
+
+  /**
+   * LISTEN to the heap collections parsed from the gc log of the game process.
+   * @param callback The callback function to be called with each gc event.
+   */
+  static onGameProcessGc(callback: (payload: GcEvent) => void) {
+    const unlisten = getCurrentWebview().listen<GcEvent>(
+      "launch:game-process-gc",
+      (event) => {
+        callback(event.payload);
+      }
+    );
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }
 }
@@ -85,6 +85,33 @@ export class ResourceService {
     });
   }
 
+  /**
+   * FETCH the list of resources from all the download sources, merging the same projects.
+   * @returns {Promise<InvokeResponse<OtherResourceSearchRes>>}
+   */
+  @responseHandler("resource")
+  static async fetchMergedResourceListByName(
+    resourceType: string,
+    searchQuery: string,
+    gameVersion: string,
+    selectedTag: string,
+    sortBy: string,
+    page: number,
+    pageSize: number
+  ): Promise<InvokeResponse<OtherResourceSearchRes>> {
+    return await invoke("fetch_merged_resource_list_by_name", {
+      query: {
+        resourceType,
+        searchQuery,
+        gameVersion,
+        selectedTag,
+        sortBy,
+        page,
+        pageSize,
+      },
+    });
+  }
+
   /**
    * FETCH the version packs for a specific resource.
    * @returns {Promise<InvokeResponse<OtherResourceVersionPack[]>>}